@@ -0,0 +1,112 @@
+#ifndef VALIDATE_COMMIT_H
+#define VALIDATE_COMMIT_H
+
+/* Generated by cbindgen from src/ffi.rs, do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/*
+ Errors of a validated message, freed by `vc_result_free`
+ */
+typedef struct VcResult VcResult;
+
+/*
+ Options of the validation
+ */
+typedef struct VcOptions {
+  /*
+   Maximum length of the header, 0 to disable the check
+   */
+  size_t max_header_length;
+  /*
+   Maximum length of the body lines, 0 to disable the check
+   */
+  size_t max_body_line_length;
+  /*
+   Require a scope in the header
+   */
+  bool require_scope;
+  /*
+   Report the warnings as errors
+   */
+  bool strict;
+} VcOptions;
+
+/*
+ Default options, following the Angular convention.
+ */
+VcOptions vc_options_default(void);
+
+/*
+ Validate the message with the options, or the default options if `options` is `NULL`.
+
+ Return `NULL` if the message is `NULL` or is not valid UTF-8.
+
+ # Safety
+
+ `message` must be `NULL` or a NUL-terminated string, and `options` must be `NULL` or point to
+ valid options.
+ */
+VcResult *vc_validate_message(const char *message, const VcOptions *options);
+
+/*
+ Number of errors of the result, 0 if the message is valid.
+
+ # Safety
+
+ `result` must be `NULL` or a result of `vc_validate_message` which was not freed.
+ */
+size_t vc_result_error_count(const VcResult *result);
+
+/*
+ Name of the kind of the error at the index, like `"InvalidCommitType"`, or `NULL` if there is
+ no such error.
+
+ # Safety
+
+ `result` must be `NULL` or a result of `vc_validate_message` which was not freed.
+ */
+const char *vc_result_error_kind(const VcResult *result, size_t index);
+
+/*
+ Message of the error at the index, like `"Missing scope"`, or `NULL` if there is no such
+ error.
+
+ # Safety
+
+ `result` must be `NULL` or a result of `vc_validate_message` which was not freed.
+ */
+const char *vc_result_error_message(const VcResult *result, size_t index);
+
+/*
+ Line number of the error at the index, starting at 1, or 0 if it is unknown.
+
+ # Safety
+
+ `result` must be `NULL` or a result of `vc_validate_message` which was not freed.
+ */
+size_t vc_result_error_line(const VcResult *result, size_t index);
+
+/*
+ Column of the error at the index, starting at 1, or 0 if it is unknown.
+
+ # Safety
+
+ `result` must be `NULL` or a result of `vc_validate_message` which was not freed.
+ */
+size_t vc_result_error_column(const VcResult *result, size_t index);
+
+/*
+ Free the result, doing nothing if it is `NULL`.
+
+ # Safety
+
+ `result` must be `NULL` or a result of `vc_validate_message` which was not freed.
+ */
+void vc_result_free(VcResult *result);
+
+#endif /* VALIDATE_COMMIT_H */
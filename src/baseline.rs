@@ -0,0 +1,194 @@
+//! Known failures of existing commits, to validate the new commits of an old repository.
+//!
+//! A baseline file lists the invalid commits with the kinds of their errors, one commit per line,
+//! sorted so that it diffs cleanly:
+//!
+//! ```text
+//! # Known failures of validate-commit
+//! 3f786850e387550fdab836ed7e6dc881de23001b CapitalizedFirstLetter
+//! 89e6c98d92887913cadf06b2adb97f26cde4849b NoColumn
+//! ```
+//!
+//! The errors of these kinds are removed from the reports of these commits, so that the new
+//! commits, and the new kinds of errors of the listed commits, are still reported.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use errors::BaselineError;
+use ValidationReport;
+
+/// Header of the baseline files
+const HEADER: &str = "# Known failures of validate-commit";
+
+/// Known failures of commits, by commit id
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Baseline {
+    failures: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl Baseline {
+    /// Create an empty baseline.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the baseline file.
+    ///
+    /// The blank lines and the lines starting with `#` are ignored.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, BaselineError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .map_err(|e| BaselineError::Io(path.to_owned(), e.to_string()))?;
+        Self::parse(&text)
+            .map_err(|(line, message)| BaselineError::Malformed(path.to_owned(), line, message))
+    }
+
+    /// Write the baseline file, replacing it.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), BaselineError> {
+        let path = path.as_ref();
+        fs::write(path, self.to_string())
+            .map_err(|e| BaselineError::Io(path.to_owned(), e.to_string()))
+    }
+
+    /// Record the errors of the commit as known failures.
+    ///
+    /// Nothing is recorded for a valid commit.
+    pub fn record(&mut self, commit: &str, report: &ValidationReport) {
+        if report.is_ok() {
+            return;
+        }
+        self.failures
+            .entry(commit.to_owned())
+            .or_default()
+            .extend(report.errors.iter().map(|e| e.kind.name().to_owned()));
+    }
+
+    /// Remove the known failures of the commit from its report.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use validate_commit::{validate_commit_message_report, Baseline};
+    /// let commit = "3f786850e387550fdab836ed7e6dc881de23001b";
+    /// let mut baseline = Baseline::new();
+    /// baseline.record(commit, &validate_commit_message_report("feat: Add colors"));
+    ///
+    /// let report = validate_commit_message_report("feat: Add colors.");
+    /// let report = baseline.apply(commit, report);
+    /// assert_eq!(report.errors.len(), 1);
+    /// assert_eq!(report.errors[0].kind.name(), "TrailingPunctuation");
+    /// ```
+    pub fn apply(&self, commit: &str, mut report: ValidationReport) -> ValidationReport {
+        if let Some(kinds) = self.failures.get(commit) {
+            report.errors.retain(|e| !kinds.contains(e.kind.name()));
+        }
+        report
+    }
+
+    /// Number of commits with known failures.
+    pub fn len(&self) -> usize {
+        self.failures.len()
+    }
+
+    /// Whether there are no known failures.
+    pub fn is_empty(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Parse the content of a baseline file, returning the number and the error of the first
+    /// malformed line.
+    fn parse(text: &str) -> Result<Self, (usize, String)> {
+        let mut baseline = Baseline::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut words = line.split_whitespace();
+            let commit = words.next().unwrap();
+            if !commit.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err((i + 1, format!("Invalid commit id '{}'", commit)));
+            }
+            let kinds: BTreeSet<_> = words.map(str::to_owned).collect();
+            if kinds.is_empty() {
+                return Err((i + 1, format!("No error kinds for commit {}", commit)));
+            }
+            baseline
+                .failures
+                .entry(commit.to_owned())
+                .or_default()
+                .extend(kinds);
+        }
+        Ok(baseline)
+    }
+}
+
+impl fmt::Display for Baseline {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", HEADER)?;
+        for (commit, kinds) in &self.failures {
+            write!(f, "{}", commit)?;
+            for kind in kinds {
+                write!(f, " {}", kind)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use validate_commit_message_report;
+
+    const OLD: &str = "89e6c98d92887913cadf06b2adb97f26cde4849b";
+    const OLDER: &str = "3f786850e387550fdab836ed7e6dc881de23001b";
+
+    #[test]
+    fn write_sorted_baselines() {
+        let mut baseline = Baseline::new();
+        baseline.record(OLD, &validate_commit_message_report("feat: Add colors."));
+        baseline.record(OLDER, &validate_commit_message_report("feat: Add colors"));
+        baseline.record(OLDER, &validate_commit_message_report("feat: add colors"));
+        assert_eq!(baseline.len(), 2);
+        assert_eq!(
+            baseline.to_string(),
+            "# Known failures of validate-commit\n\
+             3f786850e387550fdab836ed7e6dc881de23001b CapitalizedFirstLetter\n\
+             89e6c98d92887913cadf06b2adb97f26cde4849b CapitalizedFirstLetter \
+             TrailingPunctuation\n"
+        );
+        assert_eq!(Baseline::parse(&baseline.to_string()), Ok(baseline));
+    }
+
+    #[test]
+    fn suppress_known_failures_only() {
+        let mut baseline = Baseline::new();
+        baseline.record(OLD, &validate_commit_message_report("feat: Add colors"));
+
+        let report = baseline.apply(OLD, validate_commit_message_report("feat: Add colors"));
+        assert!(report.is_ok());
+        let report = baseline.apply(OLD, validate_commit_message_report("feat: Add colors."));
+        assert_eq!(report.errors.len(), 1);
+        let report = baseline.apply(OLDER, validate_commit_message_report("feat: Add colors"));
+        assert!(!report.is_ok());
+    }
+
+    #[test]
+    fn reject_malformed_lines() {
+        assert_eq!(Baseline::parse("\n# Comment\n"), Ok(Baseline::new()));
+        assert_eq!(
+            Baseline::parse("# Baseline\nHEAD NoColumn\n"),
+            Err((2, "Invalid commit id 'HEAD'".to_owned()))
+        );
+        assert_eq!(
+            Baseline::parse(&format!("{}\n", OLD)),
+            Err((1, format!("No error kinds for commit {}", OLD)))
+        );
+    }
+}
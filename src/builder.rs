@@ -0,0 +1,236 @@
+//! Construction of commit messages which pass the validation.
+
+use errors::FormatError;
+use options::ValidationOptions;
+use owned::{CommitMsgBuf, FooterBuf};
+use {
+    parse_commit_message_with, validate_commit_message_all_with, CommitHeader, CommitMsg,
+    CommitType,
+};
+
+/// Built commit message, with its parsed form
+pub type BuiltCommitMsg = (String, CommitMsgBuf);
+
+/// Builder of commit messages, validated with the same rules as [`validate_commit_message`]
+///
+/// [`validate_commit_message`]: ::validate_commit_message
+///
+/// # Examples
+///
+/// ```
+/// # use validate_commit::{CommitMsgBuilder, CommitType};
+/// let (message, commit) = CommitMsgBuilder::new()
+///     .commit_type(CommitType::Feat)
+///     .scope("lib")
+///     .subject("add a builder")
+///     .body("The messages are validated when they are built.")
+///     .footer("Refs", "#72")
+///     .build()
+///     .unwrap();
+/// assert_eq!(
+///     message,
+///     "feat(lib): add a builder\n\nThe messages are validated when they are built.\n\nRefs: #72"
+/// );
+/// assert_eq!(commit.header.subject, "add a builder");
+///
+/// assert!(CommitMsgBuilder::new().subject("Add a builder").build().is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CommitMsgBuilder {
+    commit_type: Option<CommitType>,
+    scope: Option<String>,
+    breaking: bool,
+    subject: String,
+    body: Option<String>,
+    footers: Vec<FooterBuf>,
+}
+
+impl CommitMsgBuilder {
+    /// Create a builder of an empty message.
+    pub fn new() -> Self {
+        CommitMsgBuilder::default()
+    }
+
+    /// Set the type of the commit.
+    pub fn commit_type(mut self, commit_type: CommitType) -> Self {
+        self.commit_type = Some(commit_type);
+        self
+    }
+
+    /// Set the scope of the commit, which may contain several comma-separated scopes.
+    pub fn scope<S: Into<String>>(mut self, scope: S) -> Self {
+        self.scope = Some(scope.into());
+        self
+    }
+
+    /// Set whether the header contains the breaking change marker `!`.
+    pub fn breaking(mut self, breaking: bool) -> Self {
+        self.breaking = breaking;
+        self
+    }
+
+    /// Set the subject of the commit.
+    pub fn subject<S: Into<String>>(mut self, subject: S) -> Self {
+        self.subject = subject.into();
+        self
+    }
+
+    /// Set the body of the commit, which may contain several paragraphs.
+    pub fn body<S: Into<String>>(mut self, body: S) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// Add a footer, written as `token: value`.
+    pub fn footer<T: Into<String>, V: Into<String>>(mut self, token: T, value: V) -> Self {
+        self.footers.push(FooterBuf {
+            token: token.into(),
+            value: value.into(),
+        });
+        self
+    }
+
+    /// Build the message, and validate it with the default options.
+    ///
+    /// Return the message with its parsed form, or all the errors of the message.
+    pub fn build(&self) -> Result<BuiltCommitMsg, Vec<FormatError>> {
+        self.build_with(&ValidationOptions::default())
+    }
+
+    /// Build the message, and validate it with the given options.
+    ///
+    /// See [`CommitMsgBuilder::build`].
+    pub fn build_with(
+        &self,
+        options: &ValidationOptions,
+    ) -> Result<BuiltCommitMsg, Vec<FormatError>> {
+        let message = self.message();
+        validate_commit_message_all_with(&message, options)?;
+        // The validation may skip the messages matching the ignore patterns of the options
+        let commit = parse_commit_message_with(&message, options)
+            .map_err(|e| vec![e])?
+            .to_owned();
+        Ok((message, commit))
+    }
+
+    /// Write the message in its canonical form, see [`CommitMsg`](::CommitMsg).
+    fn message(&self) -> String {
+        let commit_type = self
+            .commit_type
+            .clone()
+            .unwrap_or_else(|| CommitType::Custom(String::new()));
+        let commit = CommitMsg {
+            header: CommitHeader {
+                commit_type,
+                scope: self.scope.as_deref(),
+                scopes: Vec::new(),
+                breaking: self.breaking,
+                subject: &self.subject,
+                ticket: None,
+                emoji: None,
+            },
+            body: self.body.as_deref(),
+            footers: self.footers.iter().map(FooterBuf::as_footer).collect(),
+        };
+        commit.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use errors::FormatErrorKind;
+
+    fn error_kinds(
+        builder: &CommitMsgBuilder,
+        options: &ValidationOptions,
+    ) -> Vec<FormatErrorKind> {
+        let errors = builder.build_with(options).unwrap_err();
+        errors.into_iter().map(|e| e.kind).collect()
+    }
+
+    #[test]
+    fn build_valid_messages() {
+        let (message, commit) = CommitMsgBuilder::new()
+            .commit_type(CommitType::Fix)
+            .scope("parser,cli")
+            .breaking(true)
+            .subject("handle builders")
+            .footer("BREAKING CHANGE", "the messages are built")
+            .footer("Signed-off-by", "A <a@example.com>")
+            .build()
+            .unwrap();
+        assert_eq!(
+            message,
+            "fix(parser,cli)!: handle builders\n\n\
+             BREAKING CHANGE: the messages are built\n\
+             Signed-off-by: A <a@example.com>"
+        );
+        assert_eq!(commit.header.scopes, vec!["parser", "cli"]);
+        assert!(commit.is_breaking());
+        assert_eq!(commit.body, None);
+        assert_eq!(commit.footers.len(), 2);
+        assert_eq!(commit.to_string(), message);
+    }
+
+    #[test]
+    fn reject_invalid_messages() {
+        let options = ValidationOptions::default();
+        let builder = CommitMsgBuilder::new().commit_type(CommitType::Feat);
+        assert_eq!(
+            error_kinds(&builder, &options),
+            vec![FormatErrorKind::EmptyCommitSubject]
+        );
+        assert_eq!(
+            error_kinds(&builder.clone().subject("Add builders."), &options),
+            vec![
+                FormatErrorKind::CapitalizedFirstLetter,
+                FormatErrorKind::TrailingPunctuation('.')
+            ]
+        );
+        assert_eq!(
+            error_kinds(&CommitMsgBuilder::new().subject("add builders"), &options),
+            vec![FormatErrorKind::EmptyCommitType]
+        );
+    }
+
+    #[test]
+    fn reject_long_subjects() {
+        let options = ValidationOptions {
+            max_header_length: 20,
+            ..ValidationOptions::default()
+        };
+        let builder = CommitMsgBuilder::new()
+            .commit_type(CommitType::Feat)
+            .subject("add message builders");
+        assert_eq!(
+            error_kinds(&builder, &options),
+            vec![FormatErrorKind::LineTooLong(20)]
+        );
+        assert!(builder.build().is_ok());
+    }
+
+    #[test]
+    fn build_with_options() {
+        let options = ValidationOptions {
+            allowed_types: Some(vec!["deps".to_owned()]),
+            require_signoff: true,
+            ..ValidationOptions::default()
+        };
+        let builder = CommitMsgBuilder::new()
+            .commit_type(CommitType::Custom("deps".to_owned()))
+            .subject("update serde");
+        assert_eq!(
+            error_kinds(&builder, &options),
+            vec![FormatErrorKind::MissingSignOff]
+        );
+        let (_, commit) = builder
+            .footer("Signed-off-by", "Bot <bot@example.com>")
+            .build_with(&options)
+            .unwrap();
+        assert_eq!(
+            commit.header.commit_type,
+            CommitType::Custom("deps".to_owned())
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! Recommendation of the next version from the commits of a release.
+
+use std::fmt;
+
+use {CommitMsg, CommitType};
+
+/// Part of the semantic version to increment
+///
+/// The bumps are ordered from `None` to `Major`, so that the recommended bump of several commits
+/// is the greatest one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Bump {
+    /// The commits do not change the behavior, like documentation or refactoring
+    None,
+    /// The commits fix bugs or improve the performance
+    Patch,
+    /// The commits add features
+    Minor,
+    /// The commits break the compatibility
+    Major,
+}
+
+impl Bump {
+    /// Bump needed to release the commit.
+    pub fn of(commit: &CommitMsg) -> Bump {
+        if commit.is_breaking() {
+            return Bump::Major;
+        }
+
+        match commit.header.commit_type {
+            CommitType::Feat => Bump::Minor,
+            CommitType::Fix | CommitType::Perf => Bump::Patch,
+            _ => Bump::None,
+        }
+    }
+
+    /// Name of the bump, like `minor`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Bump::None => "none",
+            Bump::Patch => "patch",
+            Bump::Minor => "minor",
+            Bump::Major => "major",
+        }
+    }
+}
+
+impl fmt::Display for Bump {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Recommend the bump of the version releasing the commits.
+///
+/// Any breaking change needs a major version, any feature a minor one, and any fix or performance
+/// improvement a patch one.
+///
+/// # Examples
+///
+/// ```
+/// # use validate_commit::{parse_commit_message, recommend_bump, Bump};
+/// let commits = vec![
+///     parse_commit_message("fix: handle empty input").unwrap(),
+///     parse_commit_message("feat: add colors").unwrap(),
+///     parse_commit_message("docs: describe the colors").unwrap(),
+/// ];
+/// assert_eq!(recommend_bump(&commits), Bump::Minor);
+/// ```
+pub fn recommend_bump<'a, 'b: 'a, I>(commits: I) -> Bump
+where
+    I: IntoIterator<Item = &'a CommitMsg<'b>>,
+{
+    commits
+        .into_iter()
+        .map(Bump::of)
+        .max()
+        .unwrap_or(Bump::None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parse_commit_message;
+
+    fn bump(messages: &[&str]) -> Bump {
+        let commits: Vec<_> = messages
+            .iter()
+            .map(|message| parse_commit_message(message).unwrap())
+            .collect();
+        recommend_bump(&commits)
+    }
+
+    #[test]
+    fn bump_of_single_commits() {
+        assert_eq!(bump(&["feat!: drop the old API"]), Bump::Major);
+        assert_eq!(
+            bump(&["docs: describe the API\n\nBREAKING CHANGE: the API is renamed"]),
+            Bump::Major
+        );
+        assert_eq!(bump(&["feat(api): add colors"]), Bump::Minor);
+        assert_eq!(bump(&["fix: handle empty input"]), Bump::Patch);
+        assert_eq!(bump(&["perf: cache the parser"]), Bump::Patch);
+        assert_eq!(bump(&["refactor: split the parser"]), Bump::None);
+        assert_eq!(bump(&[]), Bump::None);
+    }
+
+    #[test]
+    fn greatest_bump_wins() {
+        let fix = "fix: handle empty input";
+        let feat = "feat: add colors";
+        let breaking = "refactor!: rename the API";
+        let docs = "docs: describe the API";
+        assert_eq!(bump(&[docs, fix]), Bump::Patch);
+        assert_eq!(bump(&[fix, feat, docs]), Bump::Minor);
+        assert_eq!(bump(&[feat, fix]), Bump::Minor);
+        assert_eq!(bump(&[fix, breaking, feat]), Bump::Major);
+        assert_eq!(bump(&[breaking, docs]), Bump::Major);
+    }
+
+    #[test]
+    fn display_bumps() {
+        assert_eq!(Bump::Major.to_string(), "major");
+        assert_eq!(Bump::None.to_string(), "none");
+    }
+}
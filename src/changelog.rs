@@ -0,0 +1,210 @@
+//! Grouping of the commits of a release into the sections of a changelog.
+
+use std::collections::BTreeMap;
+
+use {CommitMsg, CommitType};
+
+/// Commits grouped by type, with the breaking changes in their own bucket
+///
+/// A breaking change is listed both in `breaking` and in the section of its type.
+#[derive(Debug, PartialEq)]
+pub struct CommitGroups<T> {
+    /// Commits introducing a breaking change, whatever their type
+    pub breaking: Vec<T>,
+    /// Commits by type, ordered like the variants of [`CommitType`], then the custom types
+    /// alphabetically
+    pub types: BTreeMap<CommitType, Vec<T>>,
+}
+
+impl<T> CommitGroups<T> {
+    /// Group the items, in their order, by the type of their commit message.
+    pub fn new<I, F>(items: I, msg: F) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Clone,
+        F: Fn(&T) -> &CommitMsg,
+    {
+        let mut groups = CommitGroups {
+            breaking: Vec::new(),
+            types: BTreeMap::new(),
+        };
+        for item in items {
+            let (breaking, commit_type) = {
+                let msg = msg(&item);
+                (msg.is_breaking(), msg.header.commit_type.clone())
+            };
+            if breaking {
+                groups.breaking.push(item.clone());
+            }
+            groups.types.entry(commit_type).or_default().push(item);
+        }
+        groups
+    }
+
+    /// Whether there are no commits.
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+}
+
+/// Group the commits by type, for a changelog.
+///
+/// # Examples
+///
+/// ```
+/// # use validate_commit::{group_commits, parse_commit_message, CommitType};
+/// let commits = vec![
+///     parse_commit_message("feat: add colors").unwrap(),
+///     parse_commit_message("fix!: reject empty input").unwrap(),
+///     parse_commit_message("feat(cli): add --color").unwrap(),
+/// ];
+/// let groups = group_commits(&commits);
+/// assert_eq!(groups.breaking, vec![&commits[1]]);
+/// assert_eq!(groups.types[&CommitType::Feat], vec![&commits[0], &commits[2]]);
+/// ```
+pub fn group_commits<'m, 'a>(msgs: &'m [CommitMsg<'a>]) -> CommitGroups<&'m CommitMsg<'a>> {
+    CommitGroups::new(msgs, |&msg| msg)
+}
+
+/// Title of the section of the commit type in a changelog, like `Bug fixes` for `fix`
+///
+/// The custom types are titled with their name.
+pub fn section_title(commit_type: &CommitType) -> &str {
+    use CommitType::*;
+
+    match commit_type {
+        Feat => "Features",
+        Fix => "Bug fixes",
+        Docs => "Documentation",
+        Style => "Styles",
+        Refactor => "Refactoring",
+        Perf => "Performance",
+        Test => "Tests",
+        Chore => "Chores",
+        Build => "Build",
+        Ci => "Continuous integration",
+        Revert => "Reverts",
+        Custom(ref t) => t,
+    }
+}
+
+/// Render the changelog of the commits, each with its short id, as Markdown.
+///
+/// The breaking changes come first, then a section per type, each commit being listed as
+/// `- subject (scope) [id]`. Nothing is rendered without commits.
+///
+/// # Examples
+///
+/// ```
+/// # use validate_commit::{markdown_changelog, parse_commit_message, CommitGroups};
+/// let feat = parse_commit_message("feat(cli): add --color").unwrap();
+/// let groups = CommitGroups::new(vec![("1a2b3c4", &feat)], |&(_, msg)| msg);
+/// assert_eq!(
+///     markdown_changelog(&groups),
+///     "### Features\n\n- add --color (cli) [1a2b3c4]\n"
+/// );
+/// ```
+pub fn markdown_changelog(groups: &CommitGroups<(&str, &CommitMsg)>) -> String {
+    let breaking = ("Breaking changes", &groups.breaking);
+    let types = groups
+        .types
+        .iter()
+        .map(|(commit_type, commits)| (section_title(commit_type), commits));
+    let sections: Vec<String> = Some(breaking)
+        .into_iter()
+        .chain(types)
+        .filter(|(_, commits)| !commits.is_empty())
+        .map(|(title, commits)| {
+            let mut section = format!("### {}\n\n", title);
+            for (id, msg) in commits {
+                section += &markdown_entry(id, msg);
+            }
+            section
+        })
+        .collect();
+    sections.join("\n")
+}
+
+fn markdown_entry(id: &str, msg: &CommitMsg) -> String {
+    match msg.header.scope {
+        Some(scope) => format!("- {} ({}) [{}]\n", msg.header.subject, scope, id),
+        None => format!("- {} [{}]\n", msg.header.subject, id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parse_commit_message;
+
+    #[test]
+    fn group_by_type() {
+        let commits: Vec<_> = [
+            "fix: handle empty input",
+            "feat(api)!: drop the old API",
+            "docs: describe colors",
+            "feat: add colors",
+        ]
+        .iter()
+        .map(|message| parse_commit_message(message).unwrap())
+        .collect();
+        let groups = group_commits(&commits);
+        assert_eq!(groups.breaking, vec![&commits[1]]);
+        let types: Vec<_> = groups
+            .types
+            .iter()
+            .map(|(commit_type, commits)| (commit_type.as_str(), commits.len()))
+            .collect();
+        assert_eq!(types, vec![("feat", 2), ("fix", 1), ("docs", 1)]);
+        assert_eq!(
+            groups.types[&CommitType::Feat],
+            vec![&commits[1], &commits[3]]
+        );
+        assert!(group_commits(&[]).is_empty());
+    }
+
+    #[test]
+    fn render_markdown() {
+        let commits: Vec<_> = [
+            ("aaaaaaa", "fix(parser): handle empty input"),
+            ("bbbbbbb", "feat!: drop the old API"),
+            ("ccccccc", "feat(cli): add colors"),
+            ("ddddddd", "docs: describe colors"),
+        ]
+        .iter()
+        .map(|&(id, message)| (id, parse_commit_message(message).unwrap()))
+        .collect();
+        let groups = CommitGroups::new(commits.iter().map(|(id, msg)| (*id, msg)), |&(_, msg)| msg);
+        assert_eq!(
+            markdown_changelog(&groups),
+            "### Breaking changes\n\
+             \n\
+             - drop the old API [bbbbbbb]\n\
+             \n\
+             ### Features\n\
+             \n\
+             - drop the old API [bbbbbbb]\n\
+             - add colors (cli) [ccccccc]\n\
+             \n\
+             ### Bug fixes\n\
+             \n\
+             - handle empty input (parser) [aaaaaaa]\n\
+             \n\
+             ### Documentation\n\
+             \n\
+             - describe colors [ddddddd]\n"
+        );
+        assert_eq!(
+            markdown_changelog(&CommitGroups::new(vec![], |&(_, msg)| msg)),
+            ""
+        );
+    }
+
+    #[test]
+    fn title_custom_types() {
+        assert_eq!(
+            section_title(&CommitType::Custom("security".to_owned())),
+            "security"
+        );
+    }
+}
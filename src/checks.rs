@@ -0,0 +1,370 @@
+//! Built-in rules checking the parsed messages.
+//!
+//! The checks of the lines and of the header format are done while the message is parsed, as
+//! they apply to the messages which cannot be parsed too.
+
+use std::ops::Range;
+
+use errors::{FormatError, FormatErrorKind};
+use mood;
+use options::ValidationOptions;
+use parse::{line_at, message_lines, split_autosquash, Line};
+use validator::Rule;
+use {CommitMsg, Footer, CO_AUTHOR_TOKEN, SIGN_OFF_TOKEN};
+
+/// Rule of the subject: its case, its first word, its length and its last character
+pub struct SubjectRule<'o>(pub &'o ValidationOptions);
+
+/// Rule of the issue references, for the configured commit types
+pub struct IssueReferenceRule<'o>(pub &'o ValidationOptions);
+
+/// Rule of the body, for the configured commit types
+pub struct BodyRule<'o>(pub &'o ValidationOptions);
+
+/// Rule of the `Signed-off-by` footers, when they are required
+pub struct SignOffRule<'o>(pub &'o ValidationOptions);
+
+/// Rule of the `Co-authored-by` footers
+pub struct CoAuthorRule;
+
+/// Rule of the words forbidden in the subject and the body
+pub struct ForbiddenWordRule<'o>(pub &'o ValidationOptions);
+
+impl<'o> Rule for SubjectRule<'o> {
+    fn check(&self, msg: &CommitMsg, raw: &str) -> Vec<FormatError> {
+        let options = self.0;
+        let mut errors = Vec::new();
+        let (header, subject_pos) = header_line(msg, raw);
+        let subject = msg.header.subject;
+        let subject_end = subject_pos + subject.len();
+        let first_word_len = subject
+            .find(|c: char| !c.is_alphanumeric() && c != '-')
+            .unwrap_or(subject.len());
+        let first_word = &subject[..first_word_len];
+        let first_word_end = subject_pos + first_word_len;
+
+        // Check if the first letter is not capitalized, unless the first word is an acronym or
+        // an allowed proper noun
+        if subject.chars().next().unwrap().is_uppercase()
+            && !is_acronym(first_word)
+            && !options
+                .allowed_capitalized_words
+                .iter()
+                .any(|word| word == first_word)
+        {
+            errors.push(header.error(FormatErrorKind::CapitalizedFirstLetter, subject_pos));
+        }
+
+        // Check if the subject repeats the commit type, like `fix: fix crash`
+        let commit_type = msg.header.commit_type.as_str();
+        if !first_word.is_empty() && first_word.to_lowercase() == commit_type.to_lowercase() {
+            errors.push(header.error_spanning(
+                FormatErrorKind::SubjectEchoesType,
+                subject_pos,
+                first_word_end,
+            ));
+        } else if let Some(suggestion) = mood::check_imperative(
+            first_word,
+            &options.imperative_allowed_words,
+            &options.imperative_denied_words,
+        ) {
+            let kind = FormatErrorKind::NonImperativeSubject {
+                word: first_word.to_owned(),
+                suggestion,
+            };
+            errors.push(header.error_spanning(kind, subject_pos, first_word_end));
+        }
+
+        if let Some(limit) = options.soft_subject_limit {
+            if let Some((pos, _)) = subject.char_indices().nth(limit) {
+                errors.push(header.error_spanning(
+                    FormatErrorKind::SubjectTooLong(limit),
+                    subject_pos + pos,
+                    subject_end,
+                ));
+            }
+        }
+
+        let last_char = subject.chars().next_back().unwrap();
+        if options.subject_trailing_punctuation.contains(last_char) {
+            let pos = subject_end - last_char.len_utf8();
+            errors.push(header.error(FormatErrorKind::TrailingPunctuation(last_char), pos));
+        }
+        errors
+    }
+}
+
+impl<'o> Rule for IssueReferenceRule<'o> {
+    fn check(&self, msg: &CommitMsg, raw: &str) -> Vec<FormatError> {
+        let options = self.0;
+        if !options.require_reference.contains(&msg.header.commit_type)
+            || message_lines(raw, options.comment_char)
+                .skip_while(Line::is_blank)
+                .any(|line| has_reference(options, line.text))
+        {
+            return Vec::new();
+        }
+
+        #[cfg(feature = "regex")]
+        let pattern = options.reference_pattern.to_string();
+        #[cfg(not(feature = "regex"))]
+        let pattern = ValidationOptions::DEFAULT_REFERENCE_PATTERN.to_owned();
+        let kind = FormatErrorKind::MissingIssueReference { pattern };
+        vec![header_line(msg, raw).0.error(kind, 0)]
+    }
+}
+
+impl<'o> Rule for BodyRule<'o> {
+    fn check(&self, msg: &CommitMsg, raw: &str) -> Vec<FormatError> {
+        let commit_type = &msg.header.commit_type;
+        if !self.0.require_body.contains(commit_type)
+            || msg.body.is_some_and(|body| !body.trim().is_empty())
+        {
+            return Vec::new();
+        }
+
+        // The `fixup!` and `squash!` commits are squashed in a commit with a body
+        let header = header_line(msg, raw).0;
+        if !split_autosquash(header.text).0.is_empty() {
+            return Vec::new();
+        }
+
+        let kind = FormatErrorKind::MissingBody {
+            commit_type: commit_type.to_string(),
+        };
+        vec![header.error(kind, 0)]
+    }
+}
+
+impl<'o> Rule for SignOffRule<'o> {
+    /// Check that the message contains a `Signed-off-by` footer, and that each of them is
+    /// well-formed.
+    fn check(&self, msg: &CommitMsg, raw: &str) -> Vec<FormatError> {
+        if !self.0.require_signoff {
+            return Vec::new();
+        }
+
+        let mut signoffs = msg
+            .footers
+            .iter()
+            .filter(|footer| footer.token.eq_ignore_ascii_case(SIGN_OFF_TOKEN))
+            .peekable();
+        if signoffs.peek().is_none() {
+            return vec![FormatErrorKind::MissingSignOff.into()];
+        }
+
+        signoffs
+            .filter(|footer| !is_valid_identity(footer.value))
+            .map(|footer| footer_error(raw, footer, FormatErrorKind::MalformedSignOff))
+            .collect()
+    }
+}
+
+impl Rule for CoAuthorRule {
+    /// Check that the `Co-authored-by` footers are well-formed.
+    fn check(&self, msg: &CommitMsg, raw: &str) -> Vec<FormatError> {
+        msg.footers
+            .iter()
+            .filter(|footer| {
+                footer.token.eq_ignore_ascii_case(CO_AUTHOR_TOKEN)
+                    && !is_valid_identity(footer.value)
+            })
+            .map(|footer| footer_error(raw, footer, FormatErrorKind::MalformedCoAuthor))
+            .collect()
+    }
+}
+
+/// Search of a forbidden word or pattern, returning the range of its first occurrence
+type Finder<'o> = Box<dyn Fn(&str) -> Option<Range<usize>> + 'o>;
+
+impl<'o> Rule for ForbiddenWordRule<'o> {
+    /// Report the first occurrence of each forbidden word and pattern.
+    fn check(&self, msg: &CommitMsg, raw: &str) -> Vec<FormatError> {
+        let options = self.0;
+        let words = options
+            .forbidden_words
+            .iter()
+            .map(|word| Box::new(move |text: &str| find_word(text, word)) as Finder);
+        #[cfg(feature = "regex")]
+        let patterns = options.forbidden_patterns.iter().map(|pattern| {
+            Box::new(move |text: &str| pattern.regex().find(text).map(|found| found.range()))
+                as Finder
+        });
+        #[cfg(not(feature = "regex"))]
+        let patterns = std::iter::empty();
+        let finders: Vec<_> = words.chain(patterns).collect();
+        if finders.is_empty() {
+            return Vec::new();
+        }
+
+        // The lines are searched in order, from the subject to the end of the body
+        let (header, subject_pos) = header_line(msg, raw);
+        let mut lines = vec![(header, subject_pos, subject_pos + msg.header.subject.len())];
+        if let Some(body) = msg.body {
+            let body_start = body.as_ptr() as usize - raw.as_ptr() as usize;
+            let body_end = body_start + body.len();
+            lines.extend(
+                message_lines(raw, options.comment_char)
+                    .filter(|line| line.pos >= body_start && line.pos < body_end)
+                    .map(|line| (line, 0, line.text.len().min(body_end - line.pos))),
+            );
+        }
+
+        finders
+            .iter()
+            .filter_map(|find| {
+                lines.iter().find_map(|&(line, start, end)| {
+                    let found = find(&line.text[start..end])?;
+                    let (pos, end) = (start + found.start, start + found.end);
+                    let kind = FormatErrorKind::ForbiddenWord {
+                        word: line.text[pos..end].to_owned(),
+                    };
+                    Some(line.error_spanning(kind, pos, end))
+                })
+            })
+            .collect()
+    }
+}
+
+/// Whether the character is part of a word, for the word boundaries
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Find the first occurrence of the whole word in the text, ignoring the case.
+///
+/// The word boundaries are only required next to the letters and digits, so that a word like
+/// `[skip]` matches too.
+fn find_word(text: &str, word: &str) -> Option<Range<usize>> {
+    let same_char = |a: char, b: char| a == b || a.to_lowercase().eq(b.to_lowercase());
+    text.char_indices().find_map(|(pos, _)| {
+        let mut chars = text[pos..].char_indices();
+        let mut len = 0;
+        for expected in word.chars() {
+            let (offset, c) = chars.next().filter(|&(_, c)| same_char(c, expected))?;
+            len = offset + c.len_utf8();
+        }
+        let end = pos + len;
+        let bounded_start = !word.starts_with(is_word_char) || !text[..pos].ends_with(is_word_char);
+        let bounded_end = !word.ends_with(is_word_char) || !text[end..].starts_with(is_word_char);
+        if bounded_start && bounded_end {
+            Some(pos..end)
+        } else {
+            None
+        }
+    })
+}
+
+/// Whether the text references an issue with the reference pattern of the options
+#[cfg(feature = "regex")]
+fn has_reference(options: &ValidationOptions, text: &str) -> bool {
+    options.reference_pattern.is_match(text)
+}
+
+/// Whether the text references an issue like the
+/// [default pattern](ValidationOptions::DEFAULT_REFERENCE_PATTERN), like `#42` or `PROJ-42`
+#[cfg(not(feature = "regex"))]
+fn has_reference(_options: &ValidationOptions, text: &str) -> bool {
+    let digits = |s: &str| s.len() - s.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    text.char_indices().any(|(pos, c)| {
+        let rest = &text[pos + c.len_utf8()..];
+        if c == '#' {
+            return digits(rest) > 0;
+        }
+        if !c.is_ascii_uppercase() || text[..pos].ends_with(is_word_char) {
+            return false;
+        }
+        let key = rest.len()
+            - rest
+                .trim_start_matches(|c: char| c.is_ascii_uppercase() || c.is_ascii_digit())
+                .len();
+        match rest[key..].strip_prefix('-') {
+            Some(number) if key > 0 => {
+                let len = digits(number);
+                len > 0 && !number[len..].starts_with(is_word_char)
+            }
+            _ => false,
+        }
+    })
+}
+
+/// Return the line of the header, and the position of the subject in it.
+///
+/// The subject is not at the end of the header with a custom header pattern.
+fn header_line<'a>(msg: &CommitMsg, raw: &'a str) -> (Line<'a>, usize) {
+    let subject_offset = msg.header.subject.as_ptr() as usize - raw.as_ptr() as usize;
+    let line = line_at(raw, subject_offset);
+    (line, subject_offset - line.pos)
+}
+
+/// Report an error spanning the first line of the footer value.
+fn footer_error(raw: &str, footer: &Footer, kind: FormatErrorKind) -> FormatError {
+    let value_pos = footer.value.as_ptr() as usize - raw.as_ptr() as usize;
+    let line = line_at(raw, value_pos);
+    let pos = value_pos - line.pos;
+    let end = line.text.len().min(pos + footer.value.len());
+    line.error_spanning(kind, pos, end)
+}
+
+/// Check if the identity is like `Name <email>`, with a non-empty name and a plausible email.
+fn is_valid_identity(value: &str) -> bool {
+    let value = value.trim_end();
+    let (name, email) = match value
+        .strip_suffix('>')
+        .and_then(|value| value.rsplit_once('<'))
+    {
+        Some(parts) => parts,
+        None => return false,
+    };
+    let (local, domain) = match email.split_once('@') {
+        Some(parts) => parts,
+        None => return false,
+    };
+
+    let name = name.trim();
+    !name.is_empty()
+        && !name.contains(['<', '>'])
+        && !local.is_empty()
+        && !email.contains(|c: char| c.is_whitespace() || c == '<')
+        && !domain.contains('@')
+        && domain.contains('.')
+        && domain.split('.').all(|part| !part.is_empty())
+}
+
+/// Check if the word is an acronym, like `JSON`: at least two letters, all uppercase.
+fn is_acronym(word: &str) -> bool {
+    word.chars().filter(|c| c.is_alphabetic()).count() >= 2 && !word.chars().any(char::is_lowercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_word, has_reference};
+
+    use options::ValidationOptions;
+
+    #[test]
+    fn find_whole_words() {
+        assert_eq!(find_word("feat: add temp files", "temp"), Some(10..14));
+        assert_eq!(find_word("feat: add TEMP files", "temp"), Some(10..14));
+        assert_eq!(find_word("feat: add temporary files", "temp"), None);
+        assert_eq!(find_word("feat: add attempt", "temp"), None);
+        assert_eq!(find_word("fix: Do Not Merge", "do not merge"), Some(5..17));
+        assert_eq!(find_word("docs: fix typo [skip]", "[skip]"), Some(15..21));
+        assert_eq!(find_word("docs: fix typo[skip]", "[skip]"), Some(14..20));
+        assert_eq!(find_word("feat: add ÉTÉ mode", "été"), Some(10..15));
+    }
+
+    /// The default pattern is matched without the `regex` feature too
+    #[test]
+    fn find_default_references() {
+        let options = ValidationOptions::default();
+        for text in &["Closes: #42", "fix (#7)", "See PROJ-42.", "A1-2", "(AB-12)"] {
+            assert!(has_reference(&options, text), "{:?}", text);
+        }
+        for text in &[
+            "#", "issue 42", "PROJ-", "xPROJ-42", "PROJ-42a", "P-42", "proj-42",
+        ] {
+            assert!(!has_reference(&options, text), "{:?}", text);
+        }
+    }
+}
@@ -0,0 +1,361 @@
+//! Import of the configuration files of [commitlint](https://commitlint.js.org), to migrate from
+//! it.
+//!
+//! The `.commitlintrc.json` and `.commitlintrc.yml` files are read, and the rules with an
+//! equivalent in validate-commit are mapped onto the [`ValidationOptions`]:
+//!
+//! - `type-enum`, `type-case`, `type-empty`
+//! - `scope-enum`, `scope-case` (`lower-case` and `kebab-case`), `scope-empty`
+//! - `subject-case`, `subject-empty`, `subject-full-stop`
+//! - `header-max-length`, `body-max-line-length`, `body-leading-blank`
+//! - `signed-off-by`
+//!
+//! The level `0` of a rule disables it, `1` makes it a warning and `2` an error. The other rules,
+//! the rules applied in a way validate-commit does not support, and the `extends` presets are
+//! ignored with a warning.
+//!
+//! ```json
+//! {
+//!   "extends": ["@commitlint/config-conventional"],
+//!   "rules": {
+//!     "type-enum": [2, "always", ["feat", "fix", "docs"]],
+//!     "header-max-length": [2, "always", 72]
+//!   }
+//! }
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use errors::ConfigError;
+use options::{ScopePattern, ScopePolicy, ValidationOptions};
+use rules::{RuleId, Severity};
+
+/// Names of the commitlint configuration files, in the order they are searched
+pub const COMMITLINT_FILE_NAMES: [&str; 3] = [
+    ".commitlintrc.json",
+    ".commitlintrc.yml",
+    ".commitlintrc.yaml",
+];
+
+/// Options imported from a commitlint configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommitlintConfig {
+    /// Options following the supported rules
+    pub options: ValidationOptions,
+    /// Descriptions of the rules and fields which were ignored
+    pub warnings: Vec<String>,
+}
+
+/// Search the commitlint configuration file from the given directory, up to the repository root.
+pub fn find_commitlint_file<P: AsRef<Path>>(dir: P) -> Option<PathBuf> {
+    for dir in dir.as_ref().ancestors() {
+        if let Some(path) = COMMITLINT_FILE_NAMES
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file())
+        {
+            return Some(path);
+        }
+
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+
+    None
+}
+
+/// Import the commitlint configuration file found from the given directory.
+///
+/// Return the default options if there is no configuration file.
+pub fn load_commitlint_config<P: AsRef<Path>>(dir: P) -> Result<CommitlintConfig, ConfigError> {
+    match find_commitlint_file(dir) {
+        Some(path) => load_commitlint_file(path),
+        None => Ok(CommitlintConfig {
+            options: ValidationOptions::default(),
+            warnings: Vec::new(),
+        }),
+    }
+}
+
+/// Import the given commitlint configuration file, read as YAML unless its extension is `.json`.
+pub fn load_commitlint_file<P: AsRef<Path>>(path: P) -> Result<CommitlintConfig, ConfigError> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path).map_err(|e| ConfigError::new(path, e))?;
+    let config: Value = if path.extension().is_some_and(|ext| ext == "json") {
+        serde_json::from_str(&content).map_err(|e| ConfigError::new(path, e))?
+    } else {
+        serde_yaml::from_str(&content).map_err(|e| ConfigError::new(path, e))?
+    };
+    import(&config).map_err(|e| ConfigError::new(path, e))
+}
+
+/// A commitlint rule, like `[2, "always", 72]`
+struct RuleConfig<'v> {
+    /// `0` to disable the rule, `1` for a warning, `2` for an error
+    level: u64,
+    /// Whether the rule is applied with `always`, rather than with `never`
+    always: bool,
+    value: Option<&'v Value>,
+}
+
+impl<'v> RuleConfig<'v> {
+    fn parse(name: &str, rule: &'v Value) -> Result<Self, String> {
+        let malformed = || format!("Rule '{}' must be like [2, \"always\", value]", name);
+        let rule = rule.as_array().ok_or_else(malformed)?;
+        let level = rule
+            .first()
+            .and_then(Value::as_u64)
+            .filter(|&level| level <= 2)
+            .ok_or_else(malformed)?;
+        let always = match rule.get(1).map(Value::as_str) {
+            None | Some(Some("always")) => true,
+            Some(Some("never")) => false,
+            Some(_) => return Err(malformed()),
+        };
+        Ok(RuleConfig {
+            level,
+            always,
+            value: rule.get(2),
+        })
+    }
+
+    fn strings(&self) -> Option<Vec<String>> {
+        match self.value? {
+            Value::String(value) => Some(vec![value.clone()]),
+            Value::Array(values) => values
+                .iter()
+                .map(|value| value.as_str().map(str::to_owned))
+                .collect(),
+            _ => None,
+        }
+    }
+
+    fn length(&self) -> Option<usize> {
+        self.value?.as_u64().map(|length| length as usize)
+    }
+}
+
+fn import(config: &Value) -> Result<CommitlintConfig, String> {
+    let config = config
+        .as_object()
+        .ok_or("The configuration must be an object")?;
+    let mut imported = CommitlintConfig {
+        options: ValidationOptions::default(),
+        warnings: Vec::new(),
+    };
+    for key in config.keys() {
+        match key.as_str() {
+            "rules" => {}
+            "extends" => imported.warnings.push(
+                "The 'extends' presets are ignored, the rules apply over the defaults of \
+                 validate-commit"
+                    .to_owned(),
+            ),
+            _ => imported
+                .warnings
+                .push(format!("The '{}' field is not supported", key)),
+        }
+    }
+
+    let rules = match config.get("rules") {
+        Some(rules) => rules.as_object().ok_or("'rules' must be an object")?,
+        None => return Ok(imported),
+    };
+    for (name, rule) in rules {
+        let rule = RuleConfig::parse(name, rule)?;
+        if !imported.apply(name, &rule) {
+            imported.warnings.push(format!(
+                "The rule '{}' is not supported, it is ignored",
+                name
+            ));
+        }
+    }
+    Ok(imported)
+}
+
+impl CommitlintConfig {
+    /// Map the rule onto the options, returning whether it is supported.
+    fn apply(&mut self, name: &str, rule: &RuleConfig) -> bool {
+        let options = &mut self.options;
+        let rule_id = match (name, rule.always) {
+            ("type-enum", true) => match rule.strings() {
+                Some(types) => {
+                    options.allowed_types = Some(types);
+                    RuleId::TypeValid
+                }
+                None => return false,
+            },
+            ("type-case", true) if rule.strings() == Some(vec!["lower-case".to_owned()]) => {
+                options.allow_uppercase_types = rule.level == 0;
+                return true;
+            }
+            ("type-empty", false) => RuleId::TypeNotEmpty,
+            ("scope-enum", true) => match rule.strings() {
+                Some(scopes) => {
+                    options.allowed_scopes = Some(scopes).filter(|scopes| !scopes.is_empty());
+                    RuleId::ScopeAllowed
+                }
+                None => return false,
+            },
+            ("scope-case", true) => {
+                options.scope_pattern = match rule.strings().as_deref() {
+                    Some([case]) if case == "lower-case" => ScopePattern::Lowercase,
+                    Some([case]) if case == "kebab-case" => ScopePattern::KebabCase,
+                    _ => return false,
+                };
+                RuleId::ScopeFormat
+            }
+            ("scope-empty", always) => {
+                if rule.level > 0 {
+                    options.scope_policy = if always {
+                        ScopePolicy::Forbidden
+                    } else {
+                        ScopePolicy::Required
+                    };
+                }
+                RuleId::ScopePolicy
+            }
+            ("subject-case", always) => {
+                let cases = rule.strings().unwrap_or_default();
+                let forbids_capitals = if always {
+                    cases == ["lower-case"]
+                } else {
+                    cases.iter().any(|case| case == "sentence-case")
+                };
+                if !forbids_capitals {
+                    return false;
+                }
+                RuleId::SubjectNotCapitalized
+            }
+            ("subject-empty", false) => RuleId::SubjectNotEmpty,
+            ("subject-full-stop", false) => {
+                options.subject_trailing_punctuation = match rule.value {
+                    Some(Value::String(stop)) => stop.clone(),
+                    None => ".".to_owned(),
+                    Some(_) => return false,
+                };
+                RuleId::SubjectNoTrailingPunctuation
+            }
+            ("header-max-length", true) => match rule.length() {
+                Some(length) => {
+                    options.max_header_length = if rule.level == 0 { 0 } else { length };
+                    return true;
+                }
+                None => return false,
+            },
+            ("body-max-line-length", true) => match rule.length() {
+                Some(length) => {
+                    options.max_body_line_length = if rule.level == 0 { 0 } else { length };
+                    return true;
+                }
+                None => return false,
+            },
+            ("body-leading-blank", true) => RuleId::SecondLineEmpty,
+            ("signed-off-by", true) => {
+                options.require_signoff = rule.level > 0;
+                RuleId::SignOff
+            }
+            _ => return false,
+        };
+
+        match rule.level {
+            0 => {
+                options.disabled_rules.insert(rule_id);
+            }
+            1 => {
+                options.severities.insert(rule_id, Severity::Warning);
+            }
+            _ => {
+                options.severities.insert(rule_id, Severity::Error);
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(path: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(path)
+    }
+
+    #[test]
+    fn import_json_config() {
+        let config = load_commitlint_config(fixture("commitlint-config")).unwrap();
+        let options = &config.options;
+        assert_eq!(
+            options.allowed_types,
+            Some(vec![
+                "feat".to_owned(),
+                "fix".to_owned(),
+                "docs".to_owned(),
+                "deps".to_owned(),
+            ])
+        );
+        assert_eq!(
+            options.allowed_scopes,
+            Some(vec!["lib".to_owned(), "cli".to_owned()])
+        );
+        assert_eq!(options.scope_pattern, ScopePattern::KebabCase);
+        assert_eq!(options.max_header_length, 72);
+        assert_eq!(options.max_body_line_length, 0);
+        assert_eq!(
+            options.severity(RuleId::SubjectNotCapitalized),
+            Severity::Warning
+        );
+        assert_eq!(options.severity(RuleId::TypeValid), Severity::Error);
+        assert!(!options.is_enabled(RuleId::SubjectNoTrailingPunctuation));
+        assert_eq!(
+            config.warnings,
+            vec![
+                "The 'extends' presets are ignored, the rules apply over the defaults of \
+                 validate-commit",
+                "The rule 'footer-leading-blank' is not supported, it is ignored",
+            ]
+        );
+
+        let report = ::validate_commit_message_report_with("deps(lib): Bump regex", options);
+        assert!(report.is_ok());
+        assert_eq!(report.warnings.len(), 1);
+        assert!(::validate_commit_message_with("chore(lib): bump regex", options).is_err());
+        assert!(::validate_commit_message_with("feat(api): add colors", options).is_err());
+    }
+
+    #[test]
+    fn import_yaml_config() {
+        let config = load_commitlint_config(fixture("commitlint-yaml-config")).unwrap();
+        let options = &config.options;
+        assert_eq!(
+            options.allowed_types,
+            Some(vec!["feat".to_owned(), "fix".to_owned()])
+        );
+        assert_eq!(options.allowed_scopes, None);
+        assert_eq!(options.scope_policy, ScopePolicy::Required);
+        assert_eq!(options.max_header_length, 100);
+        assert!(options.require_signoff);
+        assert_eq!(
+            config.warnings,
+            vec!["The rule 'scope-case' is not supported, it is ignored"]
+        );
+    }
+
+    #[test]
+    fn discard_malformed_rules() {
+        let config = serde_json::json!({ "rules": { "type-enum": [2, "sometimes", ["feat"]] } });
+        assert_eq!(
+            import(&config).unwrap_err(),
+            "Rule 'type-enum' must be like [2, \"always\", value]"
+        );
+        let config = serde_json::json!({ "rules": { "type-enum": "feat" } });
+        assert!(import(&config).is_err());
+        assert!(import(&serde_json::json!(["feat"])).is_err());
+    }
+}
@@ -0,0 +1,670 @@
+//! Loading of the validation options from a configuration file.
+//!
+//! The configuration file is named `.validate-commit.toml`, and contains the fields of
+//! [`ValidationOptions`]:
+//!
+//! ```toml
+//! allowed_types = ["feat", "fix", "deps", "release"]
+//! allow_uppercase_types = false
+//! aliases = { feature = "feat", bugfix = "fix", doc = "docs" }
+//! strict_aliases = false
+//! allowed_scopes = ["lib", "api/*"]
+//! scope_pattern = "kebab-case" # or "any", "lowercase"
+//! scope_policy = "required" # or "any", "forbidden"
+//! header_pattern = '^(?P<type>[a-z]+): (?P<subject>.+)$'
+//! allow_ticket_prefix = '^\[[A-Z]+-\d+\]'
+//! allow_emoji_prefix = false
+//! comment_char = "#"
+//! subject_trailing_punctuation = ".!?"
+//! ignore_prefixes = ["Merge ", "WIP", "Release:"]
+//! ignore_patterns = ['^\[bot\] ']
+//! allowed_literal_headers = ["Initial commit"]
+//! allowed_header_regexes = ['^Release \d+\.\d+\.\d+$']
+//! validate_merges = false
+//! strict_reverts = false
+//! pr_title = false
+//! allowed_capitalized_words = ["Windows", "GitHub"]
+//! imperative_allowed_words = ["fixes"]
+//! imperative_denied_words = ["misc"]
+//! soft_subject_limit = 50
+//! require_reference = ["fix"]
+//! reference_pattern = '#\d+|\b[A-Z][A-Z0-9]+-\d+\b'
+//! require_signoff = false
+//! require_body = ["feat"]
+//! max_header_length = 72
+//! max_body_line_length = 100
+//! ignore_long_urls = true
+//! ignore_indented_lines = true
+//! forbidden_words = ["temp", "do not merge"]
+//! forbidden_patterns = ['(?i)\bfixme\b']
+//! disabled_rules = ["subject-not-capitalized"]
+//!
+//! [severities]
+//! subject-not-capitalized = "warning"
+//!
+//! [[plugin]]
+//! command = "./scripts/check-msg.sh"
+//! ```
+//!
+//! Missing fields keep their default value. An alias which is an allowed type itself is rejected.
+//! The `[[plugin]]` tables need the `plugin` feature, see the [`plugin`](::plugin) module.
+//!
+//! Personal defaults can be kept in a user configuration file, `validate-commit/config.toml` in
+//! `$XDG_CONFIG_HOME` or `~/.config`. The repository configuration file overrides the keys it
+//! sets, and keeps the other keys of the user configuration file.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use errors::ConfigError;
+use options::ValidationOptions;
+
+/// Name of the configuration file
+pub const CONFIG_FILE_NAME: &str = ".validate-commit.toml";
+
+/// Path of the user configuration file, relative to the configuration directory of the user
+pub const USER_CONFIG_PATH: &str = "validate-commit/config.toml";
+
+/// Keys of the configuration file, in the order of [`ValidationOptions`], with their description,
+/// and an example value for the keys without default value
+const CONFIG_KEYS: [(&str, &str, Option<&str>); 36] = [
+    (
+        "allowed_types",
+        "Commit types allowed in the header, instead of the built-in ones",
+        Some(r#"["feat", "fix", "docs", "deps"]"#),
+    ),
+    (
+        "allow_uppercase_types",
+        "Accept commit types with uppercase letters, like `Feat`",
+        None,
+    ),
+    (
+        "aliases",
+        "Other spellings of the commit types, reported as warnings",
+        None,
+    ),
+    (
+        "strict_aliases",
+        "Report the aliases of the commit types as errors",
+        None,
+    ),
+    (
+        "allowed_scopes",
+        "Scopes allowed in the header, which can be glob patterns",
+        Some(r#"["lib", "api/*"]"#),
+    ),
+    (
+        "scope_pattern",
+        "Format of the scopes: \"any\", \"lowercase\" or \"kebab-case\"",
+        None,
+    ),
+    (
+        "header_pattern",
+        "Pattern replacing the `type(scope)!: subject` format, with `type`, `scope` and \
+         `subject` groups",
+        Some(r#"'^(?P<type>[a-z]+): (?P<subject>.+)$'"#),
+    ),
+    (
+        "allow_ticket_prefix",
+        "Pattern of a ticket tag accepted before the header",
+        Some(r#"'^\[[A-Z]+-\d+\]'"#),
+    ),
+    (
+        "allow_emoji_prefix",
+        "Accept an emoji, like `:sparkles:`, before the header",
+        None,
+    ),
+    (
+        "scope_policy",
+        "Whether the header has a scope: \"any\", \"required\" or \"forbidden\"",
+        None,
+    ),
+    (
+        "comment_char",
+        "Character starting the comment lines, like git's `core.commentChar`",
+        None,
+    ),
+    (
+        "subject_trailing_punctuation",
+        "Characters the subject must not end with",
+        None,
+    ),
+    (
+        "ignore_prefixes",
+        "Prefixes of the messages which are not validated",
+        None,
+    ),
+    (
+        "ignore_patterns",
+        "Patterns of the messages which are not validated",
+        None,
+    ),
+    (
+        "allowed_literal_headers",
+        "Headers accepted as they are",
+        None,
+    ),
+    (
+        "allowed_header_regexes",
+        "Patterns of the headers accepted as they are",
+        None,
+    ),
+    (
+        "validate_merges",
+        "Validate the merge messages which were not generated by git",
+        None,
+    ),
+    (
+        "strict_reverts",
+        "Validate the reverted header of the messages generated by `git revert`",
+        None,
+    ),
+    (
+        "pr_title",
+        "Validate only the header, as the title of a pull request",
+        None,
+    ),
+    (
+        "allowed_capitalized_words",
+        "Words the subject may start with, like proper nouns",
+        None,
+    ),
+    (
+        "imperative_allowed_words",
+        "First words of the subject accepted as imperative",
+        None,
+    ),
+    (
+        "imperative_denied_words",
+        "First words of the subject rejected as not imperative",
+        None,
+    ),
+    (
+        "soft_subject_limit",
+        "Length of the subject above which a warning is reported",
+        Some("50"),
+    ),
+    (
+        "require_reference",
+        "Commit types which must reference an issue",
+        None,
+    ),
+    ("reference_pattern", "Pattern of the issue references", None),
+    ("require_signoff", "Require a `Signed-off-by` footer", None),
+    ("require_body", "Commit types which must have a body", None),
+    (
+        "max_header_length",
+        "Maximum length of the header, 0 to disable the check",
+        None,
+    ),
+    (
+        "max_body_line_length",
+        "Maximum length of the body lines, 0 to disable the check",
+        None,
+    ),
+    (
+        "ignore_long_urls",
+        "Accept the body lines longer than the maximum length because of a URL",
+        None,
+    ),
+    (
+        "ignore_indented_lines",
+        "Accept the indented body lines longer than the maximum length, like code blocks",
+        None,
+    ),
+    (
+        "forbidden_words",
+        "Words the subject and the body must not contain",
+        None,
+    ),
+    (
+        "forbidden_patterns",
+        "Patterns the subject and the body must not match",
+        None,
+    ),
+    (
+        "severities",
+        "Severities of the rules, \"error\" or \"warning\", like \
+         `{ subject-not-capitalized = \"warning\" }`",
+        None,
+    ),
+    (
+        "disabled_rules",
+        "Rules which are not checked, see `validate-commit explain <RULE>`",
+        None,
+    ),
+    (
+        "plugin",
+        "External commands checking the messages",
+        Some("[[plugin]]\ncommand = \"./scripts/check-msg.sh\"\nargs = []\ntimeout = 10"),
+    ),
+];
+
+/// Render the configuration file setting every key to its default value, with a comment
+/// describing it.
+///
+/// The keys without default value are commented out, with an example value.
+pub fn default_config_file() -> String {
+    let defaults = to_table(&ValidationOptions::default());
+    let mut text =
+        "# Configuration of validate-commit, every key is set to its default value\n".to_owned();
+    for &(key, description, example) in CONFIG_KEYS.iter() {
+        // The plugins are rejected without the plugin feature
+        if key == "plugin" && !cfg!(feature = "plugin") {
+            continue;
+        }
+        text += &format!("\n# {}\n", description);
+        match defaults.get(key) {
+            Some(value) => text += &format!("{}\n", render_entry(key, value)),
+            None => {
+                let example = example.expect(key);
+                let example = if example.starts_with("[[") {
+                    example.to_owned()
+                } else {
+                    format!("{} = {}", key, example)
+                };
+                for line in example.lines() {
+                    text += &format!("# {}\n", line);
+                }
+            }
+        }
+    }
+    text
+}
+
+/// Render the options as a configuration file, like the effective configuration.
+pub fn config_to_toml(options: &ValidationOptions) -> String {
+    toml::to_string(&toml::Value::Table(to_table(options))).expect("The options are valid TOML")
+}
+
+fn to_table(options: &ValidationOptions) -> toml::value::Table {
+    match toml::Value::try_from(options) {
+        Ok(toml::Value::Table(table)) => table,
+        _ => unreachable!("The options are serialized as a table"),
+    }
+}
+
+/// Render the key and its value on a single line, the tables being inline.
+fn render_entry(key: &str, value: &toml::Value) -> String {
+    match value {
+        toml::Value::Table(table) if table.is_empty() => format!("{} = {{}}", key),
+        toml::Value::Table(table) => {
+            let entries: Vec<_> = table.iter().map(|(k, v)| render_entry(k, v)).collect();
+            format!("{} = {{ {} }}", key, entries.join(", "))
+        }
+        _ => {
+            let mut entry = toml::value::Table::new();
+            entry.insert(key.to_owned(), value.clone());
+            let entry = toml::to_string(&toml::Value::Table(entry)).expect(key);
+            entry.trim_end().to_owned()
+        }
+    }
+}
+
+/// Search the configuration file from the given directory, up to the repository root.
+///
+/// The repository root is the first directory containing a `.git` entry.
+pub fn find_config_file<P: AsRef<Path>>(dir: P) -> Option<PathBuf> {
+    for dir in dir.as_ref().ancestors() {
+        let path = dir.join(CONFIG_FILE_NAME);
+        if path.is_file() {
+            return Some(path);
+        }
+
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+
+    None
+}
+
+/// Find the user configuration file, in `$XDG_CONFIG_HOME`, or in `~/.config` if it is not set.
+pub fn find_user_config_file() -> Option<PathBuf> {
+    let config_dir = match std::env::var_os("XDG_CONFIG_HOME").map(PathBuf::from) {
+        Some(dir) if dir.is_absolute() => dir,
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    let path = config_dir.join(USER_CONFIG_PATH);
+    if path.is_file() {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+/// Load the validation options from the configuration file found from the given directory.
+///
+/// Return the default options if there is no configuration file.
+pub fn load_config<P: AsRef<Path>>(dir: P) -> Result<ValidationOptions, ConfigError> {
+    match find_config_file(dir) {
+        Some(path) => load_config_file(path),
+        None => Ok(ValidationOptions::default()),
+    }
+}
+
+/// Load the validation options from the user configuration file, if any, overridden by the
+/// configuration file found from the given directory.
+pub fn load_layered_config<P: AsRef<Path>>(
+    dir: P,
+    user_config: Option<&Path>,
+) -> Result<ValidationOptions, ConfigError> {
+    let paths: Vec<PathBuf> = user_config
+        .map(Path::to_owned)
+        .into_iter()
+        .chain(find_config_file(dir))
+        .collect();
+    load_config_files(&paths)
+}
+
+/// Load the validation options from the given configuration file.
+pub fn load_config_file<P: AsRef<Path>>(path: P) -> Result<ValidationOptions, ConfigError> {
+    load_config_files(&[path])
+}
+
+/// Load the validation options from the given configuration files, each file overriding the keys
+/// it sets.
+///
+/// The tables, like `[severities]` and `[aliases]`, are merged key by key, while the lists are
+/// replaced as a whole. The plugins are replaced as a whole, by the last file declaring some.
+pub fn load_config_files<P: AsRef<Path>>(paths: &[P]) -> Result<ValidationOptions, ConfigError> {
+    let layers = paths
+        .iter()
+        .map(|path| read_config_table(path.as_ref()).map(|layer| (path.as_ref(), layer)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let last_path = match layers.last() {
+        Some(&(path, _)) => path,
+        None => return Ok(ValidationOptions::default()),
+    };
+    #[cfg(feature = "plugin")]
+    let plugins_path = layers
+        .iter()
+        .rev()
+        .find(|(_, layer)| layer.contains_key("plugin"))
+        .map_or(last_path, |&(path, _)| path);
+
+    let table = layers
+        .into_iter()
+        .fold(toml::value::Table::new(), |table, (_, layer)| {
+            merge_tables(table, layer)
+        });
+    #[cfg_attr(not(feature = "plugin"), allow(unused_mut))]
+    let mut options: ValidationOptions = toml::Value::Table(table)
+        .try_into()
+        .map_err(|e| ConfigError::new(last_path, e))?;
+    #[cfg(feature = "plugin")]
+    for plugin in &mut options.plugins {
+        plugin.declared_in(plugins_path);
+    }
+    if let Some(alias) = options.colliding_alias() {
+        let message = format!("Alias '{}' is already a commit type", alias);
+        return Err(ConfigError::new(last_path, message));
+    }
+    Ok(options)
+}
+
+/// Override the keys of the table by the ones of the layer, merging the nested tables.
+fn merge_tables(mut table: toml::value::Table, layer: toml::value::Table) -> toml::value::Table {
+    for (key, value) in layer {
+        let value = match (table.remove(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(value)) => {
+                toml::Value::Table(merge_tables(base, value))
+            }
+            (_, value) => value,
+        };
+        table.insert(key, value);
+    }
+    table
+}
+
+/// Read the keys set by the configuration file, checking that they are valid on their own.
+fn read_config_table(path: &Path) -> Result<toml::value::Table, ConfigError> {
+    let content = fs::read_to_string(path).map_err(|e| ConfigError::new(path, e))?;
+    // Deserialize the options from the text, for the errors to give the position of the values
+    toml::from_str::<ValidationOptions>(&content).map_err(|e| ConfigError::new(path, e))?;
+    toml::from_str(&content).map_err(|e| ConfigError::new(path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use options::{Pattern, ScopePattern, ScopePolicy};
+    use rules::{RuleId, Severity};
+    use CommitType;
+
+    fn fixture(path: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(path)
+    }
+
+    #[test]
+    fn find_config_file_from_nested_directory() {
+        assert_eq!(
+            find_config_file(fixture("config/nested/directory")),
+            Some(fixture("config/.validate-commit.toml"))
+        );
+        assert_eq!(
+            find_config_file(fixture("config")),
+            Some(fixture("config/.validate-commit.toml"))
+        );
+    }
+
+    #[test]
+    fn missing_config_file_gives_default_options() {
+        assert_eq!(find_config_file(fixture("")), None);
+        assert_eq!(
+            load_config(fixture("")).unwrap(),
+            ValidationOptions::default()
+        );
+    }
+
+    #[test]
+    fn load_config_from_nested_directory() {
+        let options = load_config(fixture("config/nested/directory")).unwrap();
+        assert_eq!(
+            options.allowed_types,
+            Some(vec![
+                "feat".to_owned(),
+                "fix".to_owned(),
+                "deps".to_owned(),
+                "release".to_owned(),
+            ])
+        );
+        assert_eq!(options.max_header_length, 72);
+        assert_eq!(options.max_body_line_length, 100);
+        assert_eq!(options.scope_pattern, ScopePattern::KebabCase);
+        assert_eq!(options.scope_policy, ScopePolicy::Required);
+        assert_eq!(
+            options.severity(RuleId::SubjectNotCapitalized),
+            Severity::Warning
+        );
+        assert_eq!(options.severity(RuleId::TypeValid), Severity::Error);
+        assert!(!options.is_enabled(RuleId::SecondLineEmpty));
+        assert!(options.is_enabled(RuleId::TypeValid));
+        assert_eq!(options.ignore_prefixes, vec!["Merge ", "WIP", "Release:"]);
+        assert_eq!(
+            options.ignore_patterns,
+            vec![Pattern::new(r"^\[bot\] ").unwrap()]
+        );
+    }
+
+    #[test]
+    fn discard_malformed_config_file() {
+        let error = load_config(fixture("malformed-config")).unwrap_err();
+        assert_eq!(
+            error.path(),
+            fixture("malformed-config/.validate-commit.toml")
+        );
+        assert!(error
+            .to_string()
+            .starts_with("Invalid configuration file '"));
+    }
+
+    #[test]
+    fn discard_invalid_patterns() {
+        let error = toml::from_str::<ValidationOptions>("ignore_patterns = ['(']").unwrap_err();
+        assert!(error.to_string().contains("regex parse error"));
+    }
+
+    #[test]
+    #[cfg(feature = "plugin")]
+    fn load_plugins_relative_to_config_file() {
+        let options = load_config(fixture("plugin-config")).unwrap();
+        assert_eq!(options.plugins.len(), 1);
+        assert_eq!(
+            Path::new(&options.plugins[0].command),
+            fixture("plugin-config/scripts/check-msg.sh")
+        );
+        assert_eq!(options.plugins[0].args, Vec::<String>::new());
+        assert_eq!(options.plugins[0].timeout, 5);
+
+        let options: ValidationOptions =
+            toml::from_str("[[plugin]]\ncommand = 'check-msg'").unwrap();
+        assert_eq!(options.plugins[0].timeout, 10);
+    }
+
+    #[test]
+    fn load_required_bodies() {
+        let options: ValidationOptions = toml::from_str("require_body = ['feat', 'deps']").unwrap();
+        assert_eq!(
+            options.require_body,
+            vec![CommitType::Feat, CommitType::Custom("deps".to_owned())]
+        );
+        let report = ::validate_commit_message_report_with("feat: add colors", &options);
+        assert_eq!(report.errors[0].rule(), RuleId::BodyRequired);
+    }
+
+    #[test]
+    fn discard_aliases_colliding_with_types() {
+        let error = load_config(fixture("alias-collision-config")).unwrap_err();
+        assert!(error
+            .to_string()
+            .ends_with(": Alias 'fix' is already a commit type"));
+
+        let options: ValidationOptions = toml::from_str("aliases = { feature = 'feat' }").unwrap();
+        assert_eq!(options.aliases["feature"], CommitType::Feat);
+    }
+
+    #[test]
+    fn discard_unknown_config_keys() {
+        assert!(load_config_file(fixture("unknown-key-config/.validate-commit.toml")).is_err());
+    }
+
+    #[test]
+    fn merge_user_and_repository_configs() {
+        let user_config = fixture("user-config/validate-commit/config.toml");
+        let options = load_layered_config(fixture("layered-config"), Some(&user_config)).unwrap();
+        assert_eq!(
+            options.allowed_types,
+            Some(vec!["feat".to_owned(), "fix".to_owned(), "deps".to_owned()])
+        );
+        assert_eq!(options.max_header_length, 72);
+        assert_eq!(options.max_body_line_length, 80);
+        // The tables are merged key by key
+        assert_eq!(
+            options.severities,
+            [
+                (RuleId::SubjectImperative, Severity::Error),
+                (RuleId::TypeCanonical, Severity::Error),
+            ]
+            .into()
+        );
+        assert_eq!(
+            options.aliases,
+            [
+                ("feature".to_owned(), CommitType::Feat),
+                ("bugfix".to_owned(), CommitType::Fix),
+            ]
+            .into()
+        );
+
+        let options = load_layered_config(fixture("messages"), Some(&user_config)).unwrap();
+        assert_eq!(options.max_header_length, 50);
+        let options = load_layered_config(fixture("layered-config"), None).unwrap();
+        assert_eq!(options.allowed_types, None);
+        assert_eq!(options.max_header_length, 72);
+
+        // An invalid file is reported even if the next one overrides its keys
+        let error = load_config_files(&[
+            fixture("unknown-key-config/.validate-commit.toml"),
+            fixture("layered-config/.validate-commit.toml"),
+        ])
+        .unwrap_err();
+        assert!(error.to_string().contains("unknown-key-config"));
+    }
+
+    #[test]
+    fn describe_every_config_key() {
+        let keys: Vec<_> = CONFIG_KEYS.iter().map(|&(key, _, _)| key).collect();
+        let defaults = to_table(&ValidationOptions::default());
+        for key in defaults.keys() {
+            assert!(keys.contains(&key.as_str()), "{} is not described", key);
+        }
+        for &(key, _, example) in CONFIG_KEYS.iter() {
+            assert!(
+                defaults.contains_key(key) != example.is_some(),
+                "{} needs an example if and only if it has no default value",
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn load_default_config_file() {
+        let text = default_config_file();
+        assert!(text.contains("\n# Maximum length of the header, 0 to disable the check\n"));
+        assert!(text.contains("\nmax_header_length = 100\n"));
+        assert!(text.contains("\n# soft_subject_limit = 50\n"));
+        let options: ValidationOptions = toml::from_str(&text).unwrap();
+        assert_eq!(options, ValidationOptions::default());
+
+        let uncommented: String = text
+            .lines()
+            .map(|line| line.trim_start_matches("# "))
+            .filter(|line| {
+                let key = line.split(" = ").next().unwrap();
+                line.starts_with("[[") || key.chars().all(|c| c.is_ascii_lowercase() || c == '_')
+            })
+            .map(|line| format!("{}\n", line))
+            .collect();
+        let options: ValidationOptions = toml::from_str(&uncommented).unwrap();
+        assert_eq!(options.soft_subject_limit, Some(50));
+        assert!(options.header_pattern.is_some());
+        assert!(options
+            .allow_ticket_prefix
+            .unwrap()
+            .is_match("[PROJ-12] feat: add colors"));
+        #[cfg(feature = "plugin")]
+        assert_eq!(options.plugins.len(), 1);
+        #[cfg(not(feature = "plugin"))]
+        assert!(!text.contains("[[plugin]]"));
+    }
+
+    #[test]
+    fn round_trip_options() {
+        let options = load_config_file(fixture("config/.validate-commit.toml")).unwrap();
+        let reloaded: ValidationOptions = toml::from_str(&config_to_toml(&options)).unwrap();
+        assert_eq!(reloaded, options);
+
+        let mut options = ValidationOptions {
+            allowed_scopes: Some(vec!["lib".to_owned()]),
+            scope_pattern: ScopePattern::KebabCase,
+            scope_policy: ScopePolicy::Required,
+            soft_subject_limit: Some(50),
+            forbidden_patterns: vec!["(?i)wip".parse::<Pattern>().unwrap()],
+            ..ValidationOptions::default()
+        };
+        options
+            .severities
+            .insert(RuleId::SubjectNotCapitalized, Severity::Warning);
+        options.disabled_rules.insert(RuleId::SignOff);
+        options
+            .aliases
+            .insert("feature".to_owned(), CommitType::Feat);
+        let text = config_to_toml(&options);
+        assert!(text.contains("scope_pattern = \"kebab-case\"\n"));
+        let reloaded: ValidationOptions = toml::from_str(&text).unwrap();
+        assert_eq!(reloaded, options);
+    }
+}
@@ -0,0 +1,169 @@
+//! Overrides of the options by environment variables, to configure the validation in CI without
+//! a configuration file.
+//!
+//! The variables are applied over the configuration file, and the command line options are
+//! applied over them:
+//!
+//! - `VALIDATE_COMMIT_MAX_LINE_LENGTH`: maximum length of the header and of the body lines
+//! - `VALIDATE_COMMIT_MAX_HEADER_LENGTH`: maximum length of the header
+//! - `VALIDATE_COMMIT_MAX_BODY_LINE_LENGTH`: maximum length of the body lines
+//! - `VALIDATE_COMMIT_ALLOWED_TYPES`: allowed commit types, separated by commas
+//! - `VALIDATE_COMMIT_REQUIRE_SCOPE`: `true` to require a scope, `false` to make it optional
+//!
+//! The command line also reads `VALIDATE_COMMIT_COLOR`, like its `--color` option.
+
+use errors::EnvError;
+use options::{ScopePolicy, ValidationOptions};
+
+/// Prefix of the environment variables overriding the options
+pub const ENV_PREFIX: &str = "VALIDATE_COMMIT_";
+
+/// Override the options with the `VALIDATE_COMMIT_` variables among the given ones, like
+/// `std::env::vars()`.
+///
+/// The specific lengths take precedence over `VALIDATE_COMMIT_MAX_LINE_LENGTH`, and the unknown
+/// variables are ignored.
+///
+/// # Examples
+///
+/// ```
+/// # use validate_commit::{apply_env_overrides, ValidationOptions};
+/// let mut options = ValidationOptions::default();
+/// let vars = vec![("VALIDATE_COMMIT_MAX_LINE_LENGTH", "72")];
+/// apply_env_overrides(&mut options, vars).unwrap();
+/// assert_eq!(options.max_header_length, 72);
+///
+/// let vars = vec![("VALIDATE_COMMIT_MAX_LINE_LENGTH", "long")];
+/// let error = apply_env_overrides(&mut options, vars).unwrap_err();
+/// assert_eq!(error.variable(), "VALIDATE_COMMIT_MAX_LINE_LENGTH");
+/// ```
+pub fn apply_env_overrides<I, K, V>(
+    options: &mut ValidationOptions,
+    vars: I,
+) -> Result<(), EnvError>
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    let mut max_line_length = None;
+    let mut max_header_length = None;
+    let mut max_body_line_length = None;
+    for (name, value) in vars {
+        let (name, value) = (name.as_ref(), value.as_ref());
+        let error = |message: String| EnvError::new(name, message);
+        match name.strip_prefix(ENV_PREFIX).unwrap_or_default() {
+            "MAX_LINE_LENGTH" => max_line_length = Some(parse_length(value).map_err(error)?),
+            "MAX_HEADER_LENGTH" => max_header_length = Some(parse_length(value).map_err(error)?),
+            "MAX_BODY_LINE_LENGTH" => {
+                max_body_line_length = Some(parse_length(value).map_err(error)?)
+            }
+            "ALLOWED_TYPES" => {
+                let types: Vec<String> = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|t| !t.is_empty())
+                    .map(str::to_owned)
+                    .collect();
+                if types.is_empty() {
+                    return Err(error("No commit types".to_owned()));
+                }
+                options.allowed_types = Some(types);
+            }
+            "REQUIRE_SCOPE" => {
+                options.scope_policy = if parse_bool(value).map_err(error)? {
+                    ScopePolicy::Required
+                } else {
+                    ScopePolicy::Any
+                };
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(length) = max_header_length.or(max_line_length) {
+        options.max_header_length = length;
+    }
+    if let Some(length) = max_body_line_length.or(max_line_length) {
+        options.max_body_line_length = length;
+    }
+    Ok(())
+}
+
+fn parse_length(value: &str) -> Result<usize, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("'{}' is not a length", value))
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    match value.trim() {
+        "true" | "1" => Ok(true),
+        "false" | "0" => Ok(false),
+        _ => Err(format!("'{}' is not a boolean, use true or false", value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn override_options() {
+        let mut options = ValidationOptions::default();
+        apply_env_overrides(
+            &mut options,
+            vec![
+                ("VALIDATE_COMMIT_MAX_HEADER_LENGTH", "50"),
+                ("VALIDATE_COMMIT_MAX_LINE_LENGTH", "72"),
+                ("VALIDATE_COMMIT_ALLOWED_TYPES", "feat, fix,deps,"),
+                ("VALIDATE_COMMIT_REQUIRE_SCOPE", "true"),
+                ("VALIDATE_COMMIT_UNKNOWN", "value"),
+                ("MAX_LINE_LENGTH", "10"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(options.max_header_length, 50);
+        assert_eq!(options.max_body_line_length, 72);
+        assert_eq!(
+            options.allowed_types,
+            Some(vec!["feat".to_owned(), "fix".to_owned(), "deps".to_owned()])
+        );
+        assert_eq!(options.scope_policy, ScopePolicy::Required);
+
+        apply_env_overrides(&mut options, vec![("VALIDATE_COMMIT_REQUIRE_SCOPE", "0")]).unwrap();
+        assert_eq!(options.scope_policy, ScopePolicy::Any);
+        apply_env_overrides(&mut options, Vec::<(String, String)>::new()).unwrap();
+        assert_eq!(options.max_header_length, 50);
+    }
+
+    #[test]
+    fn reject_invalid_values() {
+        let mut options = ValidationOptions::default();
+        let error = apply_env_overrides(
+            &mut options,
+            vec![("VALIDATE_COMMIT_MAX_BODY_LINE_LENGTH", "-1")],
+        )
+        .unwrap_err();
+        assert_eq!(error.variable(), "VALIDATE_COMMIT_MAX_BODY_LINE_LENGTH");
+        assert_eq!(
+            error.to_string(),
+            "Invalid environment variable VALIDATE_COMMIT_MAX_BODY_LINE_LENGTH: '-1' is not a \
+             length"
+        );
+        let error =
+            apply_env_overrides(&mut options, vec![("VALIDATE_COMMIT_REQUIRE_SCOPE", "yes")])
+                .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid environment variable VALIDATE_COMMIT_REQUIRE_SCOPE: 'yes' is not a boolean, \
+             use true or false"
+        );
+        assert!(
+            apply_env_overrides(&mut options, vec![("VALIDATE_COMMIT_ALLOWED_TYPES", " , ")])
+                .is_err()
+        );
+        assert_eq!(options, ValidationOptions::default());
+    }
+}
@@ -1,13 +1,43 @@
-use std::fmt;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::{fmt, io};
 
-use failure::{Backtrace, Context, Fail};
+#[cfg(feature = "serde")]
+use serde::ser::SerializeStruct;
+#[cfg(feature = "serde")]
+use serde::{Serialize, Serializer};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-#[derive(Debug, Fail)]
+use lang::Language;
+use rules::RuleId;
+use suggest::Suggestion;
+
+#[derive(Debug)]
 pub enum CommitValidationError {
-    #[fail(display = "{}", _0)]
-    Format(#[cause] FormatError),
-    #[fail(display = "{}", _0)]
-    Io(#[cause] IOError),
+    Format(FormatError),
+    Io(IOError),
+    Config(ConfigError),
+}
+
+impl fmt::Display for CommitValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CommitValidationError::Format(error) => error.fmt(f),
+            CommitValidationError::Io(error) => error.fmt(f),
+            CommitValidationError::Config(error) => error.fmt(f),
+        }
+    }
+}
+
+/// The error is transparent: its message and its source are the ones of the wrapped error.
+impl Error for CommitValidationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CommitValidationError::Format(error) => error.source(),
+            CommitValidationError::Io(error) => error.source(),
+            CommitValidationError::Config(error) => error.source(),
+        }
+    }
 }
 
 impl From<FormatError> for CommitValidationError {
@@ -22,135 +52,986 @@ impl From<IOError> for CommitValidationError {
     }
 }
 
+impl From<ConfigError> for CommitValidationError {
+    fn from(error: ConfigError) -> Self {
+        CommitValidationError::Config(error)
+    }
+}
+
 #[derive(Debug)]
 pub struct IOError {
-    inner: Context<IOErrorKind>,
+    kind: IOErrorKind,
+    path: Option<PathBuf>,
+    source: Option<io::Error>,
 }
 
-impl Fail for IOError {
-    fn cause(&self) -> Option<&Fail> {
-        self.inner.cause()
+impl IOError {
+    pub(crate) fn new(kind: IOErrorKind, source: io::Error) -> IOError {
+        IOError {
+            kind,
+            path: None,
+            source: Some(source),
+        }
+    }
+
+    pub(crate) fn with_path<P: AsRef<Path>>(mut self, path: P) -> IOError {
+        self.path = Some(path.as_ref().to_owned());
+        self
+    }
+
+    /// Kind of the error
+    pub fn kind(&self) -> IOErrorKind {
+        self.kind
+    }
+
+    /// Path of the commit file, if the message was read from a file
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
     }
+}
 
-    fn backtrace(&self) -> Option<&Backtrace> {
-        self.inner.backtrace()
+impl Error for IOError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source
+            .as_ref()
+            .map(|source| source as &(dyn Error + 'static))
     }
 }
 
 impl fmt::Display for IOError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        self.inner.fmt(f)
+        self.kind.fmt(f)?;
+        if let Some(ref path) = self.path {
+            write!(f, " '{}'", path.display())?;
+        }
+        Ok(())
     }
 }
 
 impl From<IOErrorKind> for IOError {
-    fn from(c: IOErrorKind) -> Self {
+    fn from(kind: IOErrorKind) -> Self {
         IOError {
-            inner: Context::new(c),
+            kind,
+            path: None,
+            source: None,
         }
     }
 }
 
-impl From<Context<IOErrorKind>> for IOError {
-    fn from(c: Context<IOErrorKind>) -> Self {
-        IOError { inner: c }
-    }
-}
-
-#[derive(Copy, Clone, Eq, PartialEq, Debug, Fail)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum IOErrorKind {
-    #[fail(display = "Error while opening commit file")]
     OpenFileError,
-    #[fail(display = "Error while reading commit file")]
     ReadFileError,
+    ReadMessageError,
+}
+
+impl fmt::Display for IOErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IOErrorKind::OpenFileError => write!(f, "Error while opening commit file"),
+            IOErrorKind::ReadFileError => write!(f, "Error while reading commit file"),
+            IOErrorKind::ReadMessageError => write!(f, "Error while reading commit message"),
+        }
+    }
+}
+
+/// Error while loading a configuration file
+#[derive(Debug)]
+pub struct ConfigError {
+    path: PathBuf,
+    message: String,
 }
 
-#[derive(Debug, Fail)]
+impl ConfigError {
+    #[cfg_attr(not(feature = "config"), allow(dead_code))]
+    pub(crate) fn new<P: AsRef<Path>, M: fmt::Display>(path: P, message: M) -> ConfigError {
+        ConfigError {
+            path: path.as_ref().to_owned(),
+            message: message.to_string(),
+        }
+    }
+
+    /// Path of the invalid configuration file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Error for ConfigError {}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Invalid configuration file '{}': {}",
+            self.path.display(),
+            self.message
+        )
+    }
+}
+
+/// Invalid value of an environment variable overriding the options
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvError {
+    variable: String,
+    message: String,
+}
+
+impl EnvError {
+    pub(crate) fn new<M: fmt::Display>(variable: &str, message: M) -> EnvError {
+        EnvError {
+            variable: variable.to_owned(),
+            message: message.to_string(),
+        }
+    }
+
+    /// Name of the invalid variable
+    pub fn variable(&self) -> &str {
+        &self.variable
+    }
+}
+
+impl Error for EnvError {}
+
+impl fmt::Display for EnvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Invalid environment variable {}: {}",
+            self.variable, self.message
+        )
+    }
+}
+
+/// Error while installing or uninstalling the git hook
+#[cfg(feature = "hook")]
+#[derive(Debug)]
+pub enum HookError {
+    /// No git repository was found from the given directory
+    NotARepository(PathBuf),
+    /// A hook which was not installed by validate-commit already exists
+    HookExists(PathBuf),
+    /// There is no hook installed by validate-commit
+    NotInstalled(PathBuf),
+    /// Error while reading or writing a file of the repository
+    Io(PathBuf, String),
+}
+
+#[cfg(feature = "hook")]
+impl HookError {
+    pub(crate) fn io<P: AsRef<Path>, M: fmt::Display>(path: P, message: M) -> HookError {
+        HookError::Io(path.as_ref().to_owned(), message.to_string())
+    }
+}
+
+#[cfg(feature = "hook")]
+impl Error for HookError {}
+
+#[cfg(feature = "hook")]
+impl fmt::Display for HookError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HookError::NotARepository(path) => {
+                write!(f, "Not a git repository: '{}'", path.display())
+            }
+            HookError::HookExists(path) => write!(
+                f,
+                "Hook '{}' already exists, use --force to replace it",
+                path.display()
+            ),
+            HookError::NotInstalled(path) => write!(
+                f,
+                "Hook '{}' was not installed by validate-commit",
+                path.display()
+            ),
+            HookError::Io(path, message) => {
+                write!(f, "Error with '{}': {}", path.display(), message)
+            }
+        }
+    }
+}
+
+/// Error while reading or writing a baseline file
+#[cfg(feature = "baseline")]
+#[derive(Debug)]
+pub enum BaselineError {
+    /// A line of the file is malformed, with its number from 1
+    Malformed(PathBuf, usize, String),
+    /// Error while reading or writing the file
+    Io(PathBuf, String),
+}
+
+#[cfg(feature = "baseline")]
+impl Error for BaselineError {}
+
+#[cfg(feature = "baseline")]
+impl fmt::Display for BaselineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BaselineError::Malformed(path, line, message) => write!(
+                f,
+                "Invalid baseline file '{}', line {}: {}",
+                path.display(),
+                line,
+                message
+            ),
+            BaselineError::Io(path, message) => {
+                write!(f, "Error with '{}': {}", path.display(), message)
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
 pub struct FormatError {
-    #[cause]
     pub kind: FormatErrorKind,
     location: Option<Span>,
+    // Boxed to keep the errors small, as they are returned in results
+    suggestion: Option<Box<Suggestion>>,
 }
 
 impl FormatError {
-    pub(crate) fn with_span(kind: FormatErrorKind, line: &str, pos: usize) -> FormatError {
+    pub(crate) fn with_span(
+        kind: FormatErrorKind,
+        line: &str,
+        line_number: usize,
+        pos: usize,
+    ) -> FormatError {
         FormatError {
             kind,
-            location: Some(Span::new(line, pos)),
+            location: Some(Span::new(line, line_number, pos)),
+            suggestion: None,
+        }
+    }
+
+    /// Kind of the error
+    pub fn kind(&self) -> &FormatErrorKind {
+        &self.kind
+    }
+
+    /// Rule reporting the error
+    pub fn rule(&self) -> RuleId {
+        RuleId::of(&self.kind)
+    }
+
+    /// Location of the error, if known
+    pub fn span(&self) -> Option<&Span> {
+        self.location.as_ref()
+    }
+
+    /// Content of the line containing the error, if known
+    pub fn line(&self) -> Option<&str> {
+        self.location.as_ref().map(Span::line)
+    }
+
+    /// Number of the line containing the error, starting from 1
+    ///
+    /// Comment lines are counted, so that the number matches the line in the commit file.
+    pub fn line_number(&self) -> Option<usize> {
+        self.location.as_ref().map(Span::line_number)
+    }
+
+    /// Column of the error in its line, in characters and starting from 1
+    pub fn column(&self) -> Option<usize> {
+        self.location.as_ref().map(Span::column)
+    }
+
+    /// Suggestion of how to fix the error, if any
+    pub fn suggestion(&self) -> Option<&str> {
+        self.suggestion.as_deref().map(Suggestion::text)
+    }
+
+    /// Suggestion of how to fix the error in the language, in English if it has no translation
+    pub fn suggestion_message(&self, language: Language) -> Option<String> {
+        self.suggestion
+            .as_ref()
+            .map(|suggestion| suggestion.message(language))
+    }
+
+    pub(crate) fn with_suggestion(self, suggestion: Option<Suggestion>) -> FormatError {
+        FormatError {
+            suggestion: suggestion.map(Box::new),
+            ..self
         }
     }
 
-    pub(crate) fn at(self, line: &str, pos: usize) -> FormatError {
-        FormatError::with_span(self.kind, line, pos)
+    /// Move the error to the given line, shifting its location by `offset` bytes.
+    pub(crate) fn relocate(self, line: &str, line_number: usize, offset: usize) -> FormatError {
+        match self.location {
+            Some(location) => FormatError {
+                suggestion: self.suggestion,
+                ..self.kind.spanning(
+                    line,
+                    line_number,
+                    location.pos + offset,
+                    location.end + offset,
+                )
+            },
+            None => self,
+        }
+    }
+
+    /// Key used to sort errors by line, then by column
+    pub(crate) fn sort_key(&self) -> (usize, usize) {
+        self.location
+            .as_ref()
+            .map_or((0, 0), |location| (location.line_number, location.pos))
     }
 }
 
+impl Error for FormatError {}
+
 impl fmt::Display for FormatError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         if let Some(ref location) = &self.location {
-            write!(f, "{}\n{}", self.kind, location)
+            write!(
+                f,
+                "{} [{} {}]\n{}",
+                self.kind,
+                self.kind.code(),
+                self.rule(),
+                location
+            )
         } else {
-            self.kind.fmt(f)
+            write!(f, "{} [{} {}]", self.kind, self.kind.code(), self.rule())
         }
     }
 }
 
+/// Serialize the error to its kind name, code, message, line number, column and suggestion
+#[cfg(feature = "serde")]
+impl Serialize for FormatError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut error = serializer.serialize_struct("FormatError", 6)?;
+        error.serialize_field("kind", self.kind.name())?;
+        error.serialize_field("code", self.kind.code())?;
+        error.serialize_field("message", &self.kind.to_string())?;
+        error.serialize_field("line", &self.line_number())?;
+        error.serialize_field("column", &self.column())?;
+        error.serialize_field("suggestion", &self.suggestion())?;
+        error.end()
+    }
+}
+
 impl From<FormatErrorKind> for FormatError {
     fn from(kind: FormatErrorKind) -> Self {
         FormatError {
             kind,
             location: None,
+            suggestion: None,
         }
     }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug, Fail)]
+/// Kind of a formatting error
+///
+/// New kinds are added with new rules, so matching on the kinds needs a wildcard arm. Each kind
+/// has a stable code, returned by [`code`](FormatErrorKind::code). The codes follow the order in
+/// which the kinds were added, and the code of a removed kind is never given to another one.
+///
+/// | Code | Kind |
+/// |------|------|
+/// | `VC001` | `CapitalizedFirstLetter` |
+/// | `VC002` | `EmptyCommitSubject` |
+/// | `VC003` | `EmptyCommitType` |
+/// | `VC004` | `InvalidCommitType` |
+/// | `VC005` | `LineTooLong` |
+/// | `VC006` | `MissingParenthesis` |
+/// | `VC007` | `MissingWhitespace` |
+/// | `VC008` | `MisplacedWhitespace` |
+/// | `VC009` | `NoColumn` |
+/// | `VC010` | `NonEmptySecondLine` |
+/// | `VC011` | `MisplacedBreakingChangeMarker` |
+/// | `VC012` | `EmptyFooterValue` |
+/// | `VC013` | `EmptyCommitMessage` |
+/// | `VC014` | `CommitTypeNotLowercase` |
+/// | `VC015` | `UnknownScope` |
+/// | `VC016` | `MissingScope` |
+/// | `VC017` | `ScopeNotAllowed` |
+/// | `VC018` | `EmptyScope` |
+/// | `VC019` | `InvalidScopeFormat` |
+/// | `VC020` | `TrailingPunctuation` |
+/// | `VC021` | `SubjectTooLong` |
+/// | `VC022` | `SubjectEchoesType` |
+/// | `VC023` | `NonImperativeSubject` |
+/// | `VC024` | `HeaderPatternMismatch` |
+/// | `VC025` | `MissingIssueReference` |
+/// | `VC026` | `MissingSignOff` |
+/// | `VC027` | `MalformedSignOff` |
+/// | `VC028` | `MalformedCoAuthor` |
+/// | `VC029` | `TrailingWhitespace` |
+/// | `VC030` | `LeadingBlankLine` |
+/// | `VC031` | `ConsecutiveBlankLines` |
+/// | `VC032` | `Custom` |
+/// | `VC033` | `ForbiddenWord` |
+/// | `VC034` | `MissingBody` |
+/// | `VC035` | `EmojiPrefix` |
+/// | `VC036` | `NonCanonicalType` |
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[non_exhaustive]
 pub enum FormatErrorKind {
-    #[fail(display = "First letter must not be capitalized")]
     CapitalizedFirstLetter,
-    #[fail(display = "Empty commit subject")]
+    CommitTypeNotLowercase {
+        /// The rejected commit type
+        commit_type: String,
+        /// The lowercase spelling of the commit type
+        expected: String,
+    },
+    ConsecutiveBlankLines,
+    /// Violation of a rule added to a [`Validator`](::Validator)
+    Custom {
+        /// Name of the rule
+        rule: String,
+        /// Description of the violation
+        message: String,
+    },
+    EmptyCommitMessage,
     EmptyCommitSubject,
-    #[fail(display = "Empty commit type")]
     EmptyCommitType,
-    #[fail(display = "Invalid commit type")]
-    InvalidCommitType,
-    #[fail(display = "Line must not be longer than {} characters", _0)]
+    EmptyFooterValue,
+    EmptyScope,
+    /// Emoji before the header, without the
+    /// [`ValidationOptions::allow_emoji_prefix`](::ValidationOptions::allow_emoji_prefix) option
+    EmojiPrefix {
+        /// The emoji, like `✨` or `:sparkles:`
+        emoji: String,
+    },
+    ForbiddenWord {
+        /// The forbidden word, as written in the message
+        word: String,
+    },
+    HeaderPatternMismatch {
+        /// The pattern the header must match
+        pattern: String,
+    },
+    InvalidCommitType {
+        /// The rejected commit type
+        commit_type: String,
+        /// The closest allowed commit type, if any is close enough
+        suggestion: Option<String>,
+    },
+    InvalidScopeFormat,
+    LeadingBlankLine,
     LineTooLong(usize),
-    #[fail(display = "Missing parenthesis")]
+    MalformedCoAuthor,
+    MalformedSignOff,
+    MisplacedBreakingChangeMarker,
+    MissingBody {
+        /// The commit type requiring a body
+        commit_type: String,
+    },
+    MissingIssueReference {
+        /// The pattern of the issue references
+        pattern: String,
+    },
     MissingParenthesis,
-    #[fail(display = "Missing whitespace")]
+    MissingScope,
+    MissingSignOff,
     MissingWhitespace,
-    #[fail(display = "Misplaced whitespace")]
     MisplacedWhitespace,
-    #[fail(display = "First line must contain a column")]
     NoColumn,
-    #[fail(display = "Second line must be empty")]
+    NonCanonicalType {
+        /// The alias of the commit type
+        commit_type: String,
+        /// The canonical commit type
+        canonical: String,
+    },
     NonEmptySecondLine,
+    NonImperativeSubject {
+        /// The first word of the subject
+        word: String,
+        /// The base form of the word, if it can be derived
+        suggestion: Option<String>,
+    },
+    ScopeNotAllowed,
+    SubjectEchoesType,
+    SubjectTooLong(usize),
+    TrailingPunctuation(char),
+    TrailingWhitespace,
+    UnknownScope {
+        /// The rejected scope
+        scope: String,
+    },
+}
+
+impl fmt::Display for FormatErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::FormatErrorKind::*;
+
+        match self {
+            CapitalizedFirstLetter => write!(f, "First letter must not be capitalized"),
+            CommitTypeNotLowercase {
+                commit_type,
+                expected,
+            } => write!(
+                f,
+                "Commit type '{}' must be lowercase, use '{}' instead",
+                commit_type, expected
+            ),
+            ConsecutiveBlankLines => write!(f, "Consecutive blank lines"),
+            Custom { message, .. } => write!(f, "{}", message),
+            EmptyCommitMessage => write!(f, "Empty commit message"),
+            EmptyCommitSubject => write!(f, "Empty commit subject"),
+            EmptyCommitType => write!(f, "Empty commit type"),
+            EmptyFooterValue => write!(f, "Empty footer value"),
+            EmptyScope => write!(f, "Empty scope"),
+            EmojiPrefix { emoji } => {
+                write!(f, "Header appears to start with the emoji '{}'", emoji)
+            }
+            ForbiddenWord { word } => write!(f, "Forbidden word '{}'", word),
+            HeaderPatternMismatch { pattern } => {
+                write!(f, "Header must match the pattern '{}'", pattern)
+            }
+            InvalidCommitType {
+                commit_type,
+                suggestion: Some(suggestion),
+            } => write!(
+                f,
+                "Invalid commit type '{}', did you mean '{}'?",
+                commit_type, suggestion
+            ),
+            InvalidCommitType {
+                commit_type,
+                suggestion: None,
+            } => write!(f, "Invalid commit type '{}'", commit_type),
+            InvalidScopeFormat => write!(f, "Invalid scope format"),
+            LeadingBlankLine => write!(f, "Message must not start with a blank line"),
+            LineTooLong(max) => write!(f, "Line must not be longer than {} characters", max),
+            MalformedCoAuthor => write!(
+                f,
+                "Co-authored-by footer must be formatted like 'Name <email>'"
+            ),
+            MalformedSignOff => write!(
+                f,
+                "Signed-off-by footer must be formatted like 'Name <email>'"
+            ),
+            MisplacedBreakingChangeMarker => write!(
+                f,
+                "Breaking change marker '!' must be placed right before the colon"
+            ),
+            MissingBody { commit_type } => {
+                write!(f, "Commit type '{}' requires a body", commit_type)
+            }
+            MissingIssueReference { pattern } => write!(
+                f,
+                "Commit message must reference an issue matching '{}'",
+                pattern
+            ),
+            MissingParenthesis => write!(f, "Missing parenthesis"),
+            MissingScope => write!(f, "Missing scope"),
+            MissingSignOff => write!(f, "Missing Signed-off-by footer"),
+            MissingWhitespace => write!(f, "Missing whitespace"),
+            MisplacedWhitespace => write!(f, "Misplaced whitespace"),
+            NoColumn => write!(f, "First line must contain a column"),
+            NonCanonicalType {
+                commit_type,
+                canonical,
+            } => write!(
+                f,
+                "Commit type '{}' is an alias, use '{}' instead",
+                commit_type, canonical
+            ),
+            NonEmptySecondLine => write!(f, "Second line must be empty"),
+            NonImperativeSubject {
+                word,
+                suggestion: Some(suggestion),
+            } => write!(
+                f,
+                "Subject should be in the imperative mood, use '{}' instead of '{}'",
+                suggestion, word
+            ),
+            NonImperativeSubject {
+                word,
+                suggestion: None,
+            } => write!(
+                f,
+                "Subject should be in the imperative mood, '{}' is not",
+                word
+            ),
+            ScopeNotAllowed => write!(f, "Scope is not allowed"),
+            SubjectEchoesType => write!(f, "Subject should not start with the commit type"),
+            SubjectTooLong(max) => {
+                write!(f, "Subject should not be longer than {} characters", max)
+            }
+            TrailingPunctuation(c) => write!(f, "Subject must not end with '{}'", c),
+            TrailingWhitespace => write!(f, "Line must not end with whitespaces"),
+            UnknownScope { scope } => write!(f, "Unknown scope '{}'", scope),
+        }
+    }
 }
 
 impl FormatErrorKind {
-    pub(crate) fn at(self, line: &str, pos: usize) -> FormatError {
-        FormatError::with_span(self, line, pos)
+    /// Name of the kind, like `"InvalidCommitType"`
+    pub fn name(&self) -> &'static str {
+        use self::FormatErrorKind::*;
+
+        match self {
+            CapitalizedFirstLetter => "CapitalizedFirstLetter",
+            CommitTypeNotLowercase { .. } => "CommitTypeNotLowercase",
+            ConsecutiveBlankLines => "ConsecutiveBlankLines",
+            Custom { .. } => "Custom",
+            EmptyCommitMessage => "EmptyCommitMessage",
+            EmptyCommitSubject => "EmptyCommitSubject",
+            EmptyCommitType => "EmptyCommitType",
+            EmptyFooterValue => "EmptyFooterValue",
+            EmptyScope => "EmptyScope",
+            EmojiPrefix { .. } => "EmojiPrefix",
+            ForbiddenWord { .. } => "ForbiddenWord",
+            HeaderPatternMismatch { .. } => "HeaderPatternMismatch",
+            InvalidCommitType { .. } => "InvalidCommitType",
+            InvalidScopeFormat => "InvalidScopeFormat",
+            LeadingBlankLine => "LeadingBlankLine",
+            LineTooLong(_) => "LineTooLong",
+            MalformedCoAuthor => "MalformedCoAuthor",
+            MalformedSignOff => "MalformedSignOff",
+            MisplacedBreakingChangeMarker => "MisplacedBreakingChangeMarker",
+            MissingBody { .. } => "MissingBody",
+            MissingIssueReference { .. } => "MissingIssueReference",
+            MissingParenthesis => "MissingParenthesis",
+            MissingScope => "MissingScope",
+            MissingSignOff => "MissingSignOff",
+            MissingWhitespace => "MissingWhitespace",
+            MisplacedWhitespace => "MisplacedWhitespace",
+            NoColumn => "NoColumn",
+            NonCanonicalType { .. } => "NonCanonicalType",
+            NonEmptySecondLine => "NonEmptySecondLine",
+            NonImperativeSubject { .. } => "NonImperativeSubject",
+            ScopeNotAllowed => "ScopeNotAllowed",
+            SubjectEchoesType => "SubjectEchoesType",
+            SubjectTooLong(_) => "SubjectTooLong",
+            TrailingPunctuation(_) => "TrailingPunctuation",
+            TrailingWhitespace => "TrailingWhitespace",
+            UnknownScope { .. } => "UnknownScope",
+        }
+    }
+
+    /// Stable code of the kind, like `"VC001"`
+    pub fn code(&self) -> &'static str {
+        use self::FormatErrorKind::*;
+
+        match self {
+            CapitalizedFirstLetter => "VC001",
+            EmptyCommitSubject => "VC002",
+            EmptyCommitType => "VC003",
+            InvalidCommitType { .. } => "VC004",
+            LineTooLong(_) => "VC005",
+            MissingParenthesis => "VC006",
+            MissingWhitespace => "VC007",
+            MisplacedWhitespace => "VC008",
+            NoColumn => "VC009",
+            NonEmptySecondLine => "VC010",
+            MisplacedBreakingChangeMarker => "VC011",
+            EmptyFooterValue => "VC012",
+            EmptyCommitMessage => "VC013",
+            CommitTypeNotLowercase { .. } => "VC014",
+            UnknownScope { .. } => "VC015",
+            MissingScope => "VC016",
+            ScopeNotAllowed => "VC017",
+            EmptyScope => "VC018",
+            InvalidScopeFormat => "VC019",
+            TrailingPunctuation(_) => "VC020",
+            SubjectTooLong(_) => "VC021",
+            SubjectEchoesType => "VC022",
+            NonImperativeSubject { .. } => "VC023",
+            HeaderPatternMismatch { .. } => "VC024",
+            MissingIssueReference { .. } => "VC025",
+            MissingSignOff => "VC026",
+            MalformedSignOff => "VC027",
+            MalformedCoAuthor => "VC028",
+            TrailingWhitespace => "VC029",
+            LeadingBlankLine => "VC030",
+            ConsecutiveBlankLines => "VC031",
+            Custom { .. } => "VC032",
+            ForbiddenWord { .. } => "VC033",
+            MissingBody { .. } => "VC034",
+            EmojiPrefix { .. } => "VC035",
+            NonCanonicalType { .. } => "VC036",
+        }
+    }
+
+    /// Locate the error at the byte offset `pos` of the line, numbered from 1.
+    pub fn at(self, line: &str, line_number: usize, pos: usize) -> FormatError {
+        FormatError::with_span(self, line, line_number, pos)
+    }
+
+    /// Locate the error on the text between the byte offsets `pos` and `end` of the line.
+    pub fn spanning(self, line: &str, line_number: usize, pos: usize, end: usize) -> FormatError {
+        FormatError {
+            kind: self,
+            location: Some(Span::spanning(line, line_number, pos, end)),
+            suggestion: None,
+        }
     }
 }
 
-#[derive(Debug)]
-struct Span {
+/// Location of an error in a message
+///
+/// The position is the byte offset of the offending text in the line, and the end is the byte
+/// offset right after it.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Span {
     line: String,
+    line_number: usize,
     pos: usize,
+    end: usize,
 }
 
 impl Span {
-    pub fn new(line: &str, pos: usize) -> Span {
+    /// Locate the error on the character at the byte offset `pos`.
+    pub(crate) fn new(line: &str, line_number: usize, pos: usize) -> Span {
+        let pos = char_boundary(line, pos);
+        let end = line[pos..]
+            .chars()
+            .next()
+            .map_or(pos, |c| pos + c.len_utf8());
+        Span::spanning(line, line_number, pos, end)
+    }
+
+    /// Locate the error on the text between the byte offsets `pos` and `end`.
+    ///
+    /// The offsets are clamped to the line and moved back to the start of the character they
+    /// fall in, so that the span can always be sliced.
+    pub(crate) fn spanning(line: &str, line_number: usize, pos: usize, end: usize) -> Span {
+        let pos = char_boundary(line, pos);
         Span {
             line: line.to_owned(),
+            line_number,
             pos,
+            end: char_boundary(line, end).max(pos),
         }
     }
+
+    /// Content of the line containing the error
+    pub fn line(&self) -> &str {
+        &self.line
+    }
+
+    /// Number of the line containing the error, starting from 1
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    /// Byte offset of the error in the line
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// Byte offset right after the offending text in the line
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    /// Column of the error in the line, in characters and starting from 1
+    pub fn column(&self) -> usize {
+        self.line[..self.pos].chars().count() + 1
+    }
+
+    /// Line as it is echoed, with its tabs expanded to spaces
+    pub fn display_line(&self) -> String {
+        expand_tabs(&self.line)
+    }
+
+    /// Display offset and width of the offending text in the echoed line, for underlining it
+    ///
+    /// The width is at least 1, so that an error at the end of the line is still visible.
+    pub fn display_range(&self) -> (usize, usize) {
+        let offset = expand_tabs(&self.line[..self.pos]).width();
+        let width = expand_tabs(&self.line[..self.end]).width() - offset;
+        (offset, width.max(1))
+    }
+}
+
+/// Return the start of the character of `text` at the byte offset `pos`, or the end of the text
+/// if the offset is past it.
+fn char_boundary(text: &str, pos: usize) -> usize {
+    let mut pos = pos.min(text.len());
+    while !text.is_char_boundary(pos) {
+        pos -= 1;
+    }
+    pos
+}
+
+/// Number of columns between two tab stops when echoing a line
+const TAB_WIDTH: usize = 4;
+
+/// Replace the tabs of a text with spaces, up to the next tab stop.
+fn expand_tabs(text: &str) -> String {
+    let mut expanded = String::with_capacity(text.len());
+    let mut width = 0;
+    for c in text.chars() {
+        if c == '\t' {
+            let spaces = TAB_WIDTH - width % TAB_WIDTH;
+            expanded.extend(std::iter::repeat_n(' ', spaces));
+            width += spaces;
+        } else {
+            expanded.push(c);
+            width += c.width().unwrap_or(0);
+        }
+    }
+    expanded
 }
 
 impl fmt::Display for Span {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}\n{: >2$}", self.line, '^', self.pos)
+        let (offset, width) = self.display_range();
+        write!(
+            f,
+            "line {}, column {}:\n{}\n{:offset$}{}",
+            self.line_number,
+            self.column(),
+            self.display_line(),
+            "",
+            "^".repeat(width),
+            offset = offset
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FormatErrorKind, Span};
+
+    use rules::RuleId;
+
+    #[test]
+    fn test_span_caret_after_tab() {
+        let span = Span::new("\tfeat: add tabs", 2, 1);
+        assert_eq!(
+            "line 2, column 2:\n    feat: add tabs\n    ^",
+            span.to_string()
+        );
+
+        let span = Span::new("ab\tcd", 1, 3);
+        assert_eq!("line 1, column 4:\nab  cd\n    ^", span.to_string());
+    }
+
+    #[test]
+    fn test_span_caret_after_multi_byte_scope() {
+        let span = Span::new("feat(café) add scope", 1, 11);
+        assert_eq!(
+            "line 1, column 11:\nfeat(café) add scope\n          ^",
+            span.to_string()
+        );
+    }
+
+    #[test]
+    fn test_span_covering_several_characters() {
+        let span = Span::spanning("feat(café): add scope", 1, 5, 10);
+        assert_eq!(
+            "line 1, column 6:\nfeat(café): add scope\n     ^^^^",
+            span.to_string()
+        );
+
+        let span = Span::new("feat: 🎉 Party", 1, 6);
+        assert_eq!(
+            "line 1, column 7:\nfeat: 🎉 Party\n      ^^",
+            span.to_string()
+        );
+    }
+
+    #[test]
+    fn test_span_caret_after_emoji() {
+        let span = Span::new("feat: 🎉 Party", 1, 11);
+        assert_eq!(
+            "line 1, column 9:\nfeat: 🎉 Party\n         ^",
+            span.to_string()
+        );
+    }
+
+    #[test]
+    fn test_span_inside_character() {
+        let span = Span::new("fix\u{a0}: add x", 1, 4);
+        assert_eq!((3, 5), (span.pos(), span.end()));
+        assert_eq!(4, span.column());
+
+        let span = Span::spanning("feat: 🎉 Party", 1, 8, 30);
+        assert_eq!((6, 16), (span.pos(), span.end()));
+        assert_eq!(
+            "line 1, column 7:\nfeat: 🎉 Party\n      ^^^^^^^^",
+            span.to_string()
+        );
+    }
+
+    #[test]
+    fn test_stable_codes() {
+        use super::FormatErrorKind::*;
+
+        let text = String::new;
+        let kinds = vec![
+            CapitalizedFirstLetter,
+            CommitTypeNotLowercase {
+                commit_type: text(),
+                expected: text(),
+            },
+            ConsecutiveBlankLines,
+            Custom {
+                rule: text(),
+                message: text(),
+            },
+            EmptyCommitMessage,
+            EmptyCommitSubject,
+            EmptyCommitType,
+            EmptyFooterValue,
+            EmptyScope,
+            EmojiPrefix { emoji: text() },
+            ForbiddenWord { word: text() },
+            HeaderPatternMismatch { pattern: text() },
+            InvalidCommitType {
+                commit_type: text(),
+                suggestion: None,
+            },
+            InvalidScopeFormat,
+            LeadingBlankLine,
+            LineTooLong(72),
+            MalformedCoAuthor,
+            MalformedSignOff,
+            MisplacedBreakingChangeMarker,
+            MissingBody {
+                commit_type: text(),
+            },
+            MissingIssueReference { pattern: text() },
+            MissingParenthesis,
+            MissingScope,
+            MissingSignOff,
+            MissingWhitespace,
+            MisplacedWhitespace,
+            NoColumn,
+            NonCanonicalType {
+                commit_type: text(),
+                canonical: text(),
+            },
+            NonEmptySecondLine,
+            NonImperativeSubject {
+                word: text(),
+                suggestion: None,
+            },
+            ScopeNotAllowed,
+            SubjectEchoesType,
+            SubjectTooLong(50),
+            TrailingPunctuation('.'),
+            TrailingWhitespace,
+            UnknownScope { scope: text() },
+        ];
+
+        let mut names: Vec<_> = RuleId::ALL
+            .iter()
+            .chain(&[RuleId::Custom])
+            .flat_map(|rule| rule.kind_names().iter().cloned())
+            .collect();
+        names.sort_unstable();
+        let mut kind_names: Vec<_> = kinds.iter().map(FormatErrorKind::name).collect();
+        kind_names.sort_unstable();
+        assert_eq!(kind_names, names);
+        let mut codes: Vec<_> = kinds.iter().map(FormatErrorKind::code).collect();
+        codes.sort_unstable();
+        codes.dedup();
+        assert_eq!(codes.len(), kinds.len());
+        assert_eq!(codes.first(), Some(&"VC001"));
+        assert_eq!(codes.last(), Some(&"VC036"));
+
+        assert_eq!(CapitalizedFirstLetter.code(), "VC001");
+        assert_eq!(EmptyCommitSubject.code(), "VC002");
+        assert_eq!(kinds[27].code(), "VC036");
     }
 }
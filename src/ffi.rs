@@ -0,0 +1,246 @@
+//! C bindings, declared in the `include/validate_commit.h` header.
+//!
+//! ```c
+//! #include "validate_commit.h"
+//!
+//! VcOptions options = vc_options_default();
+//! options.require_scope = true;
+//! VcResult *result = vc_validate_message("feat: add colors", &options);
+//! for (size_t i = 0; i < vc_result_error_count(result); i++) {
+//!     printf("%zu:%zu: %s\n", vc_result_error_line(result, i),
+//!            vc_result_error_column(result, i), vc_result_error_message(result, i));
+//! }
+//! vc_result_free(result);
+//! ```
+//!
+//! The shared library is built with
+//! `cargo rustc --release --lib --crate-type cdylib --features ffi`, as
+//! `target/release/libvalidate_commit.so` on Linux.
+//!
+//! The strings are UTF-8 and the strings of a result are valid until it is freed. The functions
+//! never unwind into C: a panic is reported like an invalid input, with a `NULL` result.
+//!
+//! The header is generated by [cbindgen](https://github.com/mozilla/cbindgen) with the
+//! `cbindgen.toml` configuration:
+//!
+//! ```sh
+//! cbindgen --config cbindgen.toml --output include/validate_commit.h
+//! ```
+
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+
+use options::{ScopePolicy, ValidationOptions};
+use validate_commit_message_report_with;
+
+/// Options of the validation
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VcOptions {
+    /// Maximum length of the header, 0 to disable the check
+    pub max_header_length: usize,
+    /// Maximum length of the body lines, 0 to disable the check
+    pub max_body_line_length: usize,
+    /// Require a scope in the header
+    pub require_scope: bool,
+    /// Report the warnings as errors
+    pub strict: bool,
+}
+
+/// Errors of a validated message, freed by `vc_result_free`
+pub struct VcResult {
+    errors: Vec<VcError>,
+}
+
+struct VcError {
+    kind: CString,
+    message: CString,
+    line: usize,
+    column: usize,
+}
+
+/// Default options, following the Angular convention.
+#[no_mangle]
+pub extern "C" fn vc_options_default() -> VcOptions {
+    let defaults = ValidationOptions::default();
+    VcOptions {
+        max_header_length: defaults.max_header_length,
+        max_body_line_length: defaults.max_body_line_length,
+        require_scope: defaults.scope_policy == ScopePolicy::Required,
+        strict: false,
+    }
+}
+
+/// Validate the message with the options, or the default options if `options` is `NULL`.
+///
+/// Return `NULL` if the message is `NULL` or is not valid UTF-8.
+///
+/// # Safety
+///
+/// `message` must be `NULL` or a NUL-terminated string, and `options` must be `NULL` or point to
+/// valid options.
+#[no_mangle]
+pub unsafe extern "C" fn vc_validate_message(
+    message: *const c_char,
+    options: *const VcOptions,
+) -> *mut VcResult {
+    if message.is_null() {
+        return ptr::null_mut();
+    }
+    let message = CStr::from_ptr(message);
+    let options = match options.as_ref() {
+        Some(options) => *options,
+        None => vc_options_default(),
+    };
+    catch_unwind(|| {
+        let message = message.to_str().ok()?;
+        Some(Box::into_raw(Box::new(validate(message, &options))))
+    })
+    .ok()
+    .flatten()
+    .unwrap_or(ptr::null_mut())
+}
+
+fn validate(message: &str, options: &VcOptions) -> VcResult {
+    let validation_options = ValidationOptions {
+        max_header_length: options.max_header_length,
+        max_body_line_length: options.max_body_line_length,
+        scope_policy: if options.require_scope {
+            ScopePolicy::Required
+        } else {
+            ScopePolicy::Any
+        },
+        ..ValidationOptions::default()
+    };
+    let mut report = validate_commit_message_report_with(message, &validation_options);
+    if options.strict {
+        report = report.into_strict();
+    }
+    // The messages quote the input, which has no NUL character
+    let c_string = |s: String| CString::new(s).unwrap_or_default();
+    VcResult {
+        errors: report
+            .errors
+            .iter()
+            .map(|error| VcError {
+                kind: c_string(error.kind().name().to_owned()),
+                message: c_string(error.kind().to_string()),
+                line: error.line_number().unwrap_or(0),
+                column: error.column().unwrap_or(0),
+            })
+            .collect(),
+    }
+}
+
+/// Number of errors of the result, 0 if the message is valid.
+///
+/// # Safety
+///
+/// `result` must be `NULL` or a result of `vc_validate_message` which was not freed.
+#[no_mangle]
+pub unsafe extern "C" fn vc_result_error_count(result: *const VcResult) -> usize {
+    result.as_ref().map_or(0, |result| result.errors.len())
+}
+
+/// Name of the kind of the error at the index, like `"InvalidCommitType"`, or `NULL` if there is
+/// no such error.
+///
+/// # Safety
+///
+/// `result` must be `NULL` or a result of `vc_validate_message` which was not freed.
+#[no_mangle]
+pub unsafe extern "C" fn vc_result_error_kind(
+    result: *const VcResult,
+    index: usize,
+) -> *const c_char {
+    error(result, index).map_or(ptr::null(), |error| error.kind.as_ptr())
+}
+
+/// Message of the error at the index, like `"Missing scope"`, or `NULL` if there is no such
+/// error.
+///
+/// # Safety
+///
+/// `result` must be `NULL` or a result of `vc_validate_message` which was not freed.
+#[no_mangle]
+pub unsafe extern "C" fn vc_result_error_message(
+    result: *const VcResult,
+    index: usize,
+) -> *const c_char {
+    error(result, index).map_or(ptr::null(), |error| error.message.as_ptr())
+}
+
+/// Line number of the error at the index, starting at 1, or 0 if it is unknown.
+///
+/// # Safety
+///
+/// `result` must be `NULL` or a result of `vc_validate_message` which was not freed.
+#[no_mangle]
+pub unsafe extern "C" fn vc_result_error_line(result: *const VcResult, index: usize) -> usize {
+    error(result, index).map_or(0, |error| error.line)
+}
+
+/// Column of the error at the index, starting at 1, or 0 if it is unknown.
+///
+/// # Safety
+///
+/// `result` must be `NULL` or a result of `vc_validate_message` which was not freed.
+#[no_mangle]
+pub unsafe extern "C" fn vc_result_error_column(result: *const VcResult, index: usize) -> usize {
+    error(result, index).map_or(0, |error| error.column)
+}
+
+/// Free the result, doing nothing if it is `NULL`.
+///
+/// # Safety
+///
+/// `result` must be `NULL` or a result of `vc_validate_message` which was not freed.
+#[no_mangle]
+pub unsafe extern "C" fn vc_result_free(result: *mut VcResult) {
+    if !result.is_null() {
+        let result = AssertUnwindSafe(Box::from_raw(result));
+        let _ = catch_unwind(move || drop(result));
+    }
+}
+
+unsafe fn error<'r>(result: *const VcResult, index: usize) -> Option<&'r VcError> {
+    result.as_ref()?.errors.get(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_c_strings() {
+        let options = VcOptions {
+            require_scope: true,
+            ..vc_options_default()
+        };
+        unsafe {
+            let result = vc_validate_message(b"feat: Add colors\0".as_ptr().cast(), &options);
+            assert_eq!(vc_result_error_count(result), 1);
+            let kind = CStr::from_ptr(vc_result_error_kind(result, 0));
+            assert_eq!(kind.to_str(), Ok("MissingScope"));
+            let message = CStr::from_ptr(vc_result_error_message(result, 0));
+            assert_eq!(message.to_str(), Ok("Missing scope"));
+            assert_eq!(vc_result_error_line(result, 0), 1);
+            assert_eq!(vc_result_error_column(result, 0), 5);
+            assert!(vc_result_error_message(result, 1).is_null());
+            assert_eq!(vc_result_error_column(result, 1), 0);
+            vc_result_free(result);
+
+            let result = vc_validate_message(b"feat: add colors\0".as_ptr().cast(), ptr::null());
+            assert_eq!(vc_result_error_count(result), 0);
+            vc_result_free(result);
+
+            let invalid = b"feat: add \xff\0";
+            assert!(vc_validate_message(invalid.as_ptr().cast(), ptr::null()).is_null());
+            assert!(vc_validate_message(ptr::null(), ptr::null()).is_null());
+            assert_eq!(vc_result_error_count(ptr::null()), 0);
+            vc_result_free(ptr::null_mut());
+        }
+    }
+}
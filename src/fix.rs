@@ -0,0 +1,243 @@
+//! Automatic fixes of the mechanical errors of commit messages.
+//!
+//! Only the errors whose fix cannot change the meaning of the message are fixed: the commit type
+//! and the wording of the subject are never changed.
+
+use std::fmt;
+
+use errors::{FormatError, FormatErrorKind};
+use options::ValidationOptions;
+use parse::BYTE_ORDER_MARK;
+use validate_commit_message_report_with;
+
+/// Maximum number of validation passes, as fixing an error can reveal another one
+const MAX_PASSES: usize = 8;
+
+/// Fix applied to a commit message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppliedFix {
+    /// Kind of the fixed error
+    pub kind: FormatErrorKind,
+    /// Number of the fixed line, starting from 1, in the message at the time of the fix
+    pub line_number: usize,
+}
+
+impl fmt::Display for AppliedFix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: fixed '{}'", self.line_number, self.kind)
+    }
+}
+
+/// Fix the mechanical errors of the message, and return the fixed message with the applied
+/// fixes.
+///
+/// The fixed errors are:
+/// - the capitalized first letter of the subject, unless it starts an acronym,
+/// - the missing space after the colon of the header, and the extra spaces before the subject,
+/// - the trailing whitespaces,
+/// - the non-empty second line, by inserting a blank line before it.
+///
+/// The other errors are left as they are, and are reported by the validation of the fixed
+/// message. The line endings, comments and verbose diff of the message are preserved.
+///
+/// # Examples
+///
+/// ```
+/// # use validate_commit::{fix_commit_message, FormatErrorKind, ValidationOptions};
+/// let options = ValidationOptions::default();
+/// let (message, fixes) = fix_commit_message("feat:Add JSON output  \nBody", &options);
+/// assert_eq!(message, "feat: add JSON output\n\nBody");
+/// assert_eq!(fixes.len(), 4);
+/// assert_eq!(fixes[1].kind, FormatErrorKind::MissingWhitespace);
+/// ```
+pub fn fix_commit_message(input: &str, options: &ValidationOptions) -> (String, Vec<AppliedFix>) {
+    let (bom, input) = match input.strip_prefix(BYTE_ORDER_MARK) {
+        Some(rest) => (&input[..input.len() - rest.len()], rest),
+        None => ("", input),
+    };
+    let mut lines: Vec<String> = input.split_inclusive('\n').map(str::to_owned).collect();
+    let mut fixes = Vec::new();
+
+    for _ in 0..MAX_PASSES {
+        // The byte order mark is validated as well, so that only this one is stripped
+        let message = format!("{}{}", bom, lines.concat());
+        let report = validate_commit_message_report_with(&message, options);
+        let mut errors: Vec<_> = report
+            .errors
+            .iter()
+            .chain(&report.warnings)
+            .filter(|error| is_fixable(error, &lines))
+            .collect();
+        if errors.is_empty() {
+            break;
+        }
+
+        // Fix from the end, so that the positions of the remaining errors stay valid
+        errors.sort_by_key(|error| std::cmp::Reverse(error.sort_key()));
+        for error in errors {
+            apply_fix(error, &mut lines);
+            fixes.push(AppliedFix {
+                kind: error.kind.clone(),
+                line_number: error.line_number().unwrap(),
+            });
+        }
+    }
+
+    fixes.sort_by_key(|fix| fix.line_number);
+    (format!("{}{}", bom, lines.concat()), fixes)
+}
+
+/// Check if the error can be fixed without changing the meaning of the message.
+fn is_fixable(error: &FormatError, lines: &[String]) -> bool {
+    let span = match error.span() {
+        Some(span) => span,
+        None => return false,
+    };
+    match error.kind {
+        FormatErrorKind::CapitalizedFirstLetter
+        | FormatErrorKind::MissingWhitespace
+        | FormatErrorKind::TrailingWhitespace
+        | FormatErrorKind::NonEmptySecondLine => true,
+        // Only the spaces between the colon and the subject are removed
+        FormatErrorKind::MisplacedWhitespace => {
+            let text = content(&lines[span.line_number() - 1]);
+            text[..span.pos()].ends_with(": ") && text[span.pos()..span.end()].trim().is_empty()
+        }
+        _ => false,
+    }
+}
+
+fn apply_fix(error: &FormatError, lines: &mut Vec<String>) {
+    let span = error.span().unwrap();
+    let index = span.line_number() - 1;
+    let line = &mut lines[index];
+    let (pos, end) = (span.pos(), span.end());
+    match error.kind {
+        FormatErrorKind::CapitalizedFirstLetter => {
+            let first_letter = line[pos..].chars().next().unwrap();
+            let lowercase: String = first_letter.to_lowercase().collect();
+            line.replace_range(pos..pos + first_letter.len_utf8(), &lowercase);
+        }
+        FormatErrorKind::MissingWhitespace => line.insert(pos, ' '),
+        FormatErrorKind::MisplacedWhitespace | FormatErrorKind::TrailingWhitespace => {
+            line.replace_range(pos..end, "");
+        }
+        // A second line made of whitespaces is emptied instead
+        FormatErrorKind::NonEmptySecondLine if content(line).trim().is_empty() => {
+            let ending = line[content(line).len()..].to_owned();
+            *line = ending;
+        }
+        FormatErrorKind::NonEmptySecondLine => {
+            let ending = if line.ends_with("\r\n") || lines[index - 1].ends_with("\r\n") {
+                "\r\n"
+            } else {
+                "\n"
+            };
+            lines.insert(index, ending.to_owned());
+        }
+        _ => unreachable!("{:?} cannot be fixed", error.kind),
+    }
+}
+
+/// Content of the line, without its line ending
+fn content(line: &str) -> &str {
+    let line = line.strip_suffix('\n').unwrap_or(line);
+    line.strip_suffix('\r').unwrap_or(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fix(input: &str) -> (String, Vec<FormatErrorKind>) {
+        let (message, fixes) = fix_commit_message(input, &ValidationOptions::default());
+        (message, fixes.into_iter().map(|fix| fix.kind).collect())
+    }
+
+    #[test]
+    fn keep_valid_messages() {
+        let message = "feat(cli): add fixes\n\nBody\n# Comment  \n";
+        assert_eq!(fix(message), (message.to_owned(), Vec::new()));
+    }
+
+    #[test]
+    fn fix_headers() {
+        assert_eq!(fix("feat: Add fixes").0, "feat: add fixes");
+        assert_eq!(fix("feat: JSON output").0, "feat: JSON output");
+        assert_eq!(
+            fix("fix(cli):handle tabs"),
+            (
+                "fix(cli): handle tabs".to_owned(),
+                vec![FormatErrorKind::MissingWhitespace]
+            )
+        );
+        assert_eq!(fix("fix:   handle spaces").0, "fix: handle spaces");
+        assert_eq!(fix("fix:\tHandle tabs").0, "fix: handle tabs");
+        assert_eq!(fix("fixup! feat:Add fixes").0, "fixup! feat: add fixes");
+        assert_eq!(
+            fix("feat: Éditer les messages").0,
+            "feat: éditer les messages"
+        );
+    }
+
+    #[test]
+    fn never_change_type_or_wording() {
+        assert_eq!(fix("Feat: Add fixes").0, "Feat: Add fixes");
+        assert_eq!(fix("feet: Add fixes").0, "feet: Add fixes");
+        assert_eq!(fix("feat: added fixes.").0, "feat: added fixes.");
+        assert_eq!(fix("feat( cli): add fixes").0, "feat( cli): add fixes");
+    }
+
+    #[test]
+    fn fix_body() {
+        assert_eq!(
+            fix("feat: add fixes  \nBody \n\nFooter\t\n"),
+            (
+                "feat: add fixes\n\nBody\n\nFooter\n".to_owned(),
+                vec![
+                    FormatErrorKind::TrailingWhitespace,
+                    FormatErrorKind::TrailingWhitespace,
+                    FormatErrorKind::NonEmptySecondLine,
+                    FormatErrorKind::TrailingWhitespace,
+                ]
+            )
+        );
+        assert_eq!(
+            fix("feat: add fixes\n  \nBody").0,
+            "feat: add fixes\n\nBody"
+        );
+        assert_eq!(
+            fix("\u{feff}feat: Add fixes\r\nBody\r\n").0,
+            "\u{feff}feat: add fixes\r\n\r\nBody\r\n"
+        );
+        // Only the first byte order mark is stripped, the next one being part of the header
+        assert_eq!(
+            fix("\u{feff}\u{feff}feat: x \n").0,
+            "\u{feff}\u{feff}feat: x\n"
+        );
+    }
+
+    #[test]
+    fn keep_comments_and_diff() {
+        let message = "feat: Add fixes\n# Comment  \nBody\n\
+                       # ------------------------ >8 ------------------------\n\
+                       +trailing  \n";
+        assert_eq!(
+            fix(message).0,
+            "feat: add fixes\n# Comment  \n\nBody\n\
+             # ------------------------ >8 ------------------------\n\
+             +trailing  \n"
+        );
+    }
+
+    #[test]
+    fn keep_disabled_rules() {
+        let mut options = ValidationOptions::default();
+        options
+            .disabled_rules
+            .insert(::RuleId::SubjectNotCapitalized);
+        let (message, fixes) = fix_commit_message("feat: Add fixes", &options);
+        assert_eq!(message, "feat: Add fixes");
+        assert!(fixes.is_empty());
+    }
+}
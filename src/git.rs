@@ -0,0 +1,617 @@
+//! Validation of the commits of a git repository.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use git2::{Commit, Oid, Repository, Revwalk, Sort};
+
+use bump::Bump;
+use options::ValidationOptions;
+use owned::CommitMsgBuf;
+use stats::ValidationStats;
+use {check_commit_message, validate_commit_message_report_with, ValidationReport};
+
+/// Validate the messages of the commits reachable from `to` but not from `from`, like
+/// `git log from..to`.
+///
+/// The repository is searched from `repo_path` up to the filesystem root, and the revisions can
+/// be anything understood by git, like `origin/main` or `HEAD~3`. The commits are returned from
+/// the oldest to the newest, and the merge commits are skipped if `skip_merges` is set.
+pub fn validate_commit_range<P: AsRef<Path>>(
+    repo_path: P,
+    from: &str,
+    to: &str,
+    skip_merges: bool,
+    options: &ValidationOptions,
+) -> Result<Vec<(Oid, ValidationReport)>, git2::Error> {
+    let repo = open_repository(repo_path)?;
+    let revwalk = walk_range(&repo, from, to)?;
+    validate_walk(&repo, revwalk, skip_merges, options)
+}
+
+/// Commit of a range, with the validation of its message
+#[derive(Debug, PartialEq)]
+pub struct RangeCommit {
+    /// Id of the commit
+    pub id: Oid,
+    /// Header of the message, its first non-blank line
+    pub header: String,
+    /// Report of the message, `None` if the commit is a skipped merge commit
+    pub report: Option<ValidationReport>,
+}
+
+/// Validate the messages of the commits reachable from `to` but not from `from`, keeping the
+/// skipped merge commits, for the reports listing every commit.
+///
+/// See [`validate_commit_range`].
+pub fn validate_range_commits<P: AsRef<Path>>(
+    repo_path: P,
+    from: &str,
+    to: &str,
+    skip_merges: bool,
+    options: &ValidationOptions,
+) -> Result<Vec<RangeCommit>, git2::Error> {
+    let repo = open_repository(repo_path)?;
+    let mut commits = Vec::new();
+    for oid in walk_range(&repo, from, to)? {
+        let commit = repo.find_commit(oid?)?;
+        let message = String::from_utf8_lossy(commit.message_bytes());
+        let header = message.lines().find(|line| !line.trim().is_empty());
+        let skipped = skip_merges && commit.parent_count() > 1;
+        commits.push(RangeCommit {
+            id: commit.id(),
+            header: header.unwrap_or_default().to_owned(),
+            report: if skipped {
+                None
+            } else {
+                Some(validate_commit_message_report_with(&message, options))
+            },
+        });
+    }
+    Ok(commits)
+}
+
+/// Validate the messages of all the commits reachable from `to`, like `git log to`.
+///
+/// See [`validate_commit_range`].
+pub fn validate_commit_history<P: AsRef<Path>>(
+    repo_path: P,
+    to: &str,
+    skip_merges: bool,
+    options: &ValidationOptions,
+) -> Result<Vec<(Oid, ValidationReport)>, git2::Error> {
+    let repo = open_repository(repo_path)?;
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+    revwalk.push(find_commit(&repo, to)?.id())?;
+    validate_walk(&repo, revwalk, skip_merges, options)
+}
+
+/// Recommend the bump of the version releasing the commits reachable from `to` but not from
+/// `from`, see [`recommend_bump`](::recommend_bump).
+///
+/// The commits whose header cannot be parsed, like the merge commits, are not counted. The other
+/// errors of the messages are ignored, so that an invalid breaking change still needs a major
+/// version.
+pub fn recommend_range_bump<P: AsRef<Path>>(
+    repo_path: P,
+    from: &str,
+    to: &str,
+    options: &ValidationOptions,
+) -> Result<Bump, git2::Error> {
+    let repo = open_repository(repo_path)?;
+    let mut bump = Bump::None;
+    for oid in walk_range(&repo, from, to)? {
+        let commit = repo.find_commit(oid?)?;
+        let message = String::from_utf8_lossy(commit.message_bytes());
+        if let Some(message) = check_commit_message(&message, options, &[], &mut Vec::new()) {
+            bump = bump.max(Bump::of(&message));
+        }
+    }
+    Ok(bump)
+}
+
+/// Count the valid and invalid messages of the commits reachable from `to` but not from `from`,
+/// see [`ValidationStats`](::ValidationStats).
+///
+/// The merge commits are skipped if `skip_merges` is set.
+pub fn range_stats<P: AsRef<Path>>(
+    repo_path: P,
+    from: &str,
+    to: &str,
+    skip_merges: bool,
+    options: &ValidationOptions,
+) -> Result<ValidationStats, git2::Error> {
+    let repo = open_repository(repo_path)?;
+    let mut stats = ValidationStats::new();
+    for oid in walk_range(&repo, from, to)? {
+        let commit = repo.find_commit(oid?)?;
+        if skip_merges && commit.parent_count() > 1 {
+            continue;
+        }
+        stats.add(&String::from_utf8_lossy(commit.message_bytes()), options);
+    }
+    Ok(stats)
+}
+
+/// Commits of a range, for its changelog
+#[derive(Debug, Default)]
+pub struct RangeCommits {
+    /// Commits whose header can be parsed, from the oldest to the newest
+    pub commits: Vec<(Oid, CommitMsgBuf)>,
+    /// Commits left out of the changelog, with the reason
+    pub skipped: Vec<(Oid, SkipReason)>,
+}
+
+/// Reason of a commit being left out of a changelog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    /// The commit has several parents
+    Merge,
+    /// The header of the message cannot be parsed
+    NonConforming,
+}
+
+impl SkipReason {
+    /// Name of the reason, like `merge`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SkipReason::Merge => "merge",
+            SkipReason::NonConforming => "non-conforming",
+        }
+    }
+}
+
+/// Read the commits reachable from `to` but not from `from`, to write their changelog with
+/// [`markdown_changelog`](::markdown_changelog).
+///
+/// Like for [`recommend_range_bump`], the commits are kept as long as their header can be
+/// parsed. The merge commits are skipped.
+pub fn read_commit_range<P: AsRef<Path>>(
+    repo_path: P,
+    from: &str,
+    to: &str,
+    options: &ValidationOptions,
+) -> Result<RangeCommits, git2::Error> {
+    let repo = open_repository(repo_path)?;
+    let mut range = RangeCommits::default();
+    for oid in walk_range(&repo, from, to)? {
+        let commit = repo.find_commit(oid?)?;
+        if commit.parent_count() > 1 {
+            range.skipped.push((commit.id(), SkipReason::Merge));
+            continue;
+        }
+        let message = String::from_utf8_lossy(commit.message_bytes());
+        match check_commit_message(&message, options, &[], &mut Vec::new()) {
+            Some(message) => range.commits.push((commit.id(), message.to_owned())),
+            None => range.skipped.push((commit.id(), SkipReason::NonConforming)),
+        }
+    }
+    Ok(range)
+}
+
+/// Validate the message of the commit designated by the revision, like `HEAD` or `v1.0`.
+///
+/// The repository is searched from `repo_path` up to the filesystem root.
+pub fn validate_commit_rev<P: AsRef<Path>>(
+    repo_path: P,
+    rev: &str,
+    options: &ValidationOptions,
+) -> Result<(Oid, ValidationReport), git2::Error> {
+    let repo = open_repository(repo_path)?;
+    let commit = find_commit(&repo, rev)?;
+    Ok((commit.id(), validate_commit(&commit, options)))
+}
+
+/// Update of a reference, as received by the `pre-receive` hook
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefUpdate {
+    /// Previous target of the reference, zero if the reference is created
+    pub old: Oid,
+    /// New target of the reference, zero if the reference is deleted
+    pub new: Oid,
+    /// Full name of the reference, like `refs/heads/main`
+    pub name: String,
+}
+
+impl RefUpdate {
+    /// Parse the `<old-sha> <new-sha> <ref>` lines given to the `pre-receive` hook.
+    pub fn parse_all(input: &str) -> Result<Vec<RefUpdate>, git2::Error> {
+        input
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(
+                |line| match line.split_whitespace().collect::<Vec<_>>()[..] {
+                    [old, new, name] => Ok(RefUpdate {
+                        old: Oid::from_str(old)?,
+                        new: Oid::from_str(new)?,
+                        name: name.to_owned(),
+                    }),
+                    _ => Err(git2::Error::from_str(&format!(
+                        "Invalid reference update '{}'",
+                        line
+                    ))),
+                },
+            )
+            .collect()
+    }
+}
+
+/// Validate the messages of the commits introduced by the reference updates of a push.
+///
+/// The deleted references and the tags are skipped, and the commits already reachable from a
+/// reference of the repository are not validated again. Each commit is validated once, even if
+/// several references are updated to include it.
+pub fn validate_ref_updates<P: AsRef<Path>>(
+    repo_path: P,
+    updates: &[RefUpdate],
+    skip_merges: bool,
+    options: &ValidationOptions,
+) -> Result<Vec<(Oid, ValidationReport)>, git2::Error> {
+    let repo = open_repository(repo_path)?;
+    // The references are not updated yet when the pre-receive hook runs
+    let mut existing = Vec::new();
+    for reference in repo.references()? {
+        if let Ok(commit) = reference?.peel_to_commit() {
+            existing.push(commit.id());
+        }
+    }
+
+    let mut validated = HashSet::new();
+    let mut reports = Vec::new();
+    for update in updates {
+        if update.new.is_zero() || update.name.starts_with("refs/tags/") {
+            continue;
+        }
+
+        let mut revwalk = repo.revwalk()?;
+        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+        revwalk.push(update.new)?;
+        for &oid in &existing {
+            revwalk.hide(oid)?;
+        }
+        for oid in revwalk {
+            let commit = repo.find_commit(oid?)?;
+            if (skip_merges && commit.parent_count() > 1) || !validated.insert(commit.id()) {
+                continue;
+            }
+            reports.push((commit.id(), validate_commit(&commit, options)));
+        }
+    }
+    Ok(reports)
+}
+
+/// Open the repository containing the given directory.
+///
+/// When run from a `pre-receive` hook, the pushed objects are read from the quarantine directory
+/// given by git in the `GIT_QUARANTINE_PATH` environment variable.
+pub fn open_repository<P: AsRef<Path>>(repo_path: P) -> Result<Repository, git2::Error> {
+    let repo = Repository::discover(repo_path)?;
+    if let Some(quarantine_path) = std::env::var_os("GIT_QUARANTINE_PATH") {
+        repo.odb()?
+            .add_disk_alternate(&Path::new(&quarantine_path).to_string_lossy())?;
+    }
+    Ok(repo)
+}
+
+/// Walk the commits reachable from `to` but not from `from`, from the oldest to the newest.
+fn walk_range<'r>(repo: &'r Repository, from: &str, to: &str) -> Result<Revwalk<'r>, git2::Error> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+    revwalk.push(find_commit(repo, to)?.id())?;
+    revwalk.hide(find_commit(repo, from)?.id())?;
+    Ok(revwalk)
+}
+
+/// Find the commit designated by the revision, peeling the annotated tags.
+fn find_commit<'r>(repo: &'r Repository, rev: &str) -> Result<Commit<'r>, git2::Error> {
+    let object = repo.revparse_single(rev).map_err(|e| {
+        if repo.is_empty().unwrap_or(false) {
+            git2::Error::from_str("The repository has no commits")
+        } else {
+            git2::Error::from_str(&format!("Unknown revision '{}': {}", rev, e.message()))
+        }
+    })?;
+    object
+        .peel_to_commit()
+        .map_err(|_| git2::Error::from_str(&format!("Revision '{}' is not a commit", rev)))
+}
+
+fn validate_walk(
+    repo: &Repository,
+    revwalk: Revwalk,
+    skip_merges: bool,
+    options: &ValidationOptions,
+) -> Result<Vec<(Oid, ValidationReport)>, git2::Error> {
+    let mut reports = Vec::new();
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        if skip_merges && commit.parent_count() > 1 {
+            continue;
+        }
+        reports.push((commit.id(), validate_commit(&commit, options)));
+    }
+    Ok(reports)
+}
+
+fn validate_commit(commit: &Commit, options: &ValidationOptions) -> ValidationReport {
+    let message = String::from_utf8_lossy(commit.message_bytes());
+    validate_commit_message_report_with(&message, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+    use std::path::PathBuf;
+
+    use git2::Signature;
+
+    /// Create a repository in a new temporary directory.
+    fn init_repository(name: &str) -> (PathBuf, Repository) {
+        let dir = std::env::temp_dir().join(format!(
+            "validate-commit-git-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        let repo = Repository::init(&dir).unwrap();
+        (dir, repo)
+    }
+
+    fn commit(repo: &Repository, message: &str, parents: &[Oid]) -> Oid {
+        let signature = Signature::now("Test", "test@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.treebuilder(None).unwrap().write().unwrap())
+            .unwrap();
+        let parents: Vec<_> = parents
+            .iter()
+            .map(|&oid| repo.find_commit(oid).unwrap())
+            .collect();
+        let parents: Vec<_> = parents.iter().collect();
+        repo.commit(None, &signature, &signature, message, &tree, &parents)
+            .unwrap()
+    }
+
+    #[test]
+    fn validate_commits_of_range() {
+        let (dir, repo) = init_repository("range");
+        let base = commit(&repo, "Initial commit", &[]);
+        let feat = commit(&repo, "feat: add colors", &[base]);
+        let fix = commit(&repo, "fix: Handle empty input.", &[feat]);
+        let side = commit(&repo, "docs: document colors", &[base]);
+        let merge = commit(&repo, "Merge feature", &[fix, side]);
+        repo.reference("refs/heads/feature", merge, true, "test")
+            .unwrap();
+
+        let options = ValidationOptions {
+            validate_merges: true,
+            ..ValidationOptions::default()
+        };
+        let from = base.to_string();
+        let reports =
+            validate_commit_range(dir.join(".git"), &from, "feature", true, &options).unwrap();
+        let oids: Vec<_> = reports.iter().map(|&(oid, _)| oid).collect();
+        assert_eq!(oids.len(), 3);
+        assert!(oids.contains(&feat) && oids.contains(&fix) && oids.contains(&side));
+        assert!(oids.iter().position(|&oid| oid == feat) < oids.iter().position(|&oid| oid == fix));
+        for (oid, report) in &reports {
+            assert_eq!(report.is_ok(), *oid != fix, "{}", oid);
+        }
+
+        let reports = validate_commit_range(&dir, &from, "feature", false, &options).unwrap();
+        assert_eq!(reports.len(), 4);
+        assert_eq!(reports[3].0, merge);
+        assert!(!reports[3].1.is_ok());
+
+        assert!(validate_commit_range(&dir, &from, "unknown", true, &options).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn keep_skipped_merges_of_range() {
+        let (dir, repo) = init_repository("range-commits");
+        let base = commit(&repo, "Initial commit", &[]);
+        let fix = commit(&repo, "\nfix: Handle empty input.\n", &[base]);
+        let side = commit(&repo, "docs: document colors", &[base]);
+        let merge = commit(&repo, "Merge feature", &[fix, side]);
+
+        let options = ValidationOptions::default();
+        let commits =
+            validate_range_commits(&dir, &base.to_string(), &merge.to_string(), true, &options)
+                .unwrap();
+        assert_eq!(commits.len(), 3);
+        let fix = commits.iter().find(|commit| commit.id == fix).unwrap();
+        assert_eq!(fix.header, "fix: Handle empty input.");
+        assert!(!fix.report.as_ref().unwrap().is_ok());
+        assert_eq!(
+            commits[2],
+            RangeCommit {
+                id: merge,
+                header: "Merge feature".to_owned(),
+                report: None,
+            }
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn validate_whole_history() {
+        let (dir, repo) = init_repository("history");
+        let base = commit(&repo, "Add the project", &[]);
+        let feat = commit(&repo, "feat: add colors", &[base]);
+
+        let options = ValidationOptions::default();
+        let reports = validate_commit_history(&dir, &feat.to_string(), true, &options).unwrap();
+        let oids: Vec<_> = reports.iter().map(|&(oid, _)| oid).collect();
+        assert_eq!(oids, vec![base, feat]);
+        assert!(!reports[0].1.is_ok());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn recommend_bump_of_range() {
+        let (dir, repo) = init_repository("bump");
+        let base = commit(&repo, "Initial commit", &[]);
+        let docs = commit(&repo, "docs: document colors", &[base]);
+        let fix = commit(&repo, "fix: Handle empty input.", &[docs]);
+        let feat = commit(&repo, "feat: add colors", &[fix]);
+        let breaking = commit(&repo, "feat!: Drop the old API", &[feat]);
+
+        let options = ValidationOptions::default();
+        let bump = |from: Oid, to: Oid| {
+            recommend_range_bump(&dir, &from.to_string(), &to.to_string(), &options).unwrap()
+        };
+        assert_eq!(bump(base, docs), Bump::None);
+        assert_eq!(bump(base, fix), Bump::Patch);
+        assert_eq!(bump(docs, feat), Bump::Minor);
+        assert_eq!(bump(fix, breaking), Bump::Major);
+        assert_eq!(bump(breaking, breaking), Bump::None);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn count_commits_of_range() {
+        let (dir, repo) = init_repository("stats");
+        let base = commit(&repo, "Initial commit", &[]);
+        let feat = commit(&repo, "feat: add colors", &[base]);
+        let fix = commit(&repo, "fix: Handle empty input", &[feat]);
+        let side = commit(&repo, "docs: document colors", &[base]);
+        let merge = commit(&repo, "Merge branch 'docs'", &[fix, side]);
+
+        let options = ValidationOptions::default();
+        let (from, to) = (base.to_string(), merge.to_string());
+        let stats = range_stats(&dir, &from, &to, true, &options).unwrap();
+        assert_eq!((stats.passed, stats.failed), (2, 1));
+        assert_eq!(stats.failures["CapitalizedFirstLetter"], 1);
+        assert_eq!(stats.types.len(), 2);
+
+        let stats = range_stats(&dir, &from, &to, false, &options).unwrap();
+        assert_eq!((stats.passed, stats.failed), (3, 1));
+        assert_eq!(stats.types.values().sum::<usize>(), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn read_commits_of_range() {
+        let (dir, repo) = init_repository("changelog");
+        let base = commit(&repo, "Initial commit", &[]);
+        let feat = commit(&repo, "feat(cli): add colors", &[base]);
+        let wip = commit(&repo, "WIP", &[feat]);
+        let side = commit(&repo, "fix: handle empty input", &[base]);
+        let merge = commit(&repo, "Merge branch 'fix'", &[wip, side]);
+
+        let from = base.to_string();
+        let to = merge.to_string();
+        let range = read_commit_range(&dir, &from, &to, &ValidationOptions::default()).unwrap();
+        let oids: Vec<_> = range.commits.iter().map(|&(oid, _)| oid).collect();
+        assert_eq!(oids.len(), 2);
+        assert!(oids.contains(&feat) && oids.contains(&side));
+        let (_, ref msg) = range.commits[oids.iter().position(|&oid| oid == feat).unwrap()];
+        assert_eq!(msg.header.scope.as_deref(), Some("cli"));
+        assert_eq!(msg.header.subject, "add colors");
+        assert_eq!(
+            range.skipped,
+            vec![(wip, SkipReason::NonConforming), (merge, SkipReason::Merge)]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn validate_pushed_commits() {
+        let (dir, repo) = init_repository("pre-receive");
+        let base = commit(&repo, "feat: add colors", &[]);
+        repo.reference("refs/heads/master", base, true, "test")
+            .unwrap();
+        repo.reference("refs/heads/old", base, true, "test")
+            .unwrap();
+        let fix = commit(&repo, "fix: Handle empty input", &[base]);
+        let docs = commit(&repo, "docs: document colors", &[fix]);
+        let feature = commit(&repo, "feature: add scopes", &[docs]);
+        let release = commit(&repo, "Release 1.0", &[base]);
+
+        let zero = Oid::zero();
+        let input = format!(
+            "{} {} refs/heads/master\n{} {} refs/heads/feature\n\
+             {} {} refs/tags/v1.0\n{} {} refs/heads/old\n",
+            base, docs, zero, feature, zero, release, base, zero
+        );
+        let updates = RefUpdate::parse_all(&input).unwrap();
+        assert_eq!(updates.len(), 4);
+        assert_eq!(
+            updates[0],
+            RefUpdate {
+                old: base,
+                new: docs,
+                name: "refs/heads/master".to_owned()
+            }
+        );
+        assert!(RefUpdate::parse_all("1234 refs/heads/master").is_err());
+
+        let options = ValidationOptions::default();
+        let reports = validate_ref_updates(&dir, &updates, true, &options).unwrap();
+        let oids: Vec<_> = reports.iter().map(|&(oid, _)| oid).collect();
+        assert_eq!(oids, vec![fix, docs, feature]);
+        let invalid: Vec<_> = reports
+            .iter()
+            .filter(|(_, report)| !report.is_ok())
+            .map(|&(oid, _)| oid)
+            .collect();
+        assert_eq!(invalid, vec![fix, feature]);
+
+        // The commits already reachable from a reference are not validated again
+        repo.reference("refs/heads/master", docs, true, "test")
+            .unwrap();
+        let reports = validate_ref_updates(&dir, &updates[1..2], true, &options).unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].0, feature);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn validate_single_commits() {
+        let (dir, repo) = init_repository("rev");
+        let options = ValidationOptions::default();
+        let error = validate_commit_rev(&dir, "HEAD", &options).unwrap_err();
+        assert_eq!(error.message(), "The repository has no commits");
+
+        let feat = commit(&repo, "feat: add colors", &[]);
+        let fix = commit(&repo, "fix: Handle empty input", &[feat]);
+        repo.reference("refs/heads/master", fix, true, "test")
+            .unwrap();
+        repo.set_head("refs/heads/master").unwrap();
+
+        let (oid, report) = validate_commit_rev(&dir, "HEAD", &options).unwrap();
+        assert_eq!(oid, fix);
+        assert!(!report.is_ok());
+        let (oid, report) = validate_commit_rev(&dir, "HEAD~1", &options).unwrap();
+        assert_eq!(oid, feat);
+        assert!(report.is_ok());
+
+        // Detached HEAD and annotated tags
+        repo.set_head_detached(feat).unwrap();
+        assert_eq!(validate_commit_rev(&dir, "HEAD", &options).unwrap().0, feat);
+        let signature = Signature::now("Test", "test@example.com").unwrap();
+        let object = repo.find_object(fix, None).unwrap();
+        repo.tag("v1.0", &object, &signature, "Release 1.0", false)
+            .unwrap();
+        assert_eq!(validate_commit_rev(&dir, "v1.0", &options).unwrap().0, fix);
+
+        let tree = repo.find_commit(fix).unwrap().tree_id().to_string();
+        let error = validate_commit_rev(&dir, &tree, &options).unwrap_err();
+        assert_eq!(
+            error.message(),
+            format!("Revision '{}' is not a commit", tree)
+        );
+        let error = validate_commit_rev(&dir, "unknown", &options).unwrap_err();
+        assert!(error.message().starts_with("Unknown revision 'unknown'"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
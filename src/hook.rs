@@ -0,0 +1,321 @@
+//! Installation of the `commit-msg` git hook running validate-commit.
+//!
+//! The hook is written in the hooks directory of the repository, which is `.git/hooks` unless
+//! `core.hooksPath` is set. In a worktree, the hooks of the main repository are used.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use errors::HookError;
+
+/// Name of the hook validating the commit messages
+pub const HOOK_NAME: &str = "commit-msg";
+
+/// Comment identifying the hooks installed by validate-commit
+pub const HOOK_MARKER: &str = "# Installed by validate-commit";
+
+/// Environment variable skipping the validation in the installed hook, for emergency commits
+pub const NO_VERIFY_VAR: &str = "VALIDATE_COMMIT_NO_VERIFY";
+
+/// Find the hooks directory of the repository containing the given directory.
+pub fn find_hooks_dir<P: AsRef<Path>>(dir: P) -> Result<PathBuf, HookError> {
+    let dir = dir.as_ref();
+    let (work_tree, git_dir) = dir
+        .ancestors()
+        .map(|dir| (dir, dir.join(".git")))
+        .find(|(_, git_dir)| git_dir.exists())
+        .ok_or_else(|| HookError::NotARepository(dir.to_owned()))?;
+
+    // A worktree has a `.git` file pointing to its git directory, which points to the common one
+    let git_dir = if git_dir.is_file() {
+        let content = read_file(&git_dir)?;
+        let path = content
+            .trim()
+            .strip_prefix("gitdir:")
+            .ok_or_else(|| HookError::io(&git_dir, "missing gitdir"))?;
+        work_tree.join(path.trim())
+    } else {
+        git_dir
+    };
+    let common_dir = match fs::read_to_string(git_dir.join("commondir")) {
+        Ok(path) => {
+            let common_dir = git_dir.join(path.trim());
+            fs::canonicalize(&common_dir).map_err(|e| HookError::io(&common_dir, e))?
+        }
+        Err(_) => git_dir,
+    };
+
+    let config_path = common_dir.join("config");
+    let hooks_path = match fs::read_to_string(&config_path) {
+        Ok(config) => find_hooks_path(&config),
+        Err(_) => None,
+    };
+    Ok(match hooks_path {
+        Some(path) => match path.strip_prefix("~/") {
+            Some(path) => home_dir()
+                .ok_or_else(|| HookError::io(&config_path, "cannot expand '~'"))?
+                .join(path),
+            // A relative path is relative to the root of the working tree
+            None => work_tree.join(path),
+        },
+        None => common_dir.join("hooks"),
+    })
+}
+
+/// Install the hook running the given executable in the repository containing the given
+/// directory, and return its path.
+///
+/// An existing hook is replaced only if it was installed by validate-commit or if `force` is
+/// set, in which case it is backed up to `commit-msg.bak`. The hook does nothing when
+/// `VALIDATE_COMMIT_NO_VERIFY` is set to a value other than empty or `0`.
+pub fn install_hook<P: AsRef<Path>>(
+    dir: P,
+    executable: &Path,
+    force: bool,
+) -> Result<PathBuf, HookError> {
+    let hooks_dir = find_hooks_dir(dir)?;
+    let hook_path = hooks_dir.join(HOOK_NAME);
+    if hook_path.exists() && !is_installed_hook(&hook_path)? {
+        if !force {
+            return Err(HookError::HookExists(hook_path));
+        }
+        fs::rename(&hook_path, backup_path(&hook_path))
+            .map_err(|e| HookError::io(&hook_path, e))?;
+    }
+
+    fs::create_dir_all(&hooks_dir).map_err(|e| HookError::io(&hooks_dir, e))?;
+    let script = format!(
+        "#!/bin/sh\n{}\ncase \"${}\" in\n    \"\" | 0) ;;\n    *) exit 0 ;;\nesac\nexec {} \"$1\"\n",
+        HOOK_MARKER,
+        NO_VERIFY_VAR,
+        shell_quote(&executable.to_string_lossy())
+    );
+    fs::write(&hook_path, script).map_err(|e| HookError::io(&hook_path, e))?;
+    set_executable(&hook_path)?;
+    Ok(hook_path)
+}
+
+/// Remove the hook installed by validate-commit in the repository containing the given
+/// directory, and return its path.
+///
+/// The hook backed up when installing with `force` is restored.
+pub fn uninstall_hook<P: AsRef<Path>>(dir: P) -> Result<PathBuf, HookError> {
+    let hook_path = find_hooks_dir(dir)?.join(HOOK_NAME);
+    if !hook_path.exists() || !is_installed_hook(&hook_path)? {
+        return Err(HookError::NotInstalled(hook_path));
+    }
+
+    fs::remove_file(&hook_path).map_err(|e| HookError::io(&hook_path, e))?;
+    let backup_path = backup_path(&hook_path);
+    if backup_path.exists() {
+        fs::rename(&backup_path, &hook_path).map_err(|e| HookError::io(&backup_path, e))?;
+    }
+    Ok(hook_path)
+}
+
+/// Return the value of `core.hooksPath` in the content of a git configuration file.
+fn find_hooks_path(config: &str) -> Option<String> {
+    let mut in_core = false;
+    let mut hooks_path = None;
+    for line in config.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_core = line.eq_ignore_ascii_case("[core]");
+        } else if let Some((key, value)) = line.split_once('=') {
+            if in_core && key.trim().eq_ignore_ascii_case("hooksPath") {
+                // The last value wins, like with git
+                hooks_path = Some(value.trim().trim_matches('"').to_owned());
+            }
+        }
+    }
+    hooks_path
+}
+
+fn is_installed_hook(path: &Path) -> Result<bool, HookError> {
+    Ok(read_file(path)?.lines().any(|line| line == HOOK_MARKER))
+}
+
+fn read_file(path: &Path) -> Result<String, HookError> {
+    fs::read_to_string(path).map_err(|e| HookError::io(path, e))
+}
+
+fn backup_path(hook_path: &Path) -> PathBuf {
+    hook_path.with_extension("bak")
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(PathBuf::from)
+}
+
+/// Quote the argument for a POSIX shell.
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+#[cfg(unix)]
+fn set_executable(path: &Path) -> Result<(), HookError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755)).map_err(|e| HookError::io(path, e))
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &Path) -> Result<(), HookError> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::process::Command;
+
+    /// Create an empty repository in a new temporary directory.
+    fn init_repository(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("validate-commit-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        git(&dir, &["init", "--quiet"]);
+        fs::canonicalize(dir).unwrap()
+    }
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {:?}", args);
+    }
+
+    #[test]
+    fn install_and_uninstall_hook() {
+        let repo = init_repository("install");
+        let executable = Path::new("/usr/bin/validate-commit");
+        let hook_path = repo.join(".git").join("hooks").join(HOOK_NAME);
+        let sub_dir = repo.join("src");
+        fs::create_dir(&sub_dir).unwrap();
+
+        assert_eq!(
+            install_hook(&sub_dir, executable, false).unwrap(),
+            hook_path
+        );
+        let script = fs::read_to_string(&hook_path).unwrap();
+        assert!(script.starts_with("#!/bin/sh\n"));
+        assert!(script.contains("exec '/usr/bin/validate-commit' \"$1\""));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&hook_path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o111, 0o111);
+        }
+
+        // The validation is skipped with the environment variable
+        install_hook(&repo, Path::new("false"), false).unwrap();
+        let run_hook = |no_verify: Option<&str>| {
+            let mut command = Command::new("sh");
+            command.arg(&hook_path).arg("COMMIT_EDITMSG");
+            match no_verify {
+                Some(value) => command.env(NO_VERIFY_VAR, value),
+                None => command.env_remove(NO_VERIFY_VAR),
+            };
+            command.status().unwrap().success()
+        };
+        assert!(!run_hook(None));
+        assert!(!run_hook(Some("0")));
+        assert!(run_hook(Some("1")));
+
+        // The hook installed by validate-commit can be replaced
+        assert!(install_hook(&repo, executable, false).is_ok());
+        assert_eq!(uninstall_hook(&repo).unwrap(), hook_path);
+        assert!(!hook_path.exists());
+        assert!(matches!(
+            uninstall_hook(&repo),
+            Err(HookError::NotInstalled(_))
+        ));
+
+        fs::remove_dir_all(&repo).unwrap();
+    }
+
+    #[test]
+    fn keep_existing_hook() {
+        let repo = init_repository("existing");
+        let executable = Path::new("/usr/bin/validate-commit");
+        let hook_path = repo.join(".git").join("hooks").join(HOOK_NAME);
+        let backup_path = hook_path.with_extension("bak");
+        fs::write(&hook_path, "#!/bin/sh\nexit 0\n").unwrap();
+
+        assert!(matches!(
+            install_hook(&repo, executable, false),
+            Err(HookError::HookExists(_))
+        ));
+        assert!(matches!(
+            uninstall_hook(&repo),
+            Err(HookError::NotInstalled(_))
+        ));
+        assert_eq!(
+            fs::read_to_string(&hook_path).unwrap(),
+            "#!/bin/sh\nexit 0\n"
+        );
+
+        install_hook(&repo, executable, true).unwrap();
+        assert_eq!(
+            fs::read_to_string(&backup_path).unwrap(),
+            "#!/bin/sh\nexit 0\n"
+        );
+        uninstall_hook(&repo).unwrap();
+        assert_eq!(
+            fs::read_to_string(&hook_path).unwrap(),
+            "#!/bin/sh\nexit 0\n"
+        );
+        assert!(!backup_path.exists());
+
+        fs::remove_dir_all(&repo).unwrap();
+    }
+
+    #[test]
+    fn find_hooks_of_worktrees_and_configured_hooks() {
+        let repo = init_repository("worktree");
+        git(
+            &repo,
+            &[
+                "-c",
+                "user.name=Test",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "--quiet",
+                "--allow-empty",
+                "-m",
+                "feat: initial commit",
+            ],
+        );
+        let worktree = repo.join("worktree");
+        git(&repo, &["worktree", "add", "--quiet", "worktree"]);
+        let hooks_dir = repo.join(".git").join("hooks");
+        assert_eq!(find_hooks_dir(&worktree).unwrap(), hooks_dir);
+
+        git(&repo, &["config", "core.hooksPath", ".githooks"]);
+        assert_eq!(find_hooks_dir(&repo).unwrap(), repo.join(".githooks"));
+        assert_eq!(
+            find_hooks_dir(&worktree).unwrap(),
+            worktree.join(".githooks")
+        );
+
+        fs::remove_dir_all(&repo).unwrap();
+    }
+
+    #[test]
+    fn parse_hooks_path() {
+        assert_eq!(find_hooks_path("[core]\n\tbare = false\n"), None);
+        assert_eq!(
+            find_hooks_path("[core]\n\thooksPath = .githooks\n"),
+            Some(".githooks".to_owned())
+        );
+        assert_eq!(
+            find_hooks_path("[core]\n\thookspath = \"/opt/hooks\"\n[user]\n\thooksPath = a\n"),
+            Some("/opt/hooks".to_owned())
+        );
+        assert_eq!(find_hooks_path("[user]\n\thooksPath = .githooks\n"), None);
+    }
+}
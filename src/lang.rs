@@ -0,0 +1,629 @@
+//! Translations of the error messages.
+//!
+//! The messages of each language are a static catalog keyed by the name of the
+//! [`FormatErrorKind`], whose `{placeholders}` are replaced by the fields of the kind. The English
+//! messages are the [`Display`](std::fmt::Display) implementation of the kinds, and the fallback
+//! for the kinds missing from a catalog. Only the messages are translated: the kinds, the rule
+//! identifiers and the spans stay the same in every language.
+
+use std::fmt;
+use std::str::FromStr;
+
+use errors::FormatErrorKind;
+
+/// Language of the error messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+    /// The default language, in which all the messages are written
+    #[default]
+    English,
+    /// French
+    French,
+}
+
+impl Language {
+    /// All the languages
+    pub const ALL: [Language; 2] = [Language::English, Language::French];
+
+    /// ISO 639-1 code of the language, like `"fr"`
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::French => "fr",
+        }
+    }
+
+    /// Language of a POSIX locale like `fr_FR.UTF-8`, or `None` if it is not supported.
+    ///
+    /// The `C` and `POSIX` locales are English.
+    pub fn from_locale(locale: &str) -> Option<Language> {
+        match locale {
+            "C" | "POSIX" => return Some(Language::English),
+            _ => {}
+        }
+        let code = locale
+            .split(['_', '.', '@', '-'])
+            .next()
+            .unwrap_or_default();
+        code.to_ascii_lowercase().parse().ok()
+    }
+
+    /// Detect the language from the locale variables among the given ones, like
+    /// `std::env::vars()`, falling back to English.
+    ///
+    /// Like `gettext`, `LC_ALL` takes precedence over `LC_MESSAGES`, which takes precedence over
+    /// `LANG`, and the empty variables are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use validate_commit::Language;
+    /// let vars = vec![("LANG", "fr_FR.UTF-8"), ("LC_MESSAGES", "")];
+    /// assert_eq!(Language::detect(vars), Language::French);
+    /// assert_eq!(Language::detect(vec![("LC_ALL", "C"), ("LANG", "fr")]), Language::English);
+    /// ```
+    pub fn detect<I, K, V>(vars: I) -> Language
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        const VARIABLES: [&str; 3] = ["LC_ALL", "LC_MESSAGES", "LANG"];
+        let mut locales: [Option<String>; 3] = Default::default();
+        for (name, value) in vars {
+            let (name, value) = (name.as_ref(), value.as_ref());
+            if let Some(i) = VARIABLES.iter().position(|&variable| variable == name) {
+                if !value.is_empty() {
+                    locales[i] = Some(value.to_owned());
+                }
+            }
+        }
+        locales
+            .iter()
+            .flatten()
+            .next()
+            .and_then(|locale| Language::from_locale(locale))
+            .unwrap_or_default()
+    }
+
+    fn catalog(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Language::English => &[],
+            Language::French => &FRENCH,
+        }
+    }
+
+    /// Template of the suggestion in the language, or `None` if it has no translation, the English
+    /// suggestions being the fallback.
+    pub(crate) fn suggestion_template(self, key: &str) -> Option<&'static str> {
+        let catalog: &[(&str, &str)] = match self {
+            Language::English => &[],
+            Language::French => &FRENCH_SUGGESTIONS,
+        };
+        catalog
+            .iter()
+            .find(|&&(name, _)| name == key)
+            .map(|&(_, template)| template)
+    }
+}
+
+impl FromStr for Language {
+    type Err = UnknownLanguageError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Language::ALL
+            .iter()
+            .cloned()
+            .find(|language| language.code() == s)
+            .ok_or_else(|| UnknownLanguageError(s.to_owned()))
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// Error returned when parsing an unknown language code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownLanguageError(String);
+
+impl fmt::Display for UnknownLanguageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let codes: Vec<_> = Language::ALL
+            .iter()
+            .map(|language| language.code())
+            .collect();
+        write!(
+            f,
+            "Unknown language '{}', expected one of: {}",
+            self.0,
+            codes.join(", ")
+        )
+    }
+}
+
+impl FormatErrorKind {
+    /// Message of the error in the language, in English if it has no translation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use validate_commit::{FormatErrorKind, Language};
+    /// let kind = FormatErrorKind::LineTooLong(72);
+    /// assert_eq!(kind.message(Language::English), kind.to_string());
+    /// assert_eq!(
+    ///     kind.message(Language::French),
+    ///     "La ligne ne doit pas dépasser 72 caractères"
+    /// );
+    /// ```
+    pub fn message(&self, language: Language) -> String {
+        let key = self.catalog_key();
+        let template = language
+            .catalog()
+            .iter()
+            .find(|&&(name, _)| name == key)
+            .map(|&(_, template)| template);
+        match template {
+            Some(template) => fill_template(template, &self.arguments()),
+            None => self.to_string(),
+        }
+    }
+
+    /// Key of the message in the catalogs, the name of the kind followed by `.suggestion` for the
+    /// messages suggesting a replacement.
+    fn catalog_key(&self) -> &'static str {
+        match self {
+            FormatErrorKind::InvalidCommitType {
+                suggestion: Some(_),
+                ..
+            } => "InvalidCommitType.suggestion",
+            FormatErrorKind::NonImperativeSubject {
+                suggestion: Some(_),
+                ..
+            } => "NonImperativeSubject.suggestion",
+            kind => kind.name(),
+        }
+    }
+
+    /// Values of the placeholders of the messages.
+    fn arguments(&self) -> Vec<(&'static str, String)> {
+        use self::FormatErrorKind::*;
+
+        match self {
+            CommitTypeNotLowercase {
+                commit_type,
+                expected,
+            } => vec![
+                ("commit_type", commit_type.clone()),
+                ("expected", expected.clone()),
+            ],
+            EmojiPrefix { emoji } => vec![("emoji", emoji.clone())],
+            ForbiddenWord { word } => vec![("word", word.clone())],
+            HeaderPatternMismatch { pattern } | MissingIssueReference { pattern } => {
+                vec![("pattern", pattern.clone())]
+            }
+            InvalidCommitType {
+                commit_type,
+                suggestion,
+            } => vec![
+                ("commit_type", commit_type.clone()),
+                ("suggestion", suggestion.clone().unwrap_or_default()),
+            ],
+            LineTooLong(max) | SubjectTooLong(max) => vec![("max", max.to_string())],
+            MissingBody { commit_type } => vec![("commit_type", commit_type.clone())],
+            NonCanonicalType {
+                commit_type,
+                canonical,
+            } => vec![
+                ("commit_type", commit_type.clone()),
+                ("canonical", canonical.clone()),
+            ],
+            NonImperativeSubject { word, suggestion } => vec![
+                ("word", word.clone()),
+                ("suggestion", suggestion.clone().unwrap_or_default()),
+            ],
+            TrailingPunctuation(c) => vec![("char", c.to_string())],
+            UnknownScope { scope } => vec![("scope", scope.clone())],
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Replace the `{name}` placeholders of the template by the values of the arguments.
+///
+/// The template is read once, so that the values are copied as they are, even if they contain a
+/// placeholder.
+pub(crate) fn fill_template(template: &str, arguments: &[(&str, String)]) -> String {
+    let mut message = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        message.push_str(&rest[..start]);
+        let placeholder = &rest[start + 1..];
+        let argument = placeholder.find('}').and_then(|end| {
+            let name = &placeholder[..end];
+            arguments
+                .iter()
+                .find(|&&(argument, _)| argument == name)
+                .map(|(_, value)| (end, value))
+        });
+        match argument {
+            Some((end, value)) => {
+                message.push_str(value);
+                rest = &placeholder[end + 1..];
+            }
+            None => {
+                message.push('{');
+                rest = placeholder;
+            }
+        }
+    }
+    message + rest
+}
+
+/// French messages, the custom errors keeping the messages of their rules
+static FRENCH: [(&str, &str); 37] = [
+    (
+        "CapitalizedFirstLetter",
+        "La première lettre ne doit pas être une majuscule",
+    ),
+    (
+        "CommitTypeNotLowercase",
+        "Le type de commit '{commit_type}' doit être en minuscules, utilisez '{expected}'",
+    ),
+    ("ConsecutiveBlankLines", "Lignes vides consécutives"),
+    ("EmptyCommitMessage", "Message de commit vide"),
+    ("EmptyCommitSubject", "Sujet du commit vide"),
+    ("EmptyCommitType", "Type de commit vide"),
+    ("EmptyFooterValue", "Valeur de pied de message vide"),
+    ("EmptyScope", "Portée vide"),
+    (
+        "EmojiPrefix",
+        "L'en-tête semble commencer par l'emoji '{emoji}'",
+    ),
+    ("ForbiddenWord", "Mot interdit '{word}'"),
+    (
+        "HeaderPatternMismatch",
+        "L'en-tête doit correspondre au motif '{pattern}'",
+    ),
+    (
+        "InvalidCommitType.suggestion",
+        "Type de commit '{commit_type}' invalide, vouliez-vous dire '{suggestion}' ?",
+    ),
+    (
+        "InvalidCommitType",
+        "Type de commit '{commit_type}' invalide",
+    ),
+    ("InvalidScopeFormat", "Format de portée invalide"),
+    (
+        "LeadingBlankLine",
+        "Le message ne doit pas commencer par une ligne vide",
+    ),
+    (
+        "LineTooLong",
+        "La ligne ne doit pas dépasser {max} caractères",
+    ),
+    (
+        "MalformedCoAuthor",
+        "Le pied Co-authored-by doit être de la forme 'Nom <email>'",
+    ),
+    (
+        "MalformedSignOff",
+        "Le pied Signed-off-by doit être de la forme 'Nom <email>'",
+    ),
+    (
+        "MisplacedBreakingChangeMarker",
+        "Le marqueur de changement cassant '!' doit être placé juste avant les deux-points",
+    ),
+    (
+        "MissingBody",
+        "Le type de commit '{commit_type}' nécessite un corps",
+    ),
+    (
+        "MissingIssueReference",
+        "Le message de commit doit référencer un ticket correspondant à '{pattern}'",
+    ),
+    ("MissingParenthesis", "Parenthèse manquante"),
+    ("MissingScope", "Portée manquante"),
+    ("MissingSignOff", "Pied Signed-off-by manquant"),
+    ("MissingWhitespace", "Espace manquante"),
+    ("MisplacedWhitespace", "Espace mal placée"),
+    ("NoColumn", "La première ligne doit contenir deux-points"),
+    (
+        "NonCanonicalType",
+        "Le type de commit '{commit_type}' est un alias, utilisez '{canonical}'",
+    ),
+    ("NonEmptySecondLine", "La deuxième ligne doit être vide"),
+    (
+        "NonImperativeSubject.suggestion",
+        "Le sujet devrait être à l'impératif, utilisez '{suggestion}' au lieu de '{word}'",
+    ),
+    (
+        "NonImperativeSubject",
+        "Le sujet devrait être à l'impératif, '{word}' ne l'est pas",
+    ),
+    ("ScopeNotAllowed", "La portée n'est pas autorisée"),
+    (
+        "SubjectEchoesType",
+        "Le sujet ne devrait pas commencer par le type de commit",
+    ),
+    (
+        "SubjectTooLong",
+        "Le sujet ne devrait pas dépasser {max} caractères",
+    ),
+    (
+        "TrailingPunctuation",
+        "Le sujet ne doit pas se terminer par '{char}'",
+    ),
+    (
+        "TrailingWhitespace",
+        "La ligne ne doit pas se terminer par des espaces",
+    ),
+    ("UnknownScope", "Portée '{scope}' inconnue"),
+];
+
+/// French suggestions
+static FRENCH_SUGGESTIONS: [(&str, &str); 38] = [
+    (
+        "CapitalizedFirstLetter",
+        "écrivez '{lowercase}' au lieu de '{word}'",
+    ),
+    ("CommitTypeNotLowercase", "écrivez '{header}'"),
+    ("ConsecutiveBlankLines", "supprimez la ligne vide en trop"),
+    (
+        "EmptyCommitMessage",
+        "écrivez un en-tête comme 'feat: add a feature'",
+    ),
+    (
+        "EmptyCommitSubject",
+        "décrivez le changement après les deux-points",
+    ),
+    (
+        "EmptyCommitType",
+        "ajoutez un type de commit avant les deux-points, comme 'feat'",
+    ),
+    (
+        "EmptyFooterValue",
+        "ajoutez une valeur après le jeton du pied",
+    ),
+    ("EmptyScope", "supprimez la portée vide"),
+    (
+        "EmojiPrefix",
+        "supprimez '{emoji}', ou définissez 'allow_emoji_prefix = true' dans la configuration",
+    ),
+    ("ForbiddenWord", "supprimez '{word}'"),
+    (
+        "InvalidCommitType.suggestion",
+        "écrivez '{suggestion}' au lieu de '{commit_type}'",
+    ),
+    ("InvalidCommitType", "utilisez l'un des types {types}"),
+    (
+        "InvalidScopeFormat.lowercase",
+        "écrivez la portée en minuscules",
+    ),
+    (
+        "InvalidScopeFormat.kebab-case",
+        "écrivez la portée en mots minuscules séparés par '-', comme 'error-handling'",
+    ),
+    (
+        "LeadingBlankLine",
+        "supprimez les lignes vides avant l'en-tête",
+    ),
+    ("LineTooLong", "coupez la ligne à {max} caractères"),
+    (
+        "MalformedCoAuthor",
+        "écrivez '{token}: Nom <nom@example.com>'",
+    ),
+    (
+        "MalformedSignOff",
+        "écrivez '{token}: Nom <nom@example.com>'",
+    ),
+    (
+        "MisplacedBreakingChangeMarker",
+        "placez le '!' juste avant les deux-points",
+    ),
+    (
+        "MissingBody",
+        "expliquez le changement dans un corps, après une ligne vide",
+    ),
+    (
+        "MissingIssueReference",
+        "référencez le ticket dans le message, comme 'Closes #123'",
+    ),
+    ("MissingParenthesis", "fermez la parenthèse de la portée"),
+    (
+        "MissingScope",
+        "ajoutez une portée avant les deux-points, comme '{commit_type}(scope):'",
+    ),
+    (
+        "MissingSignOff",
+        "signez le commit avec 'git commit --signoff'",
+    ),
+    (
+        "MissingWhitespace",
+        "ajoutez une espace après les deux-points : '{header}'",
+    ),
+    (
+        "MisplacedWhitespace.header",
+        "supprimez l'espace : '{header}'",
+    ),
+    ("MisplacedWhitespace", "supprimez l'espace"),
+    (
+        "NoColumn",
+        "écrivez l'en-tête sous la forme 'type: sujet', comme 'feat: add a feature'",
+    ),
+    (
+        "NonCanonicalType",
+        "écrivez '{canonical}' au lieu de '{commit_type}'",
+    ),
+    (
+        "NonEmptySecondLine",
+        "ajoutez une ligne vide entre l'en-tête et le corps",
+    ),
+    (
+        "NonImperativeSubject.suggestion",
+        "écrivez '{suggestion}' au lieu de '{word}'",
+    ),
+    (
+        "NonImperativeSubject",
+        "décrivez ce que fait le commit, comme 'add' ou 'fix'",
+    ),
+    ("ScopeNotAllowed", "supprimez '{scope}'"),
+    ("SubjectEchoesType", "supprimez '{word}' du début du sujet"),
+    (
+        "SubjectTooLong",
+        "raccourcissez le sujet à {max} caractères",
+    ),
+    ("TrailingPunctuation", "supprimez le '{char}' final"),
+    ("TrailingWhitespace", "supprimez l'espace en fin de ligne"),
+    ("UnknownScope", "utilisez l'une des portées {scopes}"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use options::ValidationOptions;
+    use rules::RuleId;
+    use suggest::ENGLISH as ENGLISH_SUGGESTIONS;
+    use validate_commit_message_report_with;
+
+    #[test]
+    fn translate_messages_to_french() {
+        let cases = vec![
+            (
+                FormatErrorKind::CapitalizedFirstLetter,
+                "La première lettre ne doit pas être une majuscule",
+            ),
+            (
+                FormatErrorKind::InvalidCommitType {
+                    commit_type: "feet".to_owned(),
+                    suggestion: Some("feat".to_owned()),
+                },
+                "Type de commit 'feet' invalide, vouliez-vous dire 'feat' ?",
+            ),
+            (
+                FormatErrorKind::InvalidCommitType {
+                    commit_type: "wip".to_owned(),
+                    suggestion: None,
+                },
+                "Type de commit 'wip' invalide",
+            ),
+            (
+                FormatErrorKind::InvalidCommitType {
+                    commit_type: "{suggestion}".to_owned(),
+                    suggestion: None,
+                },
+                "Type de commit '{suggestion}' invalide",
+            ),
+            (
+                FormatErrorKind::TrailingPunctuation('.'),
+                "Le sujet ne doit pas se terminer par '.'",
+            ),
+            (
+                FormatErrorKind::NonImperativeSubject {
+                    word: "added".to_owned(),
+                    suggestion: Some("add".to_owned()),
+                },
+                "Le sujet devrait être à l'impératif, utilisez 'add' au lieu de 'added'",
+            ),
+            (
+                FormatErrorKind::Custom {
+                    rule: "no-wip".to_owned(),
+                    message: "No WIP {commits}".to_owned(),
+                },
+                "No WIP {commits}",
+            ),
+        ];
+        for (kind, expected) in cases {
+            assert_eq!(kind.message(Language::French), expected);
+            assert_eq!(kind.message(Language::English), kind.to_string());
+        }
+    }
+
+    #[test]
+    fn key_catalog_by_kind_names() {
+        let names: Vec<_> = RuleId::ALL
+            .iter()
+            .flat_map(|rule| rule.kind_names().iter().cloned())
+            .collect();
+        for &(key, _) in FRENCH.iter() {
+            let name = key.trim_end_matches(".suggestion");
+            assert!(names.contains(&name), "{}", key);
+            assert_eq!(FRENCH.iter().filter(|&&(other, _)| other == key).count(), 1);
+        }
+    }
+
+    #[test]
+    fn translate_suggestions_to_french() {
+        let options = ValidationOptions::default();
+        let cases = vec![
+            ("feat: Add colors", "écrivez 'add' au lieu de 'Add'"),
+            (
+                "feat:add colors",
+                "ajoutez une espace après les deux-points : 'feat: add colors'",
+            ),
+            ("feet: add colors", "écrivez 'feat' au lieu de 'feet'"),
+        ];
+        for (message, expected) in cases {
+            let report = validate_commit_message_report_with(message, &options);
+            let error = report.errors.iter().chain(&report.warnings).next().unwrap();
+            assert_eq!(
+                error.suggestion_message(Language::French).as_deref(),
+                Some(expected)
+            );
+            assert_eq!(
+                error.suggestion_message(Language::English).as_deref(),
+                error.suggestion()
+            );
+        }
+    }
+
+    #[test]
+    fn key_suggestions_like_english() {
+        for &(key, template) in FRENCH_SUGGESTIONS.iter() {
+            let english = ENGLISH_SUGGESTIONS
+                .iter()
+                .find(|&&(other, _)| other == key)
+                .unwrap_or_else(|| panic!("{}", key));
+            let placeholders = |template: &str| {
+                let mut names: Vec<_> = template
+                    .split('{')
+                    .skip(1)
+                    .filter_map(|part| part.split('}').next())
+                    .map(str::to_owned)
+                    .collect();
+                names.sort();
+                names
+            };
+            assert_eq!(placeholders(template), placeholders(english.1), "{}", key);
+        }
+        assert_eq!(FRENCH_SUGGESTIONS.len(), ENGLISH_SUGGESTIONS.len());
+    }
+
+    #[test]
+    fn detect_language() {
+        assert_eq!(Language::from_locale("fr_CA.UTF-8"), Some(Language::French));
+        assert_eq!(Language::from_locale("FR"), Some(Language::French));
+        assert_eq!(Language::from_locale("POSIX"), Some(Language::English));
+        assert_eq!(Language::from_locale("de_DE.UTF-8"), None);
+        assert_eq!(
+            Language::detect(vec![("LC_MESSAGES", "fr_FR"), ("LANG", "en_US.UTF-8")]),
+            Language::French
+        );
+        assert_eq!(
+            Language::detect(vec![("LANG", "de_DE.UTF-8")]),
+            Language::English
+        );
+        assert_eq!(
+            Language::detect(Vec::<(String, String)>::new()),
+            Language::English
+        );
+        assert_eq!("fr".parse(), Ok(Language::French));
+        assert_eq!(
+            "es".parse::<Language>().unwrap_err().to_string(),
+            "Unknown language 'es', expected one of: en, fr"
+        );
+    }
+}
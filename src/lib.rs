@@ -1,40 +1,300 @@
-#[macro_use]
-extern crate failure;
+#[cfg(feature = "git")]
+extern crate git2;
+#[cfg(feature = "parallel")]
+extern crate rayon;
+#[cfg(feature = "regex")]
+extern crate regex;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(any(
+    feature = "commitlint-compat",
+    feature = "wasm",
+    all(test, feature = "serde")
+))]
+extern crate serde_json;
+#[cfg(feature = "commitlint-compat")]
+extern crate serde_yaml;
+#[cfg(feature = "config")]
+extern crate toml;
+extern crate unicode_width;
+#[cfg(feature = "wasm")]
+extern crate wasm_bindgen;
 
+mod checks;
+mod mood;
 mod parse;
+mod suggest;
 
+#[cfg(feature = "baseline")]
+pub mod baseline;
+pub mod builder;
+pub mod bump;
+pub mod changelog;
+#[cfg(feature = "commitlint-compat")]
+pub mod commitlint;
+#[cfg(feature = "config")]
+pub mod config;
+pub mod env;
 pub mod errors;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod fix;
+#[cfg(feature = "git")]
+pub mod git;
+#[cfg(feature = "hook")]
+pub mod hook;
+pub mod lang;
+pub mod options;
+pub mod owned;
+#[cfg(feature = "plugin")]
+pub mod plugin;
+pub mod rules;
+pub mod stats;
+pub mod validator;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
-use std::{fs::File, io::Read, str::FromStr};
+use std::{
+    convert::TryFrom,
+    fmt,
+    fs::File,
+    io::Read,
+    ops::Range,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
-use failure::ResultExt;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-use parse::parse_commit_message;
+use checks::{
+    BodyRule, CoAuthorRule, ForbiddenWordRule, IssueReferenceRule, SignOffRule, SubjectRule,
+};
+use parse::{
+    header_spans, message_lines, parse_commit_message_collect, reverted_header, Line,
+    BYTE_ORDER_MARK,
+};
 
+#[cfg(feature = "baseline")]
+pub use baseline::*;
+pub use builder::*;
+pub use bump::*;
+pub use changelog::*;
+pub use env::*;
 pub use errors::*;
+pub use fix::*;
+pub use lang::*;
+pub use options::*;
+pub use owned::*;
+pub use rules::*;
+pub use stats::*;
+pub use validator::*;
 
 /// Represent a commit message
-///
-/// For now, only contains the header.
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct CommitMsg<'a> {
     /// Commit header
     pub header: CommitHeader<'a>,
+    /// Commit body, if provided
+    ///
+    /// Contains everything after the blank second line, with its paragraph breaks preserved.
+    pub body: Option<&'a str>,
+    /// Commit footers, parsed from the last paragraph of the message
+    pub footers: Vec<Footer<'a>>,
 }
 
 /// Represent a commit header
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct CommitHeader<'a> {
     /// Type of the commit
+    ///
+    /// An empty custom type if the [`ValidationOptions::header_pattern`] has no `type` group.
     pub commit_type: CommitType,
     /// Scope of the commit, if provided
+    ///
+    /// Contains everything between the parentheses, including the commas separating multiple
+    /// scopes.
     pub scope: Option<&'a str>,
+    /// Scopes of the commit, split on commas
+    ///
+    /// Empty if the commit has no scope.
+    pub scopes: Vec<&'a str>,
+    /// Whether the header contains the breaking change marker `!`
+    pub breaking: bool,
     /// Subject of the commit
     pub subject: &'a str,
+    /// Ticket tag before the header, like `[PROJ-42]`
+    ///
+    /// Only parsed with the [`ValidationOptions::allow_ticket_prefix`] option.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub ticket: Option<&'a str>,
+    /// Emoji before the header, like `✨` or `:sparkles:`
+    ///
+    /// Only parsed with the [`ValidationOptions::allow_emoji_prefix`] option.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub emoji: Option<&'a str>,
 }
 
-/// Type of a commit
+/// Byte ranges of the parts of a header in its line, as returned by
+/// [`parse_commit_message_with_spans`]
+///
+/// The ranges are relative to the whole line, including the `fixup! ` prefixes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct CommitHeaderSpans {
+    /// Ticket tag before the header, like `[PROJ-42]`
+    pub ticket: Option<Range<usize>>,
+    /// Emoji before the header, like `:sparkles:`
+    pub emoji: Option<Range<usize>>,
+    /// Type of the commit
+    ///
+    /// Empty if the [`ValidationOptions::header_pattern`] has no `type` group.
+    pub commit_type: Range<usize>,
+    /// Scope of the commit, between the parentheses
+    pub scope: Option<Range<usize>>,
+    /// Parentheses around the scope, included
+    pub parentheses: Option<Range<usize>>,
+    /// Breaking change marker `!`
+    pub breaking: Option<Range<usize>>,
+    /// Subject of the commit
+    pub subject: Range<usize>,
+}
+
+impl<'a> CommitMsg<'a> {
+    /// Whether the commit introduces a breaking change.
+    ///
+    /// This is the case if the header contains the `!` marker, or if the message contains a
+    /// `BREAKING CHANGE` footer.
+    pub fn is_breaking(&self) -> bool {
+        self.header.breaking || self.footers.iter().any(Footer::is_breaking_change)
+    }
+}
+
+/// Write the message in its canonical form: the header, the body and the footers, separated by
+/// blank lines
+///
+/// Parsing the written message gives back an equal message.
+impl<'a> fmt::Display for CommitMsg<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.header)?;
+        if let Some(body) = self.body {
+            write!(f, "\n\n{}", body)?;
+        }
+        for (i, footer) in self.footers.iter().enumerate() {
+            let separator = if i == 0 { "\n\n" } else { "\n" };
+            write!(f, "{}{}", separator, footer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Write the header in its canonical form, like `feat(lib)!: add headers`
+impl<'a> fmt::Display for CommitHeader<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(ticket) = self.ticket {
+            write!(f, "{} ", ticket)?;
+        }
+        if let Some(emoji) = self.emoji {
+            write!(f, "{} ", emoji)?;
+        }
+        write!(f, "{}", self.commit_type)?;
+        if let Some(scope) = self.scope {
+            write!(f, "({})", scope)?;
+        }
+        if self.breaking {
+            write!(f, "!")?;
+        }
+        write!(f, ": {}", self.subject)
+    }
+}
+
+/// Parse a commit message with the default options, see [`parse_commit_message`]
+///
+/// # Examples
+///
+/// ```
+/// # use std::convert::TryFrom;
+/// # use validate_commit::{CommitMsg, CommitType};
+/// let message = CommitMsg::try_from("feat: add conversions\n\nCloses: #12").unwrap();
+/// assert_eq!(message.header.commit_type, CommitType::Feat);
+/// assert_eq!(message.footers[0].value, "#12");
+/// ```
+impl<'a> TryFrom<&'a str> for CommitMsg<'a> {
+    type Error = FormatError;
+
+    fn try_from(input: &'a str) -> Result<Self, Self::Error> {
+        parse_commit_message(input)
+    }
+}
+
+/// Parse a commit header with the default options, like the first line of
+/// [`parse_commit_message`]
+///
+/// The header must be a single line.
+///
+/// # Examples
+///
+/// ```
+/// # use std::convert::TryFrom;
+/// # use validate_commit::CommitHeader;
+/// let header = CommitHeader::try_from("feat(lib)!: add conversions").unwrap();
+/// assert_eq!(header.scope, Some("lib"));
+/// assert!(header.breaking);
+///
+/// assert!(CommitHeader::try_from("feat: Add conversions").is_err());
+/// assert!(CommitHeader::try_from("feat: add conversions\nwith a body").is_err());
+/// ```
+impl<'a> TryFrom<&'a str> for CommitHeader<'a> {
+    type Error = FormatError;
+
+    fn try_from(header: &'a str) -> Result<Self, Self::Error> {
+        let mut lines = header.lines();
+        let first_line = lines.next().unwrap_or_default();
+        if let Some(line) = lines.next() {
+            return Err(FormatErrorKind::NonEmptySecondLine.at(line, 2, 0));
+        }
+        parse_commit_message(first_line).map(|message| message.header)
+    }
+}
+
+/// Represent a commit footer, also known as git trailer
+///
+/// For instance, `Signed-off-by: Hugo Laloge <hugo.laloge@gmail.com>`.
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Footer<'a> {
+    /// Token of the footer, like `Signed-off-by` or `BREAKING CHANGE`
+    pub token: &'a str,
+    /// Value of the footer, which can span several lines
+    pub value: &'a str,
+}
+
+impl<'a> Footer<'a> {
+    /// Whether the footer is a `BREAKING CHANGE` or `BREAKING-CHANGE` one.
+    ///
+    /// The token is case-sensitive.
+    pub fn is_breaking_change(&self) -> bool {
+        self.token == "BREAKING CHANGE" || self.token == "BREAKING-CHANGE"
+    }
+}
+
+/// Write the footer with the `: ` separator, like `Refs: #12`
+impl<'a> fmt::Display for Footer<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.value.is_empty() {
+            write!(f, "{}:", self.token)
+        } else {
+            write!(f, "{}: {}", self.token, self.value)
+        }
+    }
+}
+
+/// Type of a commit
+///
+/// The types are ordered as declared, then the custom types alphabetically.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum CommitType {
     Feat,
     Fix,
@@ -44,25 +304,87 @@ pub enum CommitType {
     Perf,
     Test,
     Chore,
+    Build,
+    Ci,
+    Revert,
+    /// Type allowed by [`ValidationOptions::allowed_types`]
+    Custom(String),
 }
 
-impl From<CommitType> for &'static str {
-    fn from(t: CommitType) -> Self {
+impl CommitType {
+    /// Commit types accepted without configuration
+    pub const ALL: [CommitType; 11] = [
+        CommitType::Feat,
+        CommitType::Fix,
+        CommitType::Docs,
+        CommitType::Style,
+        CommitType::Refactor,
+        CommitType::Perf,
+        CommitType::Test,
+        CommitType::Chore,
+        CommitType::Build,
+        CommitType::Ci,
+        CommitType::Revert,
+    ];
+
+    /// Name of the type, as written in the headers, like `feat`
+    pub fn as_str(&self) -> &str {
         use CommitType::*;
 
-        match t {
+        match self {
             Feat => "feat",
             Fix => "fix",
-            Docs => "docx",
+            Docs => "docs",
             Style => "style",
             Refactor => "refactor",
             Perf => "perf",
             Test => "test",
             Chore => "chore",
+            Build => "build",
+            Ci => "ci",
+            Revert => "revert",
+            Custom(ref t) => t,
         }
     }
 }
 
+impl<'a> From<&'a CommitType> for &'a str {
+    fn from(t: &'a CommitType) -> Self {
+        t.as_str()
+    }
+}
+
+impl fmt::Display for CommitType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Token of the footers added by `git commit --signoff`
+const SIGN_OFF_TOKEN: &str = "Signed-off-by";
+
+/// Token of the footers crediting the co-authors on GitHub
+const CO_AUTHOR_TOKEN: &str = "Co-authored-by";
+
+/// Serialize the commit type to its lowercase string form, like `"feat"`
+#[cfg(feature = "serde")]
+impl Serialize for CommitType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Deserialize the commit type from its string form, unknown types being custom ones
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for CommitType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let commit_type = String::deserialize(deserializer)?;
+        Ok(commit_type
+            .parse()
+            .unwrap_or(CommitType::Custom(commit_type)))
+    }
+}
+
 impl FromStr for CommitType {
     type Err = FormatError;
 
@@ -78,35 +400,161 @@ impl FromStr for CommitType {
             "perf" => Ok(Perf),
             "test" => Ok(Test),
             "chore" => Ok(Chore),
-            _ => Err(FormatErrorKind::InvalidCommitType.into()),
+            "build" => Ok(Build),
+            "ci" => Ok(Ci),
+            "revert" => Ok(Revert),
+            _ => Err(FormatErrorKind::InvalidCommitType {
+                commit_type: s.to_owned(),
+                suggestion: None,
+            }
+            .into()),
         }
     }
 }
 
 /// Read a commit file to validate it.
 ///
+/// The options are loaded with [`load_options`].
+///
+/// See [`validate_commit_message`] for more details about validation.
+pub fn validate_commit_file<P: AsRef<Path>>(path: P) -> Result<(), CommitValidationError> {
+    let options = load_options()?;
+    validate_commit_file_with(path, &options)
+}
+
+/// Read a commit file to validate it with the given options.
+///
 /// See [`validate_commit_message`] for more details about validation.
-pub fn validate_commit_file(path: &str) -> Result<(), CommitValidationError> {
+pub fn validate_commit_file_with<P: AsRef<Path>>(
+    path: P,
+    options: &ValidationOptions,
+) -> Result<(), CommitValidationError> {
     let message = read_commit_file(path)?;
-    validate_commit_message(&message).map_err(|e| e.into())
+    validate_commit_message_with(&message, options).map_err(|e| e.into())
+}
+
+/// Path of a commit file with the result of its validation
+pub type FileValidation = (PathBuf, Result<(), CommitValidationError>);
+
+/// Read several commit files to validate them, returning the result of each file.
+///
+/// The options are loaded once with [`load_options`].
+///
+/// See [`validate_commit_message`] for more details about validation.
+pub fn validate_commit_files<P: AsRef<Path>>(
+    paths: &[P],
+) -> Result<Vec<FileValidation>, ConfigError> {
+    let options = load_options()?;
+    Ok(validate_commit_files_with(paths, &options))
+}
+
+/// Read several commit files to validate them with the given options.
+///
+/// A file which can't be read does not prevent the validation of the others.
+pub fn validate_commit_files_with<P: AsRef<Path>>(
+    paths: &[P],
+    options: &ValidationOptions,
+) -> Vec<FileValidation> {
+    paths
+        .iter()
+        .map(|path| {
+            let path = path.as_ref();
+            let result = read_commit_file(path)
+                .map_err(CommitValidationError::from)
+                .and_then(|message| {
+                    validate_commit_message_with(&message, options).map_err(|e| e.into())
+                });
+            (path.to_owned(), result)
+        })
+        .collect()
+}
+
+/// Read a commit message from a reader, like the standard input, to validate it.
+///
+/// The options are loaded with [`load_options`].
+///
+/// See [`validate_commit_message`] for more details about validation.
+pub fn validate_commit_read<R: Read>(reader: R) -> Result<(), CommitValidationError> {
+    let options = load_options()?;
+    validate_commit_read_with(reader, &options)
+}
+
+/// Read a commit message from a reader to validate it with the given options.
+///
+/// See [`validate_commit_message`] for more details about validation.
+pub fn validate_commit_read_with<R: Read>(
+    reader: R,
+    options: &ValidationOptions,
+) -> Result<(), CommitValidationError> {
+    let message = read_commit_message(reader)?;
+    validate_commit_message_with(&message, options).map_err(|e| e.into())
 }
 
-fn read_commit_file(path: &str) -> Result<String, IOError> {
-    let mut file = File::open(path).context(IOErrorKind::OpenFileError)?;
+/// Load the options used by [`validate_commit_file`].
+///
+/// With the `config` feature, the options are loaded from the user configuration file,
+/// overridden by the configuration file found from the current directory. Otherwise, the default
+/// options are returned.
+#[cfg(feature = "config")]
+pub fn load_options() -> Result<ValidationOptions, ConfigError> {
+    let user_config = config::find_user_config_file();
+    match std::env::current_dir() {
+        Ok(dir) => config::load_layered_config(dir, user_config.as_deref()),
+        Err(_) => config::load_config_files(user_config.as_slice()),
+    }
+}
+
+/// Load the options used by [`validate_commit_file`].
+///
+/// With the `config` feature, the options are loaded from the configuration file found from the
+/// current directory. Otherwise, the default options are returned.
+#[cfg(not(feature = "config"))]
+pub fn load_options() -> Result<ValidationOptions, ConfigError> {
+    Ok(ValidationOptions::default())
+}
+
+/// Read the content of a commit file.
+pub fn read_commit_file<P: AsRef<Path>>(path: P) -> Result<String, IOError> {
+    let path = path.as_ref();
+    let with_path = |kind| move |e| IOError::new(kind, e).with_path(path);
+    let mut file = File::open(path).map_err(with_path(IOErrorKind::OpenFileError))?;
     let mut message = String::with_capacity(64);
     file.read_to_string(&mut message)
-        .context(IOErrorKind::ReadFileError)?;
-    Ok(message)
+        .map_err(with_path(IOErrorKind::ReadFileError))?;
+    Ok(strip_byte_order_mark(message))
+}
+
+fn strip_byte_order_mark(message: String) -> String {
+    match message.strip_prefix(BYTE_ORDER_MARK) {
+        Some(message) => message.to_owned(),
+        None => message,
+    }
+}
+
+/// Read a commit message from a reader, like the standard input.
+pub fn read_commit_message<R: Read>(mut reader: R) -> Result<String, IOError> {
+    let mut message = String::with_capacity(64);
+    reader
+        .read_to_string(&mut message)
+        .map_err(|e| IOError::new(IOErrorKind::ReadMessageError, e))?;
+    Ok(strip_byte_order_mark(message))
 }
 
-/// Validate a commit message.
+/// Validate a commit message with the default options.
 ///
-/// For now, only validate the header, which contains the commit type, the subject
-/// and an optional scope.
+/// Validate the header, which contains the commit type, the subject and an optional scope, the
+/// blank line after it, the length and trailing whitespace of every line, and the body and
+/// footers, like the sign-offs and the co-authors. See [`RuleId::ALL`] for the list of the rules.
 ///
-/// Ignore lines starting with '#'.
+/// Ignore lines starting with '#', or with [`ValidationOptions::comment_char`], and everything
+/// after the scissors line of `git commit --verbose`.
 ///
-/// Validate the whole message if the first line starts with "Merge " or "WIP".
+/// Skip the whole message if the first line starts with "Merge " or "WIP", or with one of the
+/// [`ValidationOptions::ignore_prefixes`], or if it is an allowed header like "Initial commit",
+/// see [`ValidationOptions::allowed_literal_headers`].
+///
+/// Skip the messages generated by `git revert`, like `Revert "feat: add scopes"`, as well,
+/// unless [`ValidationOptions::strict_reverts`] is set to check the reverted header.
 ///
 /// # Examples
 ///
@@ -120,47 +568,597 @@ fn read_commit_file(path: &str) -> Result<String, IOError> {
 /// assert!(validate_commit_message("Merge branch 'develop'").is_ok());
 /// ```
 pub fn validate_commit_message(input: &str) -> Result<(), FormatError> {
-    let lines: Vec<_> = input.lines()
-        .filter(|l| !l.starts_with('#'))
-        .collect();
+    validate_commit_message_with(input, &ValidationOptions::default())
+}
 
-    if lines[0].starts_with("Merge ") || lines[0].starts_with("WIP") {
-        return Ok(());
+/// Validate a commit message with the given options.
+///
+/// See [`validate_commit_message`] for more details about validation.
+///
+/// # Examples
+///
+/// Allowing custom commit types:
+/// ```
+/// # use validate_commit::{validate_commit_message_with, ValidationOptions};
+/// let options = ValidationOptions {
+///     allowed_types: Some(vec!["feat".to_owned(), "deps".to_owned()]),
+///     ..ValidationOptions::default()
+/// };
+/// assert!(validate_commit_message_with("deps: update failure", &options).is_ok());
+/// assert!(validate_commit_message_with("fix: fix a bug", &options).is_err());
+/// ```
+pub fn validate_commit_message_with(
+    input: &str,
+    options: &ValidationOptions,
+) -> Result<(), FormatError> {
+    validate_commit_message_all_with(input, options)
+        .map_err(|errors| errors.into_iter().next().unwrap())
+}
+
+/// Validate a commit message with the default options, reporting all the errors.
+///
+/// The errors are sorted by line, then by column.
+///
+/// See [`validate_commit_message`] for more details about validation.
+///
+/// # Examples
+///
+/// ```
+/// # use validate_commit::validate_commit_message_all;
+/// let errors = validate_commit_message_all("feat: Add commit validation
+/// Without a blank line").unwrap_err();
+/// assert_eq!(errors.len(), 2);
+/// ```
+pub fn validate_commit_message_all(input: &str) -> Result<(), Vec<FormatError>> {
+    validate_commit_message_all_with(input, &ValidationOptions::default())
+}
+
+/// Validate a commit message with the given options, reporting all the errors.
+///
+/// The violations of the rules demoted to warnings are not reported, see
+/// [`validate_commit_message_report_with`] to get them.
+///
+/// See [`validate_commit_message_all`] for more details.
+pub fn validate_commit_message_all_with(
+    input: &str,
+    options: &ValidationOptions,
+) -> Result<(), Vec<FormatError>> {
+    let report = validate_commit_message_report_with(input, options);
+    if report.errors.is_empty() {
+        Ok(())
+    } else {
+        Err(report.errors)
     }
+}
 
-    let message = parse_commit_message(&lines)?;
+/// Errors and warnings found when validating a commit message
+#[derive(Debug, Default, PartialEq)]
+pub struct ValidationReport {
+    /// Violations of the rules with the [`Severity::Error`] severity, sorted by line and column
+    pub errors: Vec<FormatError>,
+    /// Violations of the rules with the [`Severity::Warning`] severity, sorted by line and column
+    pub warnings: Vec<FormatError>,
+}
 
-    for line in &lines {
-        if line.len() > 100 {
-            return Err(FormatErrorKind::LineTooLong(100).at(line, 100));
-        }
+impl ValidationReport {
+    /// Whether the message is valid, that is if there is no error.
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
     }
 
-    // Check if the first letter is not capitalized
-    if message
-        .header
-        .subject
-        .chars()
-        .next()
-        .unwrap()
-        .is_uppercase()
+    /// Turn the warnings into errors, so that they make the validation fail.
+    pub fn into_strict(mut self) -> ValidationReport {
+        self.errors.append(&mut self.warnings);
+        self.errors.sort_by_key(FormatError::sort_key);
+        self
+    }
+}
+
+/// Validate a commit message with the default options, separating the errors from the warnings.
+///
+/// See [`validate_commit_message`] for more details about validation.
+pub fn validate_commit_message_report(input: &str) -> ValidationReport {
+    validate_commit_message_report_with(input, &ValidationOptions::default())
+}
+
+/// Validate a commit message with the given options, separating the errors from the warnings.
+///
+/// The severity of each rule is given by [`ValidationOptions::severity`].
+///
+/// # Examples
+///
+/// Demoting a rule to a warning:
+/// ```
+/// # use validate_commit::{validate_commit_message_report_with, RuleId, Severity, ValidationOptions};
+/// let mut options = ValidationOptions::default();
+/// options.severities.insert(RuleId::SubjectNotCapitalized, Severity::Warning);
+/// let report = validate_commit_message_report_with("feat: Add warnings", &options);
+/// assert!(report.is_ok());
+/// assert_eq!(report.warnings.len(), 1);
+/// ```
+pub fn validate_commit_message_report_with(
+    input: &str,
+    options: &ValidationOptions,
+) -> ValidationReport {
+    let errors = check_with_rules(input, options, &[]).unwrap_or_default();
+    let errors = with_suggestions(errors, options);
+    split_by_severity(errors, options)
+}
+
+/// Validate a commit message with the given options and the additional rules.
+///
+/// Return `None` if the message is accepted without validation, like the ignored messages.
+fn check_with_rules(
+    input: &str,
+    options: &ValidationOptions,
+    rules: &[Box<dyn Rule>],
+) -> Option<Vec<FormatError>> {
+    let first_line = message_lines(input, options.comment_char).find(|l| !l.is_blank());
+    if first_line
+        .as_ref()
+        .is_some_and(|l| options.is_ignored(l.text))
     {
-        let pos = lines[0].find(message.header.subject).unwrap();
-        return Err(FormatErrorKind::CapitalizedFirstLetter.at(lines[0], pos));
+        return None;
     }
 
-    Ok(())
+    match first_line {
+        Some(line) if options.pr_title => {
+            // Only the header is validated, and it has no body
+            let title = strip_pull_request_number(line.text);
+            let first_line = message_lines(title, options.comment_char).next();
+            // The errors are located after the byte order mark stripped from the title, if any
+            let offset = if title.starts_with(BYTE_ORDER_MARK) {
+                BYTE_ORDER_MARK.len_utf8()
+            } else {
+                0
+            };
+            let errors = check_message(title, first_line, options, rules)?;
+            Some(
+                errors
+                    .into_iter()
+                    .filter(|e| !PR_TITLE_SKIPPED_RULES.contains(&e.rule()))
+                    .map(|e| e.relocate(line.text, line.number, offset))
+                    .collect(),
+            )
+        }
+        _ => check_message(input, first_line, options, rules),
+    }
+}
+
+/// Rules of the body and the footers, not checked in the titles of pull requests
+const PR_TITLE_SKIPPED_RULES: [RuleId; 4] = [
+    RuleId::BodyRequired,
+    RuleId::IssueReference,
+    RuleId::SignOff,
+    RuleId::CoAuthorFormat,
+];
+
+/// Remove the ` (#123)` suffix appended by GitHub to the titles of the squash merged pull
+/// requests.
+fn strip_pull_request_number(title: &str) -> &str {
+    let stripped = title
+        .strip_suffix(')')
+        .and_then(|title| title.rsplit_once(" (#"))
+        .filter(|(_, number)| !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit()));
+    match stripped {
+        Some((title, _)) => title,
+        None => title,
+    }
+}
+
+/// Check the message, or the header reverted by a message generated by `git revert`.
+fn check_message(
+    input: &str,
+    first_line: Option<Line>,
+    options: &ValidationOptions,
+    rules: &[Box<dyn Rule>],
+) -> Option<Vec<FormatError>> {
+    let mut errors = Vec::new();
+    match first_line.and_then(|l| reverted_header(l.text).map(|header| (l, header))) {
+        // Only the reverted header of the messages generated by `git revert` can be validated
+        Some((line, (offset, header))) => {
+            if !options.strict_reverts {
+                return None;
+            }
+            check_commit_message(header, options, rules, &mut errors);
+            // The errors are located after the byte order mark stripped from the header, if any
+            let offset = if header.starts_with(BYTE_ORDER_MARK) {
+                offset + BYTE_ORDER_MARK.len_utf8()
+            } else {
+                offset
+            };
+            // The reverted header has no body, and the reverts explain themselves
+            errors = errors
+                .into_iter()
+                .filter(|e| e.rule() != RuleId::BodyRequired)
+                .map(|e| e.relocate(line.text, line.number, offset))
+                .collect();
+        }
+        None => {
+            check_commit_message(input, options, rules, &mut errors);
+        }
+    }
+    Some(errors)
+}
+
+/// Attach to the errors the suggestions of how to fix them.
+fn with_suggestions(errors: Vec<FormatError>, options: &ValidationOptions) -> Vec<FormatError> {
+    errors
+        .into_iter()
+        .map(|e| {
+            let suggestion = suggest::suggest(&e, options);
+            e.with_suggestion(suggestion)
+        })
+        .collect()
+}
+
+/// Drop the errors of the disabled rules, and separate the errors from the warnings.
+fn split_by_severity(errors: Vec<FormatError>, options: &ValidationOptions) -> ValidationReport {
+    let (errors, warnings) = errors
+        .into_iter()
+        .filter(|e| options.is_enabled(e.rule()))
+        .partition(|e| options.severity(e.rule()) == Severity::Error);
+    ValidationReport { errors, warnings }
+}
+
+/// Parse a commit message with the default options.
+///
+/// The message is accepted if it passes the validation of [`validate_commit_message`], except
+/// for the messages starting with "Merge " or "WIP", which have no header to parse. Lines
+/// starting with '#' are ignored, and the `fixup! `, `squash! ` and `amend! ` prefixes are
+/// discarded, even when stacked.
+///
+/// # Examples
+///
+/// ```
+/// # use validate_commit::{parse_commit_message, CommitType};
+/// let message = parse_commit_message("fixup! feat(lib): add commit parsing
+/// ## A comment in a COMMIT_EDITMSG file
+///
+/// BREAKING CHANGE: the commit messages are now parsed").unwrap();
+/// assert_eq!(message.header.commit_type, CommitType::Feat);
+/// assert_eq!(message.header.scope, Some("lib"));
+/// assert_eq!(message.header.subject, "add commit parsing");
+/// assert!(message.is_breaking());
+///
+/// assert!(parse_commit_message("feat: Add commit parsing").is_err());
+/// ```
+pub fn parse_commit_message(input: &str) -> Result<CommitMsg<'_>, FormatError> {
+    parse_commit_message_with(input, &ValidationOptions::default())
+}
+
+/// Parse a commit message with the given options.
+///
+/// See [`parse_commit_message`] for more details.
+pub fn parse_commit_message_with<'a>(
+    input: &'a str,
+    options: &ValidationOptions,
+) -> Result<CommitMsg<'a>, FormatError> {
+    let mut errors = Vec::new();
+    let message = check_commit_message(input, options, &[], &mut errors);
+    let mut errors = with_suggestions(errors, options);
+    let first_error = errors.iter().position(|e| {
+        options.is_enabled(e.rule()) && options.severity(e.rule()) == Severity::Error
+    });
+    match (message, first_error) {
+        (Some(message), None) => Ok(message),
+        (_, Some(i)) => Err(errors.remove(i)),
+        // The message cannot be parsed, even if the errors are disabled or demoted
+        (None, None) => Err(errors.remove(0)),
+    }
+}
+
+/// Parse a commit message with the given options, locating the parts of its header, for editors
+/// to highlight them.
+///
+/// See [`parse_commit_message`] for more details.
+///
+/// # Examples
+///
+/// ```
+/// # use validate_commit::{parse_commit_message_with_spans, ValidationOptions};
+/// let options = ValidationOptions::default();
+/// let (message, spans) =
+///     parse_commit_message_with_spans("fixup! feat(lib)!: add spans", &options).unwrap();
+/// assert_eq!(message.header.subject, "add spans");
+/// assert_eq!(spans.commit_type, 7..11);
+/// assert_eq!(spans.scope, Some(12..15));
+/// assert_eq!(spans.parentheses, Some(11..16));
+/// assert_eq!(spans.breaking, Some(16..17));
+/// assert_eq!(spans.subject, 19..28);
+/// ```
+pub fn parse_commit_message_with_spans<'a>(
+    input: &'a str,
+    options: &ValidationOptions,
+) -> Result<(CommitMsg<'a>, CommitHeaderSpans), FormatError> {
+    let message = parse_commit_message_with(input, options)?;
+    let line = message_lines(input, options.comment_char)
+        .find(|line| !line.is_blank())
+        .expect("A parsed message has a header");
+    let spans = header_spans(line.text, &message.header, options);
+    Ok((message, spans))
+}
+
+/// Parse several commit messages with the given options.
+///
+/// The results are in the order of the messages. See [`parse_commit_message`] for more details.
+///
+/// # Examples
+///
+/// ```
+/// # use validate_commit::{validate_commit_messages, ValidationOptions};
+/// let options = ValidationOptions::default();
+/// let results = validate_commit_messages(vec!["feat: add batches", "Add batches"], &options);
+/// assert_eq!(results[0].as_ref().unwrap().header.subject, "add batches");
+/// assert!(results[1].is_err());
+/// ```
+pub fn validate_commit_messages<'a, I>(
+    messages: I,
+    options: &ValidationOptions,
+) -> Vec<Result<CommitMsg<'a>, FormatError>>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    messages
+        .into_iter()
+        .map(|message| parse_commit_message_with(message, options))
+        .collect()
+}
+
+/// Parse several commit messages in parallel with the given options.
+///
+/// The results are in the order of the messages, like with [`validate_commit_messages`].
+#[cfg(feature = "parallel")]
+pub fn validate_commit_messages_par<'a, I>(
+    messages: I,
+    options: &ValidationOptions,
+) -> Vec<Result<CommitMsg<'a>, FormatError>>
+where
+    I: rayon::iter::IntoParallelIterator<Item = &'a str>,
+{
+    use rayon::iter::ParallelIterator;
+
+    messages
+        .into_par_iter()
+        .map(|message| parse_commit_message_with(message, options))
+        .collect()
+}
+
+/// Parse and validate a commit message, collecting the errors sorted by line and column.
+///
+/// The `rules` are run after the built-in rules, on the messages which can be parsed.
+fn check_commit_message<'a>(
+    input: &'a str,
+    options: &ValidationOptions,
+    rules: &[Box<dyn Rule>],
+    errors: &mut Vec<FormatError>,
+) -> Option<CommitMsg<'a>> {
+    // The lines are checked while they are parsed, so that the message is split only once
+    let mut checks = LineChecks::new(options);
+    let lines = message_lines(input, options.comment_char).inspect(|line| checks.check(line));
+    let message = parse_commit_message_collect(input, lines, options, errors);
+    if checks.finish(errors).is_none() {
+        return message;
+    }
+
+    if let Some(ref message) = message {
+        let builtin: [&dyn Rule; 6] = [
+            &SubjectRule(options),
+            &IssueReferenceRule(options),
+            &BodyRule(options),
+            &SignOffRule(options),
+            &CoAuthorRule,
+            &ForbiddenWordRule(options),
+        ];
+        for rule in builtin
+            .iter()
+            .copied()
+            .chain(rules.iter().map(|rule| &**rule))
+        {
+            errors.append(&mut rule.check(message, input));
+        }
+    }
+
+    errors.sort_by_key(FormatError::sort_key);
+    message
+}
+
+/// Checks of the lines of a message, from its header, run while the message is parsed
+struct LineChecks<'a, 'o> {
+    options: &'o ValidationOptions,
+    /// First non-blank line, checked once the header errors are known
+    header: Option<Line<'a>>,
+    /// Number of lines checked after the header
+    count: usize,
+    previous: Option<Line<'a>>,
+    errors: Vec<FormatError>,
+    /// Consecutive blank lines, only reported if a non-blank line follows them
+    blank_line_errors: Vec<FormatError>,
+}
+
+impl<'a, 'o> LineChecks<'a, 'o> {
+    fn new(options: &'o ValidationOptions) -> Self {
+        LineChecks {
+            options,
+            header: None,
+            count: 0,
+            previous: None,
+            errors: Vec::new(),
+            blank_line_errors: Vec::new(),
+        }
+    }
+
+    fn check(&mut self, line: &Line<'a>) {
+        if self.header.is_none() {
+            if !line.is_blank() {
+                self.header = Some(*line);
+                self.previous = Some(*line);
+            }
+            return;
+        }
+
+        self.count += 1;
+        // A blank second line is already reported as not empty
+        if self.count > 1 || !line.text.trim_end().is_empty() {
+            self.check_trailing_whitespace(line);
+        }
+        let max_length = self.options.max_body_line_length;
+        if max_length > 0 && !is_exempt_from_length(line.text, max_length, self.options) {
+            self.check_length(line, max_length);
+        }
+
+        if !line.is_blank() {
+            self.errors.append(&mut self.blank_line_errors);
+        } else if let Some(previous) = self.previous {
+            if previous.is_blank() && previous.number + 1 == line.number {
+                self.blank_line_errors
+                    .push(line.error(FormatErrorKind::ConsecutiveBlankLines, 0));
+            }
+        }
+        self.previous = Some(*line);
+    }
+
+    /// Check the header, and move the errors of the lines to `errors`.
+    ///
+    /// Return the header, if the message is not blank.
+    fn finish(&mut self, errors: &mut Vec<FormatError>) -> Option<Line<'a>> {
+        let header = self.header?;
+        let trimmed_len = header.text.trim_end().len();
+        // The whitespaces ending the subject are reported like on the other lines, unless the rule
+        // is disabled
+        if self.options.is_enabled(RuleId::NoTrailingWhitespace) {
+            errors.retain(|error| {
+                error.kind != FormatErrorKind::MisplacedWhitespace
+                    || error.line_number() != Some(header.number)
+                    || error.sort_key().1 != trimmed_len
+            });
+        }
+        // The whitespaces ending an incomplete header, like `fix: `, are part of the header error
+        if !errors.iter().any(|error| {
+            error.line_number() == Some(header.number) && error.sort_key().1 >= trimmed_len
+        }) {
+            self.check_trailing_whitespace(&header);
+        }
+        if self.options.max_header_length > 0 {
+            self.check_length(&header, self.options.max_header_length);
+        }
+        errors.append(&mut self.errors);
+        Some(header)
+    }
+
+    fn check_trailing_whitespace(&mut self, line: &Line) {
+        let trimmed_len = line.text.trim_end().len();
+        if trimmed_len < line.text.len() {
+            self.errors.push(line.error_spanning(
+                FormatErrorKind::TrailingWhitespace,
+                trimmed_len,
+                line.text.len(),
+            ));
+        }
+    }
+
+    fn check_length(&mut self, line: &Line, max_length: usize) {
+        if let Some((pos, _)) = line.text.char_indices().nth(max_length) {
+            self.errors.push(line.error_spanning(
+                FormatErrorKind::LineTooLong(max_length),
+                pos,
+                line.text.len(),
+            ));
+        }
+    }
+}
+
+/// Check if the body line can be longer than the maximum length, because it contains a URL or is
+/// indented like a code block.
+fn is_exempt_from_length(line: &str, max_length: usize, options: &ValidationOptions) -> bool {
+    if options.ignore_indented_lines && (line.starts_with('\t') || line.starts_with("    ")) {
+        return true;
+    }
+
+    let length = line.chars().count();
+    options.ignore_long_urls
+        && line
+            .split_whitespace()
+            .map(|word| word.trim_start_matches(|c: char| !c.is_alphanumeric()))
+            .filter(|word| word.starts_with("http://") || word.starts_with("https://"))
+            .any(|url| length - url.chars().count() <= max_length)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::validate_commit_message;
+    use std::collections::HashSet;
+    use std::error::Error;
+    use std::io;
+
+    #[cfg(feature = "parallel")]
+    use super::validate_commit_messages_par;
+    #[cfg(feature = "regex")]
+    use super::Pattern;
+    use super::{
+        parse_commit_message, parse_commit_message_with, read_commit_message,
+        validate_commit_file_with, validate_commit_files_with, validate_commit_message,
+        validate_commit_message_all, validate_commit_message_all_with,
+        validate_commit_message_report_with, validate_commit_message_with,
+        validate_commit_messages, validate_commit_read_with, CommitMsg, CommitType,
+        CommitValidationError, FormatError, FormatErrorKind, IOErrorKind, RuleId, Severity,
+    };
+    use options::ValidationOptions;
 
     #[test]
     fn validate_short_messages() {
         assert!(validate_commit_message("feat: add commit message validation").is_ok());
         assert!(validate_commit_message("fix: fix bug in commit message validation").is_ok());
         assert!(validate_commit_message("docs: add README.md").is_ok());
+        assert!(validate_commit_message("build: update failure to 0.1.2").is_ok());
+        assert!(validate_commit_message("ci: add GitHub Actions workflow").is_ok());
+        assert!(validate_commit_message("revert: add commit message validation").is_ok());
+    }
+
+    #[test]
+    fn commit_type_round_trip() {
+        for commit_type in &CommitType::ALL {
+            assert_eq!(commit_type.as_str().parse(), Ok(commit_type.clone()));
+            assert_eq!(commit_type.to_string().parse(), Ok(commit_type.clone()));
+            assert_eq!(<&str>::from(commit_type), commit_type.as_str());
+        }
+        let names: HashSet<_> = CommitType::ALL.iter().map(CommitType::as_str).collect();
+        assert_eq!(names.len(), CommitType::ALL.len());
+        assert_eq!(CommitType::Custom("deps".to_owned()).to_string(), "deps");
+    }
+
+    #[test]
+    fn write_canonical_messages() {
+        let message = parse_commit_message("fixup! feat(lib,cli)!: add Display\r\n").unwrap();
+        assert_eq!(message.to_string(), "feat(lib,cli)!: add Display");
+
+        let message = parse_commit_message(
+            "fix(parser): handle footers\n# Comment\n\nBody\n\nRefs #12\nBREAKING CHANGE:\n  the footers\n",
+        )
+        .unwrap();
+        assert_eq!(
+            message.to_string(),
+            "fix(parser): handle footers\n\nBody\n\nRefs: #12\nBREAKING CHANGE: the footers"
+        );
+    }
+
+    #[test]
+    fn format_and_parse_round_trip() {
+        let messages = [
+            "feat: add Display",
+            "feat(lib)!: add Display",
+            "docs(lib,cli): describe Display",
+            "fix: handle bodies\n\nBody of the commit,\nwrapped on two lines",
+            "refactor: split paragraphs\n\nFirst paragraph\n\n- second\n- paragraph\n\n\
+             Reviewed-by: Z <z@example.com>\nRefs #33",
+            "chore: keep comments\n\nBody\n# Comment\n\nMore body",
+            "fix: handle footers only\n\nBREAKING CHANGE: the footers\n  span lines\nCloses #1",
+        ];
+        for input in &messages {
+            let message = parse_commit_message(input).unwrap();
+            let formatted = message.to_string();
+            assert_eq!(parse_commit_message(&formatted).unwrap(), message);
+            assert_eq!(message.to_owned().to_string(), formatted);
+        }
     }
 
     #[test]
@@ -168,6 +1166,23 @@ mod tests {
         assert!(validate_commit_message("feet: add commit message validation").is_err());
     }
 
+    #[test]
+    fn validate_custom_commit_types() {
+        let options = ValidationOptions {
+            allowed_types: Some(vec![
+                "feat".to_owned(),
+                "deps".to_owned(),
+                "release".to_owned(),
+            ]),
+            ..ValidationOptions::default()
+        };
+        assert!(validate_commit_message_with("deps: update failure", &options).is_ok());
+        assert!(validate_commit_message_with("release: 0.2.1", &options).is_ok());
+        assert!(validate_commit_message_with("feat: add custom types", &options).is_ok());
+        assert!(validate_commit_message_with("fix: fix custom types", &options).is_err());
+        assert!(validate_commit_message("deps: update failure").is_err());
+    }
+
     #[test]
     fn discard_missing_whitespace_before_subject() {
         assert!(validate_commit_message("feat:add commit message validation").is_err());
@@ -188,9 +1203,1463 @@ mod tests {
         assert!(validate_commit_message("feat: add commit message validation an other sweet features so this commit contains way too much things").is_err());
     }
 
+    #[test]
+    fn configure_max_line_length() {
+        let message = format!("feat: add {}", "a".repeat(91));
+        assert_eq!(message.len(), 101);
+        assert!(validate_commit_message(&message).is_err());
+
+        let options = ValidationOptions {
+            max_header_length: 120,
+            ..ValidationOptions::default()
+        };
+        assert!(validate_commit_message_with(&message, &options).is_ok());
+
+        let options = ValidationOptions {
+            max_header_length: 50,
+            ..ValidationOptions::default()
+        };
+        let error = validate_commit_message_with(&message, &options).unwrap_err();
+        assert_eq!(error.kind, FormatErrorKind::LineTooLong(50));
+        assert!(error
+            .to_string()
+            .ends_with(&format!("\n{:50}{}", "", "^".repeat(51))));
+
+        let options = ValidationOptions {
+            max_header_length: 0,
+            ..ValidationOptions::default()
+        };
+        assert!(validate_commit_message_with(&message.repeat(10), &options).is_ok());
+    }
+
+    #[test]
+    fn count_line_length_in_characters() {
+        let accents = format!("feat: {}", "é".repeat(94));
+        assert!(accents.len() > 100);
+        assert!(validate_commit_message(&accents).is_ok());
+
+        let cjk = format!("feat: {}", "提交信息".repeat(23));
+        assert!(cjk.len() > 100);
+        assert!(validate_commit_message(&cjk).is_ok());
+
+        let emoji = format!("feat: add {}", "🎉".repeat(90));
+        assert!(emoji.len() > 100);
+        assert!(validate_commit_message(&emoji).is_ok());
+
+        let too_long = format!("feat: {}", "é".repeat(95));
+        let error = validate_commit_message(&too_long).unwrap_err();
+        assert_eq!(error.kind, FormatErrorKind::LineTooLong(100));
+        assert!(error.to_string().ends_with(&format!("\n{:>101}", '^')));
+    }
+
+    #[test]
+    fn configure_header_and_body_lengths_independently() {
+        let header = format!("feat: add {}", "a".repeat(62));
+        let body_line = "b".repeat(90);
+        let message = format!("{}\n\n{}", header, body_line);
+
+        let options = ValidationOptions {
+            max_header_length: 72,
+            max_body_line_length: 80,
+            ..ValidationOptions::default()
+        };
+        let errors = validate_commit_message_all_with(&message, &options).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, FormatErrorKind::LineTooLong(80));
+
+        let options = ValidationOptions {
+            max_header_length: 50,
+            max_body_line_length: 100,
+            ..ValidationOptions::default()
+        };
+        let errors = validate_commit_message_all_with(&message, &options).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, FormatErrorKind::LineTooLong(50));
+    }
+
+    #[test]
+    fn accept_long_urls_and_code_blocks() {
+        let url = format!("https://example.com/{}", "a".repeat(130));
+        let prose = format!("See {}", "a".repeat(146));
+        for line in &[
+            url.clone(),
+            format!("See {}", url),
+            format!("Refs: <{}>", url),
+            format!("    let url = \"{}\";", "a".repeat(140)),
+            format!("\t{}", "a".repeat(150)),
+        ] {
+            let message = format!("docs: add links\n\n{}", line);
+            assert!(validate_commit_message(&message).is_ok(), "{:?}", line);
+        }
+        let message = format!("docs: add links\n\n{}", prose);
+        assert!(validate_commit_message(&message).is_err());
+        // The text around the URL must still fit in the line
+        let message = format!("docs: add links\n\n{} {}", "a".repeat(100), url);
+        assert!(validate_commit_message(&message).is_err());
+        // The header has no exemption
+        assert!(validate_commit_message(&format!("docs: add {}", url)).is_err());
+
+        let options = ValidationOptions {
+            ignore_long_urls: false,
+            ignore_indented_lines: false,
+            ..ValidationOptions::default()
+        };
+        for line in &[url, format!("    {}", "a".repeat(150))] {
+            let message = format!("docs: add links\n\n{}", line);
+            assert!(validate_commit_message_with(&message, &options).is_err());
+        }
+    }
+
+    #[test]
+    fn report_all_errors() {
+        let errors = validate_commit_message_all(
+            "feat: Add commit message validation an other sweet features so this commit contains way too much things
+- Validate commit type
+
+BREAKING CHANGE:",
+        )
+        .unwrap_err();
+        let kinds: Vec<_> = errors.iter().map(|e| e.kind.clone()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                FormatErrorKind::CapitalizedFirstLetter,
+                FormatErrorKind::LineTooLong(100),
+                FormatErrorKind::NonEmptySecondLine,
+                FormatErrorKind::EmptyFooterValue,
+            ]
+        );
+    }
+
+    #[test]
+    fn report_errors_after_invalid_header() {
+        let errors = validate_commit_message_all(
+            "feet: add commit message validation
+- Validate commit type",
+        )
+        .unwrap_err();
+        let kinds: Vec<_> = errors.iter().map(|e| e.kind.clone()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                FormatErrorKind::InvalidCommitType {
+                    commit_type: "feet".to_owned(),
+                    suggestion: Some("feat".to_owned()),
+                },
+                FormatErrorKind::NonEmptySecondLine,
+            ]
+        );
+    }
+
+    #[test]
+    fn discard_empty_messages() {
+        for message in &[
+            "",
+            "\n\n",
+            "   \n",
+            "# Please enter the commit message\n#\n",
+        ] {
+            let errors = validate_commit_message_all(message).unwrap_err();
+            let kinds: Vec<_> = errors.iter().map(|e| e.kind.clone()).collect();
+            assert_eq!(kinds, vec![FormatErrorKind::EmptyCommitMessage]);
+        }
+    }
+
+    #[test]
+    fn report_error_locations() {
+        let errors = validate_commit_message_all(
+            "feat: Add error locations
+# A comment line
+
+Body line
+
+BREAKING CHANGE:",
+        )
+        .unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].line(), Some("feat: Add error locations"));
+        assert_eq!(errors[0].line_number(), Some(1));
+        assert_eq!(errors[0].column(), Some(7));
+        assert_eq!(
+            errors[0].to_string(),
+            "First letter must not be capitalized [VC001 subject-not-capitalized]
+line 1, column 7:
+feat: Add error locations
+      ^"
+        );
+        let span = errors[1].span().unwrap();
+        assert_eq!(span.line(), "BREAKING CHANGE:");
+        assert_eq!(span.pos(), 16);
+        assert_eq!(errors[1].kind(), &FormatErrorKind::EmptyFooterValue);
+        assert_eq!(errors[1].line(), Some("BREAKING CHANGE:"));
+        assert_eq!(errors[1].line_number(), Some(6));
+        assert_eq!(errors[1].column(), Some(17));
+    }
+
+    #[test]
+    fn compare_whole_errors() {
+        let error = FormatErrorKind::CapitalizedFirstLetter.at("feat: Compare errors", 1, 6);
+        let suggestion = ::suggest::suggest(&error, &ValidationOptions::default());
+        assert_eq!(
+            validate_commit_message("feat: Compare errors"),
+            Err(error.with_suggestion(suggestion))
+        );
+        assert_eq!(
+            validate_commit_message("feat: Compare errors")
+                .unwrap_err()
+                .suggestion(),
+            Some("write 'compare' instead of 'Compare'")
+        );
+        assert_ne!(
+            validate_commit_message("feat: Compare errors"),
+            Err(FormatErrorKind::CapitalizedFirstLetter.into())
+        );
+    }
+
+    /// Generate messages numbered in their subject, every third one being invalid.
+    fn numbered_messages(count: usize) -> Vec<String> {
+        (0..count)
+            .map(|i| match i % 3 {
+                0 => format!("feet: handle message {}", i),
+                1 => format!("fix(batch): handle message {}\n\nBody of {}", i, i),
+                _ => format!("# Comment\nfeat: handle message {}", i),
+            })
+            .collect()
+    }
+
+    fn check_numbered_results(results: &[Result<CommitMsg, FormatError>]) {
+        for (i, result) in results.iter().enumerate() {
+            match result {
+                Ok(message) => assert_eq!(message.header.subject, format!("handle message {}", i)),
+                Err(error) => {
+                    assert_eq!(i % 3, 0);
+                    assert_eq!(error.line(), Some(&*format!("feet: handle message {}", i)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn validate_messages_in_order() {
+        let messages = numbered_messages(3000);
+        let options = ValidationOptions::default();
+        let results = validate_commit_messages(messages.iter().map(String::as_str), &options);
+        assert_eq!(results.len(), messages.len());
+        assert_eq!(results.iter().filter(|r| r.is_err()).count(), 1000);
+        check_numbered_results(&results);
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn validate_messages_in_parallel() {
+        let messages = numbered_messages(3000);
+        let messages: Vec<&str> = messages.iter().map(String::as_str).collect();
+        let options = ValidationOptions::default();
+        let results = validate_commit_messages_par(messages.clone(), &options);
+        assert_eq!(results, validate_commit_messages(messages, &options));
+        check_numbered_results(&results);
+    }
+
+    #[test]
+    fn check_lines_between_comments() {
+        let message = "# Leading comment
+feat: add streaming
+# Comment before the second line
+Body  
+
+# Comment between blank lines
+
+Body with a long line, but it is only long enough to be reported
+
+Signed-off-by: nobody
+# Comment in the footers
+Co-authored-by: A <a@example.com>
+";
+        let options = ValidationOptions {
+            max_body_line_length: 50,
+            require_signoff: true,
+            require_reference: vec![CommitType::Feat],
+            ..ValidationOptions::default()
+        };
+        let errors = validate_commit_message_all_with(message, &options).unwrap_err();
+        assert_eq!(
+            errors
+                .iter()
+                .map(|e| (e.kind.clone(), e.line_number(), e.column()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    FormatErrorKind::MissingIssueReference {
+                        pattern: ValidationOptions::DEFAULT_REFERENCE_PATTERN.to_owned()
+                    },
+                    Some(2),
+                    Some(1)
+                ),
+                (FormatErrorKind::NonEmptySecondLine, Some(4), Some(1)),
+                (FormatErrorKind::TrailingWhitespace, Some(4), Some(5)),
+                (FormatErrorKind::LineTooLong(50), Some(8), Some(51)),
+                (FormatErrorKind::MalformedSignOff, Some(10), Some(16)),
+            ]
+        );
+
+        let message = parse_commit_message_with(
+            "feat: add streaming\n\nBody\n# Comment\n\nMore body\n\n# Comment\nCloses #1\n",
+            &ValidationOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(message.body, Some("Body\n# Comment\n\nMore body"));
+        assert_eq!(message.footers.len(), 1);
+        assert_eq!(message.footers[0].value, "#1");
+    }
+
+    #[test]
+    fn parse_valid_messages_only() {
+        let message = parse_commit_message(
+            "# Please enter the commit message
+squash! fix(parser,errors): handle empty input
+
+Closes: #12",
+        )
+        .unwrap();
+        assert_eq!(message.header.commit_type, CommitType::Fix);
+        assert_eq!(message.header.scopes, vec!["parser", "errors"]);
+        assert_eq!(message.footers.len(), 1);
+
+        assert_eq!(
+            parse_commit_message("feat: Add parsing").unwrap_err().kind,
+            FormatErrorKind::CapitalizedFirstLetter
+        );
+        let options = ValidationOptions {
+            max_header_length: 10,
+            ..ValidationOptions::default()
+        };
+        assert_eq!(
+            parse_commit_message_with("feat: add parsing", &options)
+                .unwrap_err()
+                .kind,
+            FormatErrorKind::LineTooLong(10)
+        );
+        assert!(parse_commit_message("Merge branch 'develop'").is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_commit_message() {
+        let message = parse_commit_message("feat(lib): add thing").unwrap();
+        assert_eq!(
+            ::serde_json::to_string(&message).unwrap(),
+            r#"{"header":{"commit_type":"feat","scope":"lib","scopes":["lib"],"breaking":false,"subject":"add thing"},"body":null,"footers":[]}"#
+        );
+
+        let error = parse_commit_message("feet: add thing").unwrap_err();
+        assert_eq!(
+            ::serde_json::to_string(&error).unwrap(),
+            r#"{"kind":"InvalidCommitType","code":"VC004","message":"Invalid commit type 'feet', did you mean 'feat'?","line":1,"column":1,"suggestion":"write 'feat' instead of 'feet'"}"#
+        );
+    }
+
+    #[test]
+    fn validate_several_files() {
+        let dir = ::std::env::temp_dir().join("validate-commit-several-files");
+        ::std::fs::create_dir_all(&dir).unwrap();
+        let valid = dir.join("valid.txt");
+        let invalid = dir.join("invalid.txt");
+        let missing = dir.join("missing.txt");
+        ::std::fs::write(&valid, "feat: validate several files\n").unwrap();
+        ::std::fs::write(&invalid, "feat: Validate several files\n").unwrap();
+
+        let results = validate_commit_files_with(
+            &[&valid, &invalid, &missing],
+            &ValidationOptions::default(),
+        );
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].0, valid);
+        assert!(results[0].1.is_ok());
+        match results[1].1 {
+            // The wrapper is transparent, so that its message is not repeated by its source
+            Err(ref error @ CommitValidationError::Format(_)) => {
+                assert!(error.to_string().starts_with("First letter"), "{}", error);
+                assert!(error.source().is_none());
+            }
+            ref result => panic!("Unexpected result {:?}", result),
+        }
+        match results[2].1 {
+            Err(ref wrapper @ CommitValidationError::Io(ref error)) => {
+                assert_eq!(error.kind(), IOErrorKind::OpenFileError);
+                assert_eq!(error.path(), Some(missing.as_path()));
+                assert_eq!(
+                    error.to_string(),
+                    format!("Error while opening commit file '{}'", missing.display())
+                );
+                let source = error.source().unwrap().downcast_ref::<io::Error>();
+                assert_eq!(source.map(io::Error::kind), Some(io::ErrorKind::NotFound));
+                let source = wrapper.source().unwrap().downcast_ref::<io::Error>();
+                assert_eq!(source.map(io::Error::kind), Some(io::ErrorKind::NotFound));
+            }
+            ref result => panic!("Unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn validate_windows_messages() {
+        let message = "feat(lib): support CRLF line endings\r\n\r\nBody line\r\nwith CRLF\r\n\r\nReviewed-by: Z\r\nRefs: #33\r\n";
+        assert!(validate_commit_message(message).is_ok());
+        let message = parse_commit_message(message).unwrap();
+        assert_eq!(message.header.subject, "support CRLF line endings");
+        assert_eq!(message.footers[0].value, "Z");
+        assert_eq!(message.footers[1].value, "#33");
+
+        assert!(validate_commit_message("\u{feff}feat: support BOM\n").is_ok());
+        assert!(validate_commit_message("\u{feff}feat: support BOM\r\n\r\nBody\r").is_ok());
+
+        let errors = validate_commit_message_all("feat: keep trailing spaces \r\n").unwrap_err();
+        assert_eq!(errors[0].kind, FormatErrorKind::TrailingWhitespace);
+    }
+
+    #[test]
+    fn discard_trailing_whitespaces() {
+        let message = "feat(parser): support footers \n\
+                       \n\
+                       Footers are parsed\tfrom the last paragraph.\t\n\
+                       \x20\x20\n\
+                       Refs: #42 ";
+        let errors = validate_commit_message_all(message).unwrap_err();
+        let locations: Vec<_> = errors
+            .iter()
+            .map(|error| {
+                assert_eq!(error.kind, FormatErrorKind::TrailingWhitespace);
+                assert_eq!(error.rule(), RuleId::NoTrailingWhitespace);
+                (error.line_number(), error.column())
+            })
+            .collect();
+        assert_eq!(
+            locations,
+            vec![
+                (Some(1), Some(30)),
+                (Some(3), Some(44)),
+                (Some(4), Some(1)),
+                (Some(5), Some(10)),
+            ]
+        );
+        let options = ValidationOptions {
+            disabled_rules: vec![RuleId::NoTrailingWhitespace].into_iter().collect(),
+            ..ValidationOptions::default()
+        };
+        // The whitespaces ending the subject are still reported as misplaced in the header
+        let error = parse_commit_message_with(message, &options).unwrap_err();
+        assert_eq!(error.kind, FormatErrorKind::MisplacedWhitespace);
+        assert_eq!((error.line_number(), error.column()), (Some(1), Some(30)));
+
+        // The errors of the other lines do not hide the whitespaces of the header
+        let errors = validate_commit_message_all("feat: add x \nBody\n").unwrap_err();
+        let kinds: Vec<_> = errors
+            .iter()
+            .map(|e| (e.kind.clone(), e.line_number()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (FormatErrorKind::TrailingWhitespace, Some(1)),
+                (FormatErrorKind::NonEmptySecondLine, Some(2)),
+            ]
+        );
+
+        // The blank second line must be empty
+        let errors = validate_commit_message_all("feat: add colors\n  \nBody").unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, FormatErrorKind::NonEmptySecondLine);
+    }
+
+    #[test]
+    fn discard_leading_blank_lines() {
+        let message = "feat: add colors\n\nThe output is colored in terminals.";
+        assert!(validate_commit_message(message).is_ok());
+
+        let errors = validate_commit_message_all(&format!("\n  \n{}", message)).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, FormatErrorKind::LeadingBlankLine);
+        assert_eq!(errors[0].rule(), RuleId::NoLeadingBlankLine);
+        assert_eq!(errors[0].line_number(), Some(1));
+
+        // The header is still validated from the first line which is not blank
+        let errors =
+            validate_commit_message_all("# Comment\n\nfeat: Add colors\nBody").unwrap_err();
+        let locations: Vec<_> = errors
+            .iter()
+            .map(|error| (error.kind.clone(), error.line_number()))
+            .collect();
+        assert_eq!(
+            locations,
+            vec![
+                (FormatErrorKind::LeadingBlankLine, Some(2)),
+                (FormatErrorKind::CapitalizedFirstLetter, Some(3)),
+                (FormatErrorKind::NonEmptySecondLine, Some(4)),
+            ]
+        );
+        assert!(validate_commit_message("\nWIP").is_ok());
+    }
+
+    #[test]
+    fn warn_about_consecutive_blank_lines() {
+        let message = "feat: add colors\n\n\n\
+                       The output is colored in terminals.\n\
+                       \n\
+                       # Comment\n\
+                       \n\
+                       Refs: #42\n\
+                       \n\
+                       \n";
+        let report = validate_commit_message_report_with(message, &ValidationOptions::default());
+        assert!(report.is_ok());
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(
+            report.warnings[0].kind,
+            FormatErrorKind::ConsecutiveBlankLines
+        );
+        assert_eq!(report.warnings[0].rule(), RuleId::NoConsecutiveBlankLines);
+        assert_eq!(report.warnings[0].line_number(), Some(3));
+    }
+
+    #[test]
+    fn demote_rules_to_warnings() {
+        let message = "feat: Add warnings\nBody";
+        let mut options = ValidationOptions::default();
+        options
+            .severities
+            .insert(RuleId::SubjectNotCapitalized, Severity::Warning);
+
+        let report = validate_commit_message_report_with(message, &options);
+        assert!(!report.is_ok());
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].kind, FormatErrorKind::NonEmptySecondLine);
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(
+            report.warnings[0].kind,
+            FormatErrorKind::CapitalizedFirstLetter
+        );
+
+        let report = validate_commit_message_report_with("feat: Add warnings", &options);
+        assert!(report.is_ok());
+        assert!(validate_commit_message_with("feat: Add warnings", &options).is_ok());
+        assert!(parse_commit_message_with("feat: Add warnings", &options).is_ok());
+
+        let report = report.into_strict();
+        assert!(!report.is_ok());
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn discard_trailing_punctuation() {
+        let error = validate_commit_message("feat: add thing.").unwrap_err();
+        assert_eq!(error.kind, FormatErrorKind::TrailingPunctuation('.'));
+        assert_eq!(error.column(), Some(16));
+
+        // The period following a version number still ends the subject
+        let error = validate_commit_message("feat: bump to v1.2.").unwrap_err();
+        assert_eq!(error.kind, FormatErrorKind::TrailingPunctuation('.'));
+        assert_eq!(error.column(), Some(19));
+        assert!(validate_commit_message("feat: bump to v1.2").is_ok());
+        assert!(validate_commit_message("feat: add thing...").is_err());
+        assert!(validate_commit_message("feat: add thing!").is_ok());
+        assert!(validate_commit_message("feat: add thing?").is_ok());
+
+        let mut options = ValidationOptions {
+            subject_trailing_punctuation: ".!?…".to_owned(),
+            ..ValidationOptions::default()
+        };
+        let error = validate_commit_message_with("feat: add thing…", &options).unwrap_err();
+        assert_eq!(error.kind, FormatErrorKind::TrailingPunctuation('…'));
+        assert_eq!(error.column(), Some(16));
+        assert!(validate_commit_message_with("feat: add thing!", &options).is_err());
+
+        options.subject_trailing_punctuation = String::new();
+        assert!(validate_commit_message_with("feat: add thing.", &options).is_ok());
+
+        let mut options = ValidationOptions::default();
+        options
+            .disabled_rules
+            .insert(RuleId::SubjectNoTrailingPunctuation);
+        assert!(validate_commit_message_with("feat: add thing.", &options).is_ok());
+    }
+
+    #[test]
+    fn warn_about_long_subjects() {
+        let message = "feat(parser): support the footers spanning several lines";
+        assert!(
+            validate_commit_message_report_with(message, &ValidationOptions::default())
+                .warnings
+                .is_empty()
+        );
+
+        let options = ValidationOptions {
+            soft_subject_limit: Some(40),
+            ..ValidationOptions::default()
+        };
+        let report = validate_commit_message_report_with(message, &options);
+        assert!(report.is_ok());
+        assert_eq!(report.warnings.len(), 1);
+        let warning = &report.warnings[0];
+        assert_eq!(warning.kind, FormatErrorKind::SubjectTooLong(40));
+        assert_eq!(warning.rule(), RuleId::SubjectMaxLength);
+        // The span starts at the 41st character of the subject
+        assert_eq!(warning.column(), Some(55));
+        assert_eq!(warning.span().unwrap().end(), message.len());
+        assert!(validate_commit_message_with(message, &options).is_ok());
+
+        let report = report.into_strict();
+        assert!(!report.is_ok());
+        assert!(validate_commit_message_with("feat(parser): support footers", &options).is_ok());
+    }
+
+    #[test]
+    fn warn_about_type_echoed_in_subject() {
+        let options = ValidationOptions::default();
+        let report = validate_commit_message_report_with("fix: fix crash in parser", &options);
+        assert!(report.is_ok());
+        assert_eq!(report.warnings.len(), 1);
+        let warning = &report.warnings[0];
+        assert_eq!(warning.kind, FormatErrorKind::SubjectEchoesType);
+        assert_eq!(warning.column(), Some(6));
+        assert_eq!(warning.span().unwrap().end(), 8);
+
+        let report = validate_commit_message_report_with("docs: docs update", &options);
+        assert_eq!(report.warnings[0].kind, FormatErrorKind::SubjectEchoesType);
+        let report = validate_commit_message_report_with("fix(parser): fix, then test", &options);
+        assert_eq!(report.warnings[0].column(), Some(14));
+
+        for message in &[
+            "fix: fixture loading",
+            "fix: prefix the names",
+            "docs: document",
+        ] {
+            let report = validate_commit_message_report_with(message, &options);
+            assert!(report.warnings.is_empty(), "{}", message);
+        }
+    }
+
+    #[test]
+    fn warn_about_non_imperative_subjects() {
+        let options = ValidationOptions::default();
+        let report = validate_commit_message_report_with("feat(cli): added colors", &options);
+        assert!(report.is_ok());
+        assert_eq!(report.warnings.len(), 1);
+        let warning = &report.warnings[0];
+        assert_eq!(
+            warning.kind,
+            FormatErrorKind::NonImperativeSubject {
+                word: "added".to_owned(),
+                suggestion: Some("add".to_owned()),
+            }
+        );
+        assert_eq!(warning.column(), Some(12));
+        assert_eq!(warning.span().unwrap().end(), 16);
+        assert_eq!(
+            warning.kind.to_string(),
+            "Subject should be in the imperative mood, use 'add' instead of 'added'"
+        );
+
+        let options = ValidationOptions {
+            imperative_denied_words: vec!["misc".to_owned()],
+            ..ValidationOptions::default()
+        };
+        let report = validate_commit_message_report_with("chore: misc changes", &options);
+        assert_eq!(
+            report.warnings[0].kind.to_string(),
+            "Subject should be in the imperative mood, 'misc' is not"
+        );
+        assert!(
+            validate_commit_message_report_with("fix: process empty input", &options)
+                .warnings
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn allow_capitalized_words() {
+        assert!(validate_commit_message("feat: JSON output for errors").is_ok());
+        assert!(validate_commit_message("fix: HTTP2 support").is_ok());
+        assert!(validate_commit_message("fix: Windows path handling").is_err());
+        assert!(validate_commit_message("feat: A new option").is_err());
+
+        let options = ValidationOptions {
+            allowed_capitalized_words: vec!["Windows".to_owned()],
+            ..ValidationOptions::default()
+        };
+        assert!(validate_commit_message_with("fix: Windows path handling", &options).is_ok());
+        assert!(validate_commit_message_with("fix: WindowsPath handling", &options).is_err());
+        let error = validate_commit_message_with("feat: Add colors", &options).unwrap_err();
+        assert_eq!(error.kind, FormatErrorKind::CapitalizedFirstLetter);
+    }
+
+    #[test]
+    fn disable_rules() {
+        let message = "feat: Add rules\nBody";
+        let mut options = ValidationOptions::default();
+        options.disabled_rules.insert(RuleId::SubjectNotCapitalized);
+        let errors = validate_commit_message_all_with(message, &options).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, FormatErrorKind::NonEmptySecondLine);
+
+        options.disabled_rules.insert(RuleId::SecondLineEmpty);
+        assert!(validate_commit_message_with(message, &options).is_ok());
+        assert!(parse_commit_message_with(message, &options).is_ok());
+
+        // A message without header cannot be parsed, even if its errors are not reported
+        options.disabled_rules.insert(RuleId::HeaderFormat);
+        assert!(validate_commit_message_with("add rules", &options).is_ok());
+        assert_eq!(
+            parse_commit_message_with("add rules", &options)
+                .unwrap_err()
+                .kind,
+            FormatErrorKind::NoColumn
+        );
+    }
+
+    #[test]
+    fn configure_ignored_messages() {
+        let mut options = ValidationOptions::default();
+        options.ignore_prefixes.push("Release:".to_owned());
+        #[cfg(feature = "regex")]
+        {
+            options
+                .ignore_patterns
+                .push(Pattern::new(r"^\[bot\] ").unwrap());
+            assert!(validate_commit_message_with("[bot] Update dependencies", &options).is_ok());
+        }
+        assert!(validate_commit_message_with("Release: 1.2.0", &options).is_ok());
+        assert!(validate_commit_message_with("WIP", &options).is_ok());
+        assert!(validate_commit_message_with("Update [bot] dependencies", &options).is_err());
+        assert!(validate_commit_message_with("Release 1.2.0", &options).is_err());
+
+        options.ignore_prefixes.clear();
+        assert!(validate_commit_message_with("WIP: feat: add things", &options).is_err());
+        assert!(validate_commit_message_with("Merge branch 'develop'", &options).is_err());
+    }
+
+    #[test]
+    fn require_issue_references() {
+        let options = ValidationOptions {
+            require_reference: vec![CommitType::Fix],
+            ..ValidationOptions::default()
+        };
+        let error = validate_commit_message_with("fix: handle empty input", &options).unwrap_err();
+        assert_eq!(
+            error.kind,
+            FormatErrorKind::MissingIssueReference {
+                pattern: ValidationOptions::DEFAULT_REFERENCE_PATTERN.to_owned()
+            }
+        );
+        assert_eq!(error.rule(), RuleId::IssueReference);
+        assert_eq!(error.line_number(), Some(1));
+
+        for message in &[
+            "fix: handle empty input\n\nCloses: #42",
+            "fix: handle empty input (#42)",
+            "fix: handle empty input\n\nThe parser panicked, see PROJ-42.",
+            "feat: add colors",
+        ] {
+            assert!(
+                validate_commit_message_with(message, &options).is_ok(),
+                "{:?}",
+                message
+            );
+        }
+        assert!(
+            validate_commit_message_with("fix: handle empty input\n\n# Closes: #42", &options)
+                .is_err()
+        );
+
+        #[cfg(feature = "regex")]
+        {
+            let options = ValidationOptions {
+                reference_pattern: Pattern::new(r"GH-\d+").unwrap(),
+                ..options
+            };
+            let message = "fix: handle empty input (GH-42)";
+            assert!(validate_commit_message_with(message, &options).is_ok());
+            let message = "fix: handle empty input (#42)";
+            assert!(validate_commit_message_with(message, &options).is_err());
+        }
+    }
+
+    #[test]
+    fn require_signoffs() {
+        let options = ValidationOptions {
+            require_signoff: true,
+            ..ValidationOptions::default()
+        };
+        let error = validate_commit_message_with("fix: handle empty input", &options).unwrap_err();
+        assert_eq!(error.kind, FormatErrorKind::MissingSignOff);
+        assert_eq!(error.rule(), RuleId::SignOff);
+        assert_eq!(error.line_number(), None);
+
+        let message = "fix: handle empty input\n\n\
+                       Signed-off-by: Jane Doe <jane@example.com>\n\
+                       signed-off-by: John Doe <john.doe@mail.example.org>";
+        assert!(validate_commit_message_with(message, &options).is_ok());
+        assert!(
+            validate_commit_message("fix: handle empty input\n\nSigned-off-by: Jane Doe").is_ok()
+        );
+
+        for signoff in &[
+            "Jane Doe",
+            "<jane@example.com>",
+            "Jane Doe jane@example.com",
+            "Jane Doe <jane>",
+            "Jane Doe <jane@example>",
+            "Jane Doe <@example.com>",
+            "Jane Doe <jane doe@example.com>",
+            "Jane Doe <jane@example..com>",
+        ] {
+            let message = format!(
+                "fix: handle empty input\n\n\
+                 Signed-off-by: John Doe <john@example.com>\n\
+                 Signed-off-by: {}",
+                signoff
+            );
+            let errors = validate_commit_message_all_with(&message, &options).unwrap_err();
+            assert_eq!(errors.len(), 1, "{:?}", signoff);
+            assert_eq!(errors[0].kind, FormatErrorKind::MalformedSignOff);
+            assert_eq!(errors[0].line_number(), Some(4));
+            assert_eq!(errors[0].column(), Some(16));
+        }
+    }
+
+    #[test]
+    fn warn_about_malformed_co_authors() {
+        let options = ValidationOptions::default();
+        let message = "feat: add colors\n\nCo-authored-by: Jane Doe <jane@example.com>";
+        assert!(validate_commit_message_report_with(message, &options)
+            .warnings
+            .is_empty());
+
+        let message = "feat: add colors\n\n\
+                       Co-authored-by: Jane Doe jane@example.com\n\
+                       co-authored-by:  <john@example.com>";
+        let report = validate_commit_message_report_with(message, &options);
+        assert!(report.is_ok());
+        assert_eq!(report.warnings.len(), 2);
+        for (warning, line_number) in report.warnings.iter().zip(3..) {
+            assert_eq!(warning.kind, FormatErrorKind::MalformedCoAuthor);
+            assert_eq!(warning.rule(), RuleId::CoAuthorFormat);
+            assert_eq!(warning.line_number(), Some(line_number));
+        }
+        assert_eq!(report.warnings[0].column(), Some(17));
+        assert_eq!(
+            report.warnings[0].span().unwrap().end(),
+            "Co-authored-by: Jane Doe jane@example.com".len()
+        );
+    }
+
+    #[test]
+    fn require_bodies() {
+        let options = ValidationOptions {
+            require_body: vec![CommitType::Feat],
+            ..ValidationOptions::default()
+        };
+        assert!(validate_commit_message("feat: add colors").is_ok());
+        assert!(validate_commit_message_with("fix: handle colors", &options).is_ok());
+        let message = "feat: add colors\n\nThe output is easier to read.\n\nCloses: #42";
+        assert!(validate_commit_message_with(message, &options).is_ok());
+
+        for message in &[
+            "feat: add colors",
+            "feat: add colors\n\n  \n",
+            "feat: add colors\n\nCloses: #42",
+            "feat: add colors\n\n# Please enter the commit message",
+        ] {
+            let error = validate_commit_message_with(message, &options).unwrap_err();
+            assert_eq!(
+                error.kind,
+                FormatErrorKind::MissingBody {
+                    commit_type: "feat".to_owned()
+                },
+                "{:?}",
+                message
+            );
+            assert_eq!(error.rule(), RuleId::BodyRequired);
+            assert_eq!(error.line_number(), Some(1));
+        }
+
+        // The commits squashed in other ones and the reverts do not need a body
+        let options = ValidationOptions {
+            strict_reverts: true,
+            ..options
+        };
+        for message in &[
+            "fixup! feat: add colors",
+            "squash! feat: add colors",
+            "Revert \"feat: add colors\"\n\nThis reverts commit abc.",
+        ] {
+            assert!(
+                validate_commit_message_with(message, &options).is_ok(),
+                "{:?}",
+                message
+            );
+        }
+    }
+
+    #[test]
+    fn validate_pull_request_titles() {
+        let options = ValidationOptions {
+            pr_title: true,
+            require_body: vec![CommitType::Feat],
+            require_signoff: true,
+            ..ValidationOptions::default()
+        };
+        let header = format!("feat(api): add {}", "x".repeat(85));
+        assert_eq!(header.len(), 100);
+        let title = format!("{} (#1234)", header);
+        assert!(validate_commit_message_with(&title, &options).is_ok());
+        let error = validate_commit_message(&title).unwrap_err();
+        assert_eq!(error.kind, FormatErrorKind::LineTooLong(100));
+
+        // Only the header is validated, with the errors located in the title
+        let report = validate_commit_message_report_with("feat: Add colors (#12)\nBody", &options);
+        let errors: Vec<_> = report.errors.iter().map(|e| e.kind.clone()).collect();
+        assert_eq!(errors, vec![FormatErrorKind::CapitalizedFirstLetter]);
+        assert_eq!(report.errors[0].line(), Some("feat: Add colors (#12)"));
+        assert_eq!(report.errors[0].column(), Some(7));
+
+        // Only a pull request number is removed
+        for suffix in &[" (#)", " (#12a)", "(#12)"] {
+            let title = format!("{}{}", header, suffix);
+            assert!(
+                validate_commit_message_with(&title, &options).is_err(),
+                "{:?}",
+                title
+            );
+        }
+        assert!(
+            validate_commit_message_with("Revert \"feat: add colors\" (#12)", &options).is_ok()
+        );
+
+        // The errors of a title starting with a byte order mark are located after it
+        let report = validate_commit_message_report_with("\n\u{feff}:", &options);
+        let error = &report.errors[0];
+        assert_eq!(error.kind, FormatErrorKind::EmptyCommitType);
+        assert_eq!(error.line(), Some("\u{feff}:"));
+        assert_eq!(error.span().unwrap().pos(), 3);
+    }
+
+    #[test]
+    fn reject_forbidden_words() {
+        let options = ValidationOptions {
+            forbidden_words: vec!["temp".to_owned(), "do not merge".to_owned()],
+            ..ValidationOptions::default()
+        };
+        assert!(validate_commit_message("feat: add temp queries").is_ok());
+
+        let error = validate_commit_message_with("feat: add Temp queries", &options).unwrap_err();
+        assert_eq!(
+            error.kind,
+            FormatErrorKind::ForbiddenWord {
+                word: "Temp".to_owned()
+            }
+        );
+        assert_eq!(error.rule(), RuleId::NoForbiddenWords);
+        assert_eq!((error.line_number(), error.column()), (Some(1), Some(11)));
+        assert_eq!(error.span().unwrap().end(), "feat: add Temp".len());
+
+        // Only the first occurrence of each word is reported, and the comments are not searched
+        let message = "feat: add queries\n\n\
+                       # Do not merge\n\
+                       They are temp.\n\
+                       Do NOT merge the temp queries.";
+        let errors = validate_commit_message_all_with(message, &options).unwrap_err();
+        let locations: Vec<_> = errors
+            .iter()
+            .map(|e| (e.kind.to_string(), e.line_number(), e.column()))
+            .collect();
+        assert_eq!(
+            locations,
+            vec![
+                ("Forbidden word 'temp'".to_owned(), Some(4), Some(10)),
+                ("Forbidden word 'Do NOT merge'".to_owned(), Some(5), Some(1)),
+            ]
+        );
+
+        for message in &[
+            "feat: add temporal queries",
+            "feat: add queries\n\nThe attempt failed.",
+            "feat(temp): add queries",
+            "feat: add queries\n\nRefs: temp",
+        ] {
+            assert!(
+                validate_commit_message_with(message, &options).is_ok(),
+                "{:?}",
+                message
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn reject_forbidden_patterns() {
+        let options = ValidationOptions {
+            forbidden_patterns: vec![Pattern::new(r"\bPROJECT-[A-Z]+\b").unwrap()],
+            ..ValidationOptions::default()
+        };
+        let message = "feat: add queries\n\nFor PROJECT-X and PROJECT-Y.";
+        let errors = validate_commit_message_all_with(message, &options).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].kind,
+            FormatErrorKind::ForbiddenWord {
+                word: "PROJECT-X".to_owned()
+            }
+        );
+        assert_eq!(errors[0].line_number(), Some(3));
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn validate_custom_header_pattern() {
+        let options = ValidationOptions {
+            header_pattern: Some(Pattern::new(r"^[A-Z]+-\d+: .+").unwrap()),
+            ..ValidationOptions::default()
+        };
+        let message = parse_commit_message_with("PROJ-42: Add the JIRA format", &options).unwrap();
+        assert_eq!(
+            message.header.commit_type,
+            CommitType::Custom(String::new())
+        );
+        assert_eq!(message.header.scope, None);
+        assert_eq!(message.header.subject, "PROJ-42: Add the JIRA format");
+        assert!(
+            validate_commit_message_with("PROJ-42: Add the JIRA format\nBody", &options).is_err()
+        );
+
+        let error =
+            validate_commit_message_with("feat: add the JIRA format", &options).unwrap_err();
+        assert_eq!(
+            error.kind,
+            FormatErrorKind::HeaderPatternMismatch {
+                pattern: r"^[A-Z]+-\d+: .+".to_owned()
+            }
+        );
+        assert_eq!(
+            error.kind.to_string(),
+            r"Header must match the pattern '^[A-Z]+-\d+: .+'"
+        );
+        assert_eq!(error.rule(), RuleId::HeaderFormat);
+
+        let options = ValidationOptions {
+            header_pattern: Some(
+                Pattern::new(r"^\[(?P<scope>[^\]]*)\] (?P<type>\w*) - (?P<subject>.*)$").unwrap(),
+            ),
+            ..ValidationOptions::default()
+        };
+        let error =
+            parse_commit_message_with("[parser] fix - handle empty input.", &options).unwrap_err();
+        assert_eq!(error.kind, FormatErrorKind::TrailingPunctuation('.'));
+        assert_eq!(error.column(), Some(34));
+        let message =
+            parse_commit_message_with("[parser] fix - handle empty input", &options).unwrap();
+        assert_eq!(message.header.commit_type, CommitType::Fix);
+        assert_eq!(message.header.scopes, vec!["parser"]);
+        assert_eq!(message.header.subject, "handle empty input");
+
+        let error =
+            validate_commit_message_with("[parser] feet - handle input", &options).unwrap_err();
+        assert!(matches!(
+            error.kind,
+            FormatErrorKind::InvalidCommitType { .. }
+        ));
+        assert_eq!(error.column(), Some(10));
+        let error =
+            validate_commit_message_with("[parser] fix - Handle input", &options).unwrap_err();
+        assert_eq!(error.kind, FormatErrorKind::CapitalizedFirstLetter);
+        assert_eq!(error.column(), Some(16));
+        let error = validate_commit_message_with("[] fix - handle input", &options).unwrap_err();
+        assert_eq!(error.kind, FormatErrorKind::EmptyScope);
+        let error = validate_commit_message_with("[parser]  - handle input", &options).unwrap_err();
+        assert_eq!(error.kind, FormatErrorKind::EmptyCommitType);
+        let error = validate_commit_message_with("[parser] fix - ", &options).unwrap_err();
+        assert_eq!(error.kind, FormatErrorKind::EmptyCommitSubject);
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn strip_ticket_prefixes() {
+        let message = "[PROJ-42] feat(cli): add colors";
+        let error = validate_commit_message(message).unwrap_err();
+        assert!(matches!(
+            error.kind,
+            FormatErrorKind::InvalidCommitType { .. }
+        ));
+
+        let options = ValidationOptions {
+            allow_ticket_prefix: Some(Pattern::new(r"^\[[A-Z][A-Z0-9]*-\d+\]").unwrap()),
+            ..ValidationOptions::default()
+        };
+        let msg = parse_commit_message_with(message, &options).unwrap();
+        assert_eq!(msg.header.ticket, Some("[PROJ-42]"));
+        assert_eq!(msg.header.commit_type, CommitType::Feat);
+        assert_eq!(msg.header.scope, Some("cli"));
+        assert_eq!(msg.header.subject, "add colors");
+        assert_eq!(msg.header.to_string(), message);
+
+        let msg = parse_commit_message_with("feat: add colors", &options).unwrap();
+        assert_eq!(msg.header.ticket, None);
+
+        let error =
+            validate_commit_message_with("[PROJ-42] feat: Add colors", &options).unwrap_err();
+        assert_eq!(error.kind, FormatErrorKind::CapitalizedFirstLetter);
+        assert_eq!(error.column(), Some(17));
+        let error =
+            validate_commit_message_with("[PROJ-42 feat: add colors", &options).unwrap_err();
+        assert!(matches!(
+            error.kind,
+            FormatErrorKind::InvalidCommitType { .. }
+        ));
+
+        let options = ValidationOptions {
+            max_header_length: 20,
+            ..options
+        };
+        assert!(validate_commit_message_with("feat: add colors", &options).is_ok());
+        let error =
+            validate_commit_message_with("[PROJ-42] feat: add colors", &options).unwrap_err();
+        assert_eq!(error.kind, FormatErrorKind::LineTooLong(20));
+    }
+
+    #[test]
+    fn strip_emoji_prefixes() {
+        let error = validate_commit_message("✨ feat: add colors").unwrap_err();
+        assert_eq!(
+            error.kind,
+            FormatErrorKind::EmojiPrefix {
+                emoji: "✨".to_owned()
+            }
+        );
+        assert_eq!(
+            error.kind.to_string(),
+            "Header appears to start with the emoji '✨'"
+        );
+        assert_eq!(error.rule(), RuleId::HeaderFormat);
+        assert_eq!(error.column(), Some(1));
+        let error = validate_commit_message("fixup! :sparkles: feat: add colors").unwrap_err();
+        assert_eq!(
+            error.kind,
+            FormatErrorKind::EmojiPrefix {
+                emoji: ":sparkles:".to_owned()
+            }
+        );
+        assert_eq!(error.column(), Some(8));
+
+        let options = ValidationOptions {
+            allow_emoji_prefix: true,
+            ..ValidationOptions::default()
+        };
+        let msg = parse_commit_message_with("✨ feat(cli): add colors", &options).unwrap();
+        assert_eq!(msg.header.emoji, Some("✨"));
+        assert_eq!(msg.header.commit_type, CommitType::Feat);
+        assert_eq!(msg.header.subject, "add colors");
+        assert_eq!(msg.header.to_string(), "✨ feat(cli): add colors");
+        let msg = parse_commit_message_with(":bug: fix: handle input", &options).unwrap();
+        assert_eq!(msg.header.emoji, Some(":bug:"));
+        let msg = parse_commit_message_with("feat: add colors", &options).unwrap();
+        assert_eq!(msg.header.emoji, None);
+        let error = validate_commit_message_with("✨ feet: add colors", &options).unwrap_err();
+        assert!(matches!(
+            error.kind,
+            FormatErrorKind::InvalidCommitType { .. }
+        ));
+        assert_eq!(error.column(), Some(3));
+
+        let options = ValidationOptions {
+            max_header_length: 17,
+            ..options
+        };
+        assert!(validate_commit_message_with("feat: add colors", &options).is_ok());
+        let error =
+            validate_commit_message_with(":sparkles: feat: add colors", &options).unwrap_err();
+        assert_eq!(error.kind, FormatErrorKind::LineTooLong(17));
+    }
+
+    #[test]
+    fn resolve_type_aliases() {
+        let mut options = ValidationOptions::default();
+        options
+            .aliases
+            .insert("feature".to_owned(), CommitType::Feat);
+        options.aliases.insert("bugfix".to_owned(), CommitType::Fix);
+
+        let report = validate_commit_message_report_with("feature(cli): add colors", &options);
+        assert!(report.is_ok());
+        assert_eq!(report.warnings.len(), 1);
+        let warning = &report.warnings[0];
+        assert_eq!(
+            warning.kind,
+            FormatErrorKind::NonCanonicalType {
+                commit_type: "feature".to_owned(),
+                canonical: "feat".to_owned(),
+            }
+        );
+        assert_eq!(
+            warning.kind.to_string(),
+            "Commit type 'feature' is an alias, use 'feat' instead"
+        );
+        assert_eq!(warning.rule(), RuleId::TypeCanonical);
+        assert_eq!(warning.column(), Some(1));
+        assert_eq!(
+            warning.suggestion(),
+            Some("write 'feat' instead of 'feature'")
+        );
+
+        let msg = super::check_commit_message(
+            "fixup! bugfix: handle input",
+            &options,
+            &[],
+            &mut Vec::new(),
+        )
+        .unwrap();
+        assert_eq!(msg.header.commit_type, CommitType::Fix);
+        let report = validate_commit_message_report_with("fixup! bugfix: handle input", &options);
+        assert_eq!(report.warnings[0].column(), Some(8));
+        let report = validate_commit_message_report_with("feat: add colors", &options);
+        assert!(report.is_ok() && report.warnings.is_empty());
+
+        options.strict_aliases = true;
+        let error = validate_commit_message_with("feature: add colors", &options).unwrap_err();
+        assert_eq!(error.rule(), RuleId::TypeCanonical);
+        assert_eq!(
+            error.kind.to_string(),
+            "Commit type 'feature' is an alias, use 'feat' instead"
+        );
+
+        assert_eq!(options.colliding_alias(), None);
+        options.aliases.insert("fix".to_owned(), CommitType::Feat);
+        assert_eq!(options.colliding_alias(), Some("fix"));
+    }
+
+    #[test]
+    fn accept_allowed_headers() {
+        assert!(validate_commit_message("Initial commit").is_ok());
+        assert!(validate_commit_message("# Comment\nInitial commit\n\nWith a body").is_ok());
+        assert!(validate_commit_message("Initial commit!").is_err());
+        assert!(validate_commit_message("Release 1.2.3").is_err());
+
+        let mut options = ValidationOptions::default();
+        #[cfg(feature = "regex")]
+        {
+            options
+                .allowed_header_regexes
+                .push(Pattern::new(r"^Release \d+\.\d+\.\d+$").unwrap());
+            assert!(validate_commit_message_with("Release 1.2.3", &options).is_ok());
+            assert!(validate_commit_message_with("Release 1.2.3 and more", &options).is_err());
+        }
+        options.allowed_literal_headers.push("v1.2.3".to_owned());
+        assert!(validate_commit_message_with("v1.2.3", &options).is_ok());
+
+        options.allowed_literal_headers.clear();
+        assert!(validate_commit_message_with("Initial commit", &options).is_err());
+    }
+
+    #[test]
+    fn validate_merges() {
+        let options = ValidationOptions {
+            validate_merges: true,
+            ..ValidationOptions::default()
+        };
+        for message in &[
+            "Merge branch 'feature/parser' into develop",
+            "Merge pull request #42 from Hugal31/feature/parser",
+            "Merge remote-tracking branch 'origin/develop'",
+        ] {
+            assert!(validate_commit_message_with(message, &options).is_ok());
+        }
+
+        let message = "Merge the two parser modules";
+        assert!(validate_commit_message(message).is_ok());
+        let error = validate_commit_message_with(message, &options).unwrap_err();
+        assert_eq!(error.kind, FormatErrorKind::NoColumn);
+    }
+
+    #[test]
+    fn accept_git_reverts() {
+        let path = ::std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/messages/revert-commit.txt");
+        let message = ::std::fs::read_to_string(&path).unwrap();
+        assert!(validate_commit_message(&message).is_ok());
+        assert!(validate_commit_message(r#"Revert "Add: whatever""#).is_ok());
+
+        let options = ValidationOptions {
+            strict_reverts: true,
+            ..ValidationOptions::default()
+        };
+        assert!(validate_commit_message_with(&message, &options).is_ok());
+        assert!(validate_commit_file_with(&path, &options).is_ok());
+        assert!(
+            validate_commit_message_with(r#"Revert "Revert "fix: handle input"""#, &options)
+                .is_ok()
+        );
+
+        let message = "# Comment\nRevert \"Revert \"feat: Add things\"\"";
+        let errors = validate_commit_message_all_with(message, &options).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, FormatErrorKind::CapitalizedFirstLetter);
+        assert_eq!(
+            errors[0].line(),
+            Some("Revert \"Revert \"feat: Add things\"\"")
+        );
+        assert_eq!(errors[0].line_number(), Some(2));
+        assert_eq!(errors[0].column(), Some(23));
+
+        let error =
+            validate_commit_message_with(r#"Revert "feta: add things""#, &options).unwrap_err();
+        assert_eq!(error.column(), Some(9));
+        assert_eq!(error.span().unwrap().end(), 12);
+
+        let errors =
+            validate_commit_message_all_with("Revert \"\u{feff}aRevert \"", &options).unwrap_err();
+        assert_eq!(errors[0].kind, FormatErrorKind::NoColumn);
+        assert_eq!(errors[0].column(), Some(10));
+    }
+
+    #[test]
+    fn ignore_diff_of_verbose_commit() {
+        let path = ::std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/messages/verbose-commit.txt");
+        let options = ValidationOptions {
+            max_body_line_length: 72,
+            ..ValidationOptions::default()
+        };
+        assert!(validate_commit_file_with(&path, &options).is_ok());
+
+        let message = ::std::fs::read_to_string(&path).unwrap();
+        let message = parse_commit_message(&message).unwrap();
+        assert_eq!(
+            message.body,
+            Some("The diff added by `git commit --verbose` is not part of the message.")
+        );
+    }
+
+    #[test]
+    fn validate_message_from_reader() {
+        let options = ValidationOptions::default();
+        assert!(validate_commit_read_with(&b"feat: read from stdin\n"[..], &options).is_ok());
+
+        match validate_commit_read_with(&b""[..], &options) {
+            Err(CommitValidationError::Format(error)) => {
+                assert_eq!(error.kind, FormatErrorKind::EmptyCommitMessage)
+            }
+            result => panic!("Unexpected result {:?}", result),
+        }
+
+        let message = "\u{feff}feat: read from stdin\r\n";
+        assert!(validate_commit_read_with(message.as_bytes(), &options).is_ok());
+        assert_eq!(
+            read_commit_message(message.as_bytes()).unwrap(),
+            "feat: read from stdin\r\n"
+        );
+
+        match validate_commit_read_with(&b"feat: read\xff"[..], &options) {
+            Err(CommitValidationError::Io(error)) => {
+                assert_eq!(error.kind(), IOErrorKind::ReadMessageError);
+                assert_eq!(error.path(), None);
+                assert_eq!(error.to_string(), "Error while reading commit message");
+                assert!(error.source().is_some());
+            }
+            result => panic!("Unexpected result {:?}", result),
+        }
+    }
+
     #[test]
     fn ignore_wip_and_merge_message() {
         assert!(validate_commit_message("Merge branch develop").is_ok());
         assert!(validate_commit_message("WIP: feat: add feature").is_ok());
     }
+
+    #[test]
+    fn detect_breaking_changes() {
+        assert!(parse_commit_message("feat!: remove the old API")
+            .unwrap()
+            .is_breaking());
+        assert!(parse_commit_message(
+            "feat: remove the old API
+
+BREAKING CHANGE: the old API is not available anymore"
+        )
+        .unwrap()
+        .is_breaking());
+        assert!(parse_commit_message(
+            "feat: remove the old API
+
+BREAKING-CHANGE: the old API is not available anymore"
+        )
+        .unwrap()
+        .is_breaking());
+        assert!(!parse_commit_message(
+            "feat: add an API
+
+Breaking change: the token is case-sensitive"
+        )
+        .unwrap()
+        .is_breaking());
+    }
+
+    #[test]
+    fn locate_header_parts() {
+        use super::parse_commit_message_with_spans;
+
+        let options = ValidationOptions::default();
+        let (_, spans) =
+            parse_commit_message_with_spans("feat(lib,cli): add spans", &options).unwrap();
+        assert_eq!(spans.commit_type, 0..4);
+        assert_eq!(spans.scope, Some(5..12));
+        assert_eq!(spans.parentheses, Some(4..13));
+        assert_eq!(spans.breaking, None);
+        assert_eq!(spans.subject, 15..24);
+
+        let (_, spans) = parse_commit_message_with_spans("fix!: fix spans", &options).unwrap();
+        assert_eq!(spans.commit_type, 0..3);
+        assert_eq!(spans.scope, None);
+        assert_eq!(spans.parentheses, None);
+        assert_eq!(spans.breaking, Some(3..4));
+        assert_eq!(spans.subject, 6..15);
+
+        // The ranges are in the original line, after the comments
+        let input = "# Comment\nsquash! fixup! docs(api): fix spans\n\nBody";
+        let (message, spans) = parse_commit_message_with_spans(input, &options).unwrap();
+        let line = "squash! fixup! docs(api): fix spans";
+        assert_eq!(&line[spans.commit_type.clone()], "docs");
+        assert_eq!(spans.commit_type, 15..19);
+        assert_eq!(spans.scope, Some(20..23));
+        assert_eq!(spans.parentheses, Some(19..24));
+        assert_eq!(spans.subject, 26..35);
+        assert_eq!(&line[spans.subject], message.header.subject);
+
+        #[cfg(feature = "regex")]
+        {
+            let options = ValidationOptions {
+                allow_ticket_prefix: Some(Pattern::new(r"^\[[A-Z]+-\d+\]").unwrap()),
+                allow_emoji_prefix: true,
+                ..ValidationOptions::default()
+            };
+            let (_, spans) =
+                parse_commit_message_with_spans("[PROJ-7] ✨ feat(ui)!: add spans", &options)
+                    .unwrap();
+            assert_eq!(spans.ticket, Some(0..8));
+            assert_eq!(spans.emoji, Some(9..12));
+            assert_eq!(spans.commit_type, 13..17);
+            assert_eq!(spans.scope, Some(18..20));
+            assert_eq!(spans.breaking, Some(21..22));
+            assert_eq!(spans.subject, 24..33);
+
+            let options = ValidationOptions {
+                header_pattern: Some(
+                    Pattern::new(r"^\[(?P<scope>\w+)\] (?P<subject>.+)$").unwrap(),
+                ),
+                ..ValidationOptions::default()
+            };
+            let (_, spans) = parse_commit_message_with_spans("[ui] add spans", &options).unwrap();
+            assert_eq!(spans.commit_type, 0..0);
+            assert_eq!(spans.scope, Some(1..3));
+            assert_eq!(spans.parentheses, None);
+            assert_eq!(spans.subject, 5..14);
+            assert!(parse_commit_message_with_spans("add spans", &options).is_err());
+        }
+    }
 }
@@ -1,31 +1,3548 @@
+#[cfg(test)]
+extern crate serde_json;
 extern crate termcolor;
 extern crate validate_commit;
 
-use std::io::Write;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::io::{self, IsTerminal};
+use std::ops::Range;
+use std::path::PathBuf;
 use std::process::exit;
+use std::str::FromStr;
+use std::sync::OnceLock;
 
 use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
+#[cfg(feature = "commitlint-compat")]
+use validate_commit::commitlint::load_commitlint_config;
+use validate_commit::hook::{install_hook, uninstall_hook};
+use validate_commit::{
+    apply_env_overrides, Baseline, CommitValidationError, FormatError, Language, Pattern, RuleId,
+    ScopePolicy, Severity, UnknownRuleError, ValidationOptions, ValidationReport, Validator,
+    ENV_PREFIX,
+};
+#[cfg(feature = "git")]
+use validate_commit::{
+    markdown_changelog, section_title, CommitGroups, CommitMsg, ValidationStats,
+};
+
+/// Name of the standard input in the reports
+const STDIN_NAME: &str = "<stdin>";
+
+/// Exit status when a message is invalid
+const EXIT_INVALID: i32 = 1;
+
+/// Exit status when the messages cannot be validated, because of the usage, a file or the
+/// configuration
+const EXIT_ERROR: i32 = 2;
+
+/// Color choice of the diagnostics, set once the arguments are parsed
+static COLOR_CHOICE: OnceLock<ColorChoice> = OnceLock::new();
+
+/// Language of the diagnostics, set once the arguments are parsed
+///
+/// The machine-readable formats, JSON, GitLab and SARIF, stay in English.
+static LANGUAGE: OnceLock<Language> = OnceLock::new();
+
+const USAGE: &str = "Usage: validate-commit [--max-line-length N] [--max-header-length N] \
+                     [--max-body-line-length N] [--require-scope | --forbid-scope] \
+                     [--comment-char C] [--disable RULE]... [--enable RULE]... [--strict] [--dry-run] [--fix] \
+                     [--ignore-prefix PREFIX]... [--no-default-ignores] [--validate-merges] \
+                     [--allow-pattern REGEX]... [--require-signoff] [--pr-title] \
+                     [--format text|json|github|checkstyle|gitlab|sarif|teamcity|junit] \
+                     [--print] [--print-ast] \
+                     [--color always|auto|never] [--lang en|fr] \
+                     [--config-format native|commitlint] [--no-user-config] [<commit-file> | -]...
+       validate-commit [OPTIONS] --message MESSAGE [--message MESSAGE]...
+       validate-commit [OPTIONS] range [<from>..<to> | --from REV [--to REV]] \
+                     [--no-skip-merges] [--baseline FILE]
+       validate-commit [OPTIONS] head | rev <REV> [--baseline FILE]
+       validate-commit [OPTIONS] pre-receive [--no-skip-merges] [--baseline FILE]
+       validate-commit [OPTIONS] baseline --write FILE [<from>..<to> | --from REV [--to REV]] \
+                     [--no-skip-merges]
+       validate-commit [OPTIONS] bump [<from>..<to> | --from REV [--to REV]]
+       validate-commit [OPTIONS] changelog [<from>..<to> | --from REV [--to REV]] \
+                     [--format markdown|json]
+       validate-commit [OPTIONS] stats [<from>..<to> | --from REV [--to REV]] \
+                     [--no-skip-merges] [--format text|json] [--fail-under PERCENT]
+       validate-commit explain <RULE>
+       validate-commit install-hook [--path <repo>] [--force]
+       validate-commit uninstall-hook [--path <repo>]
+       validate-commit init [--force]
+       validate-commit [OPTIONS] config --show
+       validate-commit completions bash|zsh|fish
+       validate-commit --help | --version";
+
+/// Options of the command line, with the name of their value and their description
+const OPTIONS: [(&str, Option<&str>, &str); 36] = [
+    (
+        "--max-line-length",
+        Some("N"),
+        "Maximum length of the header and of the body lines",
+    ),
+    (
+        "--max-header-length",
+        Some("N"),
+        "Maximum length of the header",
+    ),
+    (
+        "--max-body-line-length",
+        Some("N"),
+        "Maximum length of the body lines",
+    ),
+    ("--require-scope", None, "Require a scope in the header"),
+    ("--forbid-scope", None, "Forbid a scope in the header"),
+    (
+        "--comment-char",
+        Some("C"),
+        "Character starting the comment lines",
+    ),
+    ("--disable", Some("RULE"), "Disable a rule"),
+    (
+        "--enable",
+        Some("RULE"),
+        "Enable a rule disabled by default",
+    ),
+    ("--strict", None, "Treat the warnings as errors"),
+    (
+        "--dry-run",
+        None,
+        "Report the invalid messages without failing",
+    ),
+    (
+        "--fix",
+        None,
+        "Fix the mechanical errors of the commit files in place",
+    ),
+    (
+        "--ignore-prefix",
+        Some("PREFIX"),
+        "Skip the messages starting with the prefix",
+    ),
+    (
+        "--no-default-ignores",
+        None,
+        "Validate the messages starting with \"Merge \" or \"WIP\", skipped by default",
+    ),
+    (
+        "--validate-merges",
+        None,
+        "Validate the merge messages not generated by git",
+    ),
+    (
+        "--allow-pattern",
+        Some("REGEX"),
+        "Accept the headers matching the pattern as they are",
+    ),
+    ("--require-signoff", None, "Require a Signed-off-by footer"),
+    (
+        "--pr-title",
+        None,
+        "Validate only the header, as the title of a pull request",
+    ),
+    (
+        "--format",
+        Some("FORMAT"),
+        "Output format: text, json, github, checkstyle, gitlab, sarif or teamcity; junit for range; \
+         markdown or json for changelog",
+    ),
+    (
+        "--print",
+        None,
+        "Print the valid messages as they are parsed, to debug the rules",
+    ),
+    (
+        "--print-ast",
+        None,
+        "Include the parsed messages in the JSON output, with the positions in their header",
+    ),
+    (
+        "--color",
+        Some("WHEN"),
+        "Color the diagnostics: always, auto or never",
+    ),
+    (
+        "--lang",
+        Some("LANG"),
+        "Language of the diagnostics: en or fr, detected from the locale by default",
+    ),
+    (
+        "--config-format",
+        Some("FORMAT"),
+        "Configuration to read: native, or commitlint's .commitlintrc",
+    ),
+    (
+        "--no-user-config",
+        None,
+        "Ignore the user configuration file, like ~/.config/validate-commit/config.toml",
+    ),
+    (
+        "--message",
+        Some("MESSAGE"),
+        "Validate the message instead of a commit file",
+    ),
+    (
+        "--from",
+        Some("REV"),
+        "Start of the range of commits, excluded",
+    ),
+    (
+        "--to",
+        Some("REV"),
+        "End of the range of commits, HEAD by default",
+    ),
+    (
+        "--no-skip-merges",
+        None,
+        "Validate the merge commits of a range or push",
+    ),
+    (
+        "--fail-under",
+        Some("PERCENT"),
+        "Fail the stats command if fewer commits pass",
+    ),
+    (
+        "--baseline",
+        Some("FILE"),
+        "Ignore the known failures of the commits listed in the file",
+    ),
+    (
+        "--write",
+        Some("FILE"),
+        "File written by the baseline command",
+    ),
+    ("--path", Some("DIR"), "Repository to install the hook in"),
+    (
+        "--force",
+        None,
+        "Replace an existing hook, backed up, or configuration file",
+    ),
+    (
+        "--show",
+        None,
+        "Print the effective configuration, with the config command",
+    ),
+    ("--help", None, "Print this help"),
+    ("--version", None, "Print the version"),
+];
+
+/// Commands of the command line, with their description
+const COMMANDS: [(&str, &str); 14] = [
+    (
+        "range",
+        "Validate the commits of a range, like origin/main..HEAD",
+    ),
+    ("head", "Validate the HEAD commit"),
+    ("rev", "Validate the commit designated by a revision"),
+    (
+        "pre-receive",
+        "Validate the commits pushed, from a pre-receive hook",
+    ),
+    ("bump", "Print the version bump of a range, like v1.0..HEAD"),
+    (
+        "changelog",
+        "Print the changelog of a range, like v1.0..HEAD",
+    ),
+    ("stats", "Print statistics of the messages of a range"),
+    (
+        "baseline",
+        "Record the failing commits of a range, or of the history",
+    ),
+    ("explain", "Describe a rule"),
+    ("install-hook", "Install the commit-msg hook"),
+    ("uninstall-hook", "Remove the commit-msg hook"),
+    (
+        "init",
+        "Write a .validate-commit.toml file with the default options",
+    ),
+    ("config", "Print the effective configuration, with --show"),
+    ("completions", "Print the completion script of a shell"),
+];
+
+/// Shells supported by the `completions` command
+const SHELLS: [&str; 3] = ["bash", "zsh", "fish"];
+
+/// Format of the validation output
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    /// Colored errors, for humans
+    Text,
+    /// A JSON document, for other tools
+    Json,
+    /// Workflow commands, shown as annotations by GitHub Actions
+    Github,
+    /// A Markdown document, only for the changelog
+    Markdown,
+    /// A Checkstyle XML document, read by Jenkins and code quality dashboards
+    Checkstyle,
+    /// A JUnit XML document with a test case per commit, only for the range
+    Junit,
+    /// A GitLab Code Quality JSON document, shown in the merge requests
+    Gitlab,
+    /// A SARIF log, shown by GitHub code scanning
+    Sarif,
+    /// Service messages, shown as build problems by TeamCity
+    Teamcity,
+}
+
+impl FromStr for Format {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "github" => Ok(Format::Github),
+            "markdown" => Ok(Format::Markdown),
+            "checkstyle" => Ok(Format::Checkstyle),
+            "junit" => Ok(Format::Junit),
+            "gitlab" => Ok(Format::Gitlab),
+            "sarif" => Ok(Format::Sarif),
+            "teamcity" => Ok(Format::Teamcity),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Format of the configuration file
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ConfigFormat {
+    /// The `.validate-commit.toml` file
+    Native,
+    /// The `.commitlintrc.json` or `.commitlintrc.yml` file of commitlint
+    Commitlint,
+}
+
+impl FromStr for ConfigFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "native" => Ok(ConfigFormat::Native),
+            "commitlint" => Ok(ConfigFormat::Commitlint),
+            _ => Err(()),
+        }
+    }
+}
+
+/// When to color the diagnostics
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ColorMode {
+    Always,
+    /// Color when writing to a terminal, unless disabled by the environment
+    Auto,
+    Never,
+}
+
+impl FromStr for ColorMode {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "always" => Ok(ColorMode::Always),
+            "auto" => Ok(ColorMode::Auto),
+            "never" => Ok(ColorMode::Never),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Command line arguments
+struct Args {
+    format: Option<Format>,
+    /// Print the parsed messages
+    print: bool,
+    /// Include the parsed messages in the JSON output
+    print_ast: bool,
+    /// Color choice of `--color`, overriding `VALIDATE_COMMIT_COLOR`
+    color: Option<ColorMode>,
+    /// Language of `--lang`, overriding the locale
+    lang: Option<Language>,
+    config_format: ConfigFormat,
+    /// Skip the user configuration file, for reproducible runs
+    no_user_config: bool,
+    /// Paths of the commit files, `-` to read a message from stdin
+    file_paths: Vec<String>,
+    /// Messages to validate given on the command line, instead of commit files
+    messages: Vec<String>,
+    max_header_length: Option<usize>,
+    max_body_line_length: Option<usize>,
+    scope_policy: Option<ScopePolicy>,
+    comment_char: Option<char>,
+    /// Rules to disable (`false`) or enable (`true`), in order
+    rule_toggles: Vec<(RuleId, bool)>,
+    /// Treat the warnings as errors
+    strict: bool,
+    /// Exit successfully even if messages are invalid
+    dry_run: bool,
+    /// Fix the commit files before validating them
+    fix: bool,
+    /// Additional prefixes of the messages to skip
+    ignore_prefixes: Vec<String>,
+    /// Validate the messages starting with the default ignored prefixes
+    no_default_ignores: bool,
+    /// Validate the merge messages which were not generated by git
+    validate_merges: bool,
+    /// Patterns of the additional headers to accept as they are
+    allow_patterns: Vec<Pattern>,
+    /// Require a `Signed-off-by` footer
+    require_signoff: bool,
+    /// Validate the messages as the titles of pull requests
+    pr_title: bool,
+    /// Start of the range of commits to validate, excluded
+    from: Option<String>,
+    /// End of the range of commits to validate
+    to: Option<String>,
+    /// Validate the merge commits of the range
+    no_skip_merges: bool,
+    /// Minimum percentage of valid commits of the `stats` command
+    fail_under: Option<f64>,
+    /// Baseline file of the known failures to ignore
+    baseline: Option<PathBuf>,
+    /// Baseline file written by the `baseline` command
+    write: Option<PathBuf>,
+    /// Print the effective configuration, with the `config` command
+    show: bool,
+}
 
 fn main() {
-    if std::env::args().len() != 2 {
-        eprintln!("Need one argument");
-        exit(1);
+    let mut raw_args = std::env::args().skip(1).peekable();
+    if let Some(arg) = std::env::args()
+        .skip(1)
+        .find(|arg| ["--help", "-h", "--version", "-V"].contains(&arg.as_str()))
+    {
+        if arg == "--help" || arg == "-h" {
+            print!("{}", help());
+        } else {
+            println!("validate-commit {}", env!("CARGO_PKG_VERSION"));
+        }
+        return;
+    }
+
+    if raw_args.peek().map(String::as_str) == Some("completions") {
+        let script = match (raw_args.nth(1), raw_args.next()) {
+            (Some(shell), None) => completions(&shell),
+            _ => None,
+        };
+        match script {
+            Some(script) => print!("{}", script),
+            None => usage_error(&format!("Need one shell among {}", SHELLS.join(", "))),
+        }
+        return;
+    }
+
+    if raw_args.peek().map(String::as_str) == Some("explain") {
+        let code = match (raw_args.nth(1), raw_args.next()) {
+            (Some(code), None) => code,
+            _ => usage_error(&"Need exactly one rule to explain"),
+        };
+        match explain(&code) {
+            Ok(explanation) => print!("{}", explanation),
+            Err(e) => fail(&e),
+        }
+        return;
+    }
+
+    if let Some(command) = raw_args
+        .peek()
+        .filter(|arg| *arg == "install-hook" || *arg == "uninstall-hook")
+        .cloned()
+    {
+        raw_args.next();
+        let args = parse_hook_args(raw_args, command == "install-hook")
+            .unwrap_or_else(|e| usage_error(&e));
+        let result = if command == "install-hook" {
+            std::env::current_exe()
+                .map_err(|e| e.to_string())
+                .and_then(|executable| {
+                    install_hook(&args.path, &executable, args.force).map_err(|e| e.to_string())
+                })
+                .map(|path| format!("Installed hook '{}'", path.display()))
+        } else {
+            uninstall_hook(&args.path)
+                .map(|path| format!("Uninstalled hook '{}'", path.display()))
+                .map_err(|e| e.to_string())
+        };
+        match result {
+            Ok(message) => println!("{}", message),
+            Err(e) => fail(&e),
+        }
+        return;
+    }
+
+    if raw_args.peek().map(String::as_str) == Some("init") {
+        let mut force = false;
+        for arg in raw_args.skip(1) {
+            if arg == "--force" {
+                force = true;
+            } else {
+                usage_error(&format!("Unknown option {}", arg));
+            }
+        }
+        match write_default_config(force) {
+            Ok(message) => println!("{}", message),
+            Err(e) => fail(&e),
+        }
+        return;
+    }
+
+    let mut args = parse_args(raw_args).unwrap_or_else(|e| usage_error(&e));
+    let color = args
+        .color
+        .or_else(|| env_color().unwrap_or_else(|e| fail(&e)))
+        .unwrap_or(ColorMode::Auto);
+    let _ = COLOR_CHOICE.set(color_choice(
+        color,
+        env_flag("NO_COLOR"),
+        env_flag("CLICOLOR_FORCE"),
+        io::stderr().is_terminal(),
+    ));
+    let _ = LANGUAGE.set(
+        args.lang
+            .unwrap_or_else(|| Language::detect(std::env::vars())),
+    );
+    let options = build_options(&args);
+
+    if args.file_paths.first().map(String::as_str) == Some("config") {
+        if !args.show || args.file_paths.len() > 1 {
+            usage_error(&"config needs --show, and no other argument");
+        }
+        print_config(&options);
+        return;
+    }
+    if args.show {
+        usage_error(&"--show only applies to config");
+    }
+    let command = args
+        .file_paths
+        .first()
+        .filter(|arg| COMMIT_COMMANDS.contains(&arg.as_str()))
+        .cloned();
+    if args.fail_under.is_some() && command.as_deref() != Some("stats") {
+        usage_error(&"--fail-under only applies to stats");
+    }
+    if args.format == Some(Format::Junit) && command.as_deref() != Some("range") {
+        usage_error(&"--format junit only applies to range");
+    }
+    if args.write.is_some() != (command.as_deref() == Some("baseline")) {
+        usage_error(&"baseline needs --write FILE, which only applies to it");
+    }
+    let validates_commits = matches!(
+        command.as_deref(),
+        Some("range" | "head" | "rev" | "pre-receive")
+    );
+    if args.baseline.is_some() && !validates_commits {
+        usage_error(&"--baseline only applies to range, head, rev and pre-receive");
+    }
+    if let Some(command) = command {
+        if args.fix {
+            usage_error(&"--fix only applies to commit files");
+        }
+        if args.print {
+            usage_error(&"--print only applies to commit files and messages");
+        }
+        args.file_paths.remove(0);
+        let commits = select_commits(&command, &args).unwrap_or_else(|e| usage_error(&e));
+        match (command.as_str(), &commits) {
+            ("range", Commits::Range(from, to)) if args.format == Some(Format::Junit) => {
+                print_junit(from, to, &args, &options)
+            }
+            ("bump", Commits::Range(from, to)) => print_bump(from, to, args.format, &options),
+            ("changelog", Commits::Range(from, to)) => {
+                print_changelog(from, to, args.format, &options)
+            }
+            ("stats", Commits::Range(from, to)) => print_stats(from, to, &args, &options),
+            ("baseline", _) => write_baseline(&commits, &args, &options),
+            _ => {
+                let baseline = args
+                    .baseline
+                    .as_ref()
+                    .map(|path| Baseline::load(path).unwrap_or_else(|e| fail(&e)));
+                validate_commits(
+                    &commits,
+                    !args.no_skip_merges,
+                    args.strict,
+                    args.dry_run,
+                    baseline.as_ref(),
+                    args.format,
+                    &options,
+                )
+            }
+        }
+        return;
+    }
+
+    // Read from stdin when asked to, or when the message is piped without argument
+    let sources: Vec<Source> = if !args.messages.is_empty() {
+        args.messages
+            .iter()
+            .enumerate()
+            .map(|(i, message)| Source::Message(i + 1, message))
+            .collect()
+    } else if !args.file_paths.is_empty() {
+        args.file_paths
+            .iter()
+            .map(|file_path| match file_path.as_str() {
+                "-" => Source::Stdin,
+                file_path => Source::File(file_path),
+            })
+            .collect()
+    } else if !io::stdin().is_terminal() {
+        vec![Source::Stdin]
+    } else {
+        usage_error(&"Need at least one argument");
+    };
+    let format = args.format.unwrap_or_else(|| {
+        if std::env::var("GITHUB_ACTIONS").is_ok_and(|value| value == "true") {
+            Format::Github
+        } else {
+            Format::Text
+        }
+    });
+    if format == Format::Markdown {
+        usage_error(&"--format markdown only applies to changelog");
+    }
+    if args.fix && sources.iter().any(|source| source.file_path().is_none()) {
+        usage_error(&"--fix only applies to commit files");
+    }
+    let several_files = sources.len() > 1;
+    let validator = Validator::new().with_options(options);
+
+    let mut invalid_count = 0;
+    let mut status = 0;
+    let mut json_reports = Vec::new();
+    let mut checkstyle_files = Vec::new();
+    let mut gitlab_issues = Vec::new();
+    let mut sarif_results = Vec::new();
+    for source in &sources {
+        let name = source.name();
+        let message = match *source {
+            Source::File(file_path) => validate_commit::read_commit_file(file_path),
+            Source::Stdin => validate_commit::read_commit_message(io::stdin()),
+            Source::Message(_, message) => Ok(message.to_owned()),
+        };
+        let message = match message {
+            Ok(message) => message,
+            Err(e) if several_files => {
+                let error = CommitValidationError::from(e);
+                write_error(&WithSources(&error));
+                invalid_count += 1;
+                status = status.max(exit_status(&error));
+                continue;
+            }
+            Err(e) => fail(&WithSources(&e)),
+        };
+        let message = match source.file_path() {
+            Some(file_path) if args.fix => {
+                match fix_file(file_path, &name, message, validator.options()) {
+                    Ok(message) => message,
+                    Err(e) => {
+                        write_error(&e);
+                        status = status.max(EXIT_ERROR);
+                        continue;
+                    }
+                }
+            }
+            _ => message,
+        };
+
+        // A plugin which cannot be run fails like an invalid configuration file
+        let mut report = validator.validate(&message).unwrap_or_else(|e| fail(&e));
+        if args.strict {
+            report = report.into_strict();
+        }
+        match format {
+            Format::Text => {
+                let errors = report.errors.iter().map(|e| (e, Severity::Error));
+                let warnings = report.warnings.iter().map(|e| (e, Severity::Warning));
+                let mut diagnostics: Vec<_> = errors.chain(warnings).collect();
+                diagnostics.sort_by_key(|&(e, _)| (e.line_number(), e.column()));
+                for (i, (error, severity)) in diagnostics.into_iter().enumerate() {
+                    if i > 0 {
+                        eprintln!();
+                    }
+                    write_format_error(severity, &name, error);
+                }
+                if args.print && report.is_ok() {
+                    if let Some(text) = text_ast(&message, validator.options()) {
+                        if several_files {
+                            print!("{}:\n{}", name, indent(&text));
+                        } else {
+                            print!("{}", text);
+                        }
+                    }
+                }
+            }
+            Format::Json => {
+                let ast = if args.print || args.print_ast {
+                    Some(json_ast(&message, validator.options()))
+                } else {
+                    None
+                };
+                if several_files {
+                    json_reports.push(json_file_report(&name, &report, ast.as_deref()));
+                } else {
+                    println!("{}", json_report(&report, ast.as_deref()));
+                }
+            }
+            Format::Github => print!("{}", github_report(source.file_path(), &report)),
+            Format::Checkstyle => checkstyle_files.push(checkstyle_file(&name, &report)),
+            Format::Gitlab => gitlab_issues.extend(gitlab_report(
+                &name,
+                &message,
+                validator.options().comment_char,
+                &report,
+            )),
+            Format::Sarif => sarif_results.extend(sarif_report(&name, &report)),
+            Format::Teamcity => print!("{}", teamcity_report(&name, &report)),
+            Format::Markdown | Format::Junit => unreachable!(),
+        }
+
+        if !report.is_ok() {
+            invalid_count += 1;
+            if !args.dry_run {
+                status = status.max(EXIT_INVALID);
+            }
+        }
+    }
+
+    if several_files {
+        match format {
+            Format::Text => eprintln!("{} of {} messages invalid", invalid_count, sources.len()),
+            Format::Json => println!(
+                r#"{{"ok": {}, "files": [{}]}}"#,
+                invalid_count == 0,
+                json_reports.join(", ")
+            ),
+            Format::Github
+            | Format::Checkstyle
+            | Format::Gitlab
+            | Format::Sarif
+            | Format::Teamcity
+            | Format::Markdown
+            | Format::Junit => {}
+        }
+    }
+    if format == Format::Checkstyle {
+        print!("{}", checkstyle_report(&checkstyle_files));
+    }
+    if format == Format::Gitlab {
+        println!("[{}]", gitlab_issues.join(", "));
+    }
+    if format == Format::Sarif {
+        println!("{}", sarif_log(&sarif_results));
+    }
+
+    if status != 0 {
+        exit(status);
+    }
+}
+
+/// Fix the message of the commit file, and write it back if it changed.
+///
+/// The applied fixes are reported, and the fixed message is returned to be validated.
+fn fix_file(
+    file_path: &str,
+    name: &str,
+    message: String,
+    options: &ValidationOptions,
+) -> Result<String, String> {
+    let (fixed, fixes) = validate_commit::fix_commit_message(&message, options);
+    if fixes.is_empty() {
+        return Ok(message);
+    }
+    std::fs::write(file_path, &fixed)
+        .map_err(|e| format!("Error while writing commit file '{}': {}", file_path, e))?;
+    for fix in fixes {
+        eprintln!("{}: {}", name, fix);
+    }
+    Ok(fixed)
+}
+
+/// Exit status of an error: the format errors make the message invalid, while the other errors
+/// prevent its validation.
+fn exit_status(error: &CommitValidationError) -> i32 {
+    match error {
+        CommitValidationError::Format(_) => EXIT_INVALID,
+        CommitValidationError::Io(_) | CommitValidationError::Config(_) => EXIT_ERROR,
+    }
+}
+
+/// Origin of a validated message
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Source<'a> {
+    /// Commit file
+    File(&'a str),
+    /// Standard input
+    Stdin,
+    /// Message given with `--message`, with its 1-based position
+    Message(usize, &'a str),
+}
+
+impl<'a> Source<'a> {
+    /// Name of the source in the reports
+    fn name(&self) -> String {
+        match *self {
+            Source::File(file_path) => file_path.to_owned(),
+            Source::Stdin => STDIN_NAME.to_owned(),
+            Source::Message(position, _) => format!("<message {}>", position),
+        }
+    }
+
+    fn file_path(&self) -> Option<&'a str> {
+        match *self {
+            Source::File(file_path) => Some(file_path),
+            _ => None,
+        }
+    }
+}
+
+fn build_options(args: &Args) -> ValidationOptions {
+    let mut options = match args.config_format {
+        ConfigFormat::Native => load_native_options(args.no_user_config),
+        ConfigFormat::Commitlint => load_commitlint_options(),
+    };
+    apply_env_overrides(&mut options, env_vars()).unwrap_or_else(|e| fail(&e));
+    if let Some(max_header_length) = args.max_header_length {
+        options.max_header_length = max_header_length;
+    }
+    if let Some(max_body_line_length) = args.max_body_line_length {
+        options.max_body_line_length = max_body_line_length;
+    }
+    if let Some(scope_policy) = args.scope_policy {
+        options.scope_policy = scope_policy;
+    }
+    if let Some(comment_char) = args.comment_char {
+        options.comment_char = comment_char;
+    }
+    if args.no_default_ignores {
+        options.ignore_prefixes.retain(|prefix| {
+            !ValidationOptions::DEFAULT_IGNORE_PREFIXES.contains(&prefix.as_str())
+        });
+    }
+    options
+        .ignore_prefixes
+        .extend(args.ignore_prefixes.iter().cloned());
+    if args.validate_merges {
+        options.validate_merges = true;
+    }
+    if args.require_signoff {
+        options.require_signoff = true;
+    }
+    if args.pr_title {
+        options.pr_title = true;
+    }
+    options
+        .allowed_header_regexes
+        .extend(args.allow_patterns.iter().cloned());
+    for &(rule, enabled) in &args.rule_toggles {
+        if enabled {
+            options.disabled_rules.remove(&rule);
+        } else {
+            options.disabled_rules.insert(rule);
+        }
+    }
+    options
+}
+
+/// Load the user configuration file, unless `no_user_config` is true, overridden by the
+/// configuration file found from the current directory.
+#[cfg(feature = "config")]
+fn load_native_options(no_user_config: bool) -> ValidationOptions {
+    let options = if no_user_config {
+        std::env::current_dir()
+            .map_err(|e| e.to_string())
+            .and_then(|dir| validate_commit::config::load_config(dir).map_err(|e| e.to_string()))
+    } else {
+        validate_commit::load_options().map_err(|e| e.to_string())
+    };
+    options.unwrap_or_else(|e| fail(&e))
+}
+
+#[cfg(not(feature = "config"))]
+fn load_native_options(_no_user_config: bool) -> ValidationOptions {
+    validate_commit::load_options().unwrap_or_else(|e| fail(&e))
+}
+
+/// Import the commitlint configuration found from the current directory, printing the rules
+/// which are ignored.
+#[cfg(feature = "commitlint-compat")]
+fn load_commitlint_options() -> ValidationOptions {
+    let dir = std::env::current_dir().unwrap_or_else(|e| fail(&e));
+    let config = load_commitlint_config(dir).unwrap_or_else(|e| fail(&e));
+    for warning in &config.warnings {
+        eprintln!("warning: {}", warning);
+    }
+    config.options
+}
+
+#[cfg(not(feature = "commitlint-compat"))]
+fn load_commitlint_options() -> ValidationOptions {
+    fail(&"validate-commit was built without the commitlint-compat feature, needed to read commitlint configurations")
+}
+
+/// Write the configuration file with the default options in the current directory, unless it
+/// exists and `force` is false.
+#[cfg(feature = "config")]
+fn write_default_config(force: bool) -> Result<String, String> {
+    let path = validate_commit::config::CONFIG_FILE_NAME;
+    if !force && std::path::Path::new(path).exists() {
+        return Err(format!(
+            "'{}' already exists, use --force to overwrite it",
+            path
+        ));
+    }
+    std::fs::write(path, validate_commit::config::default_config_file())
+        .map_err(|e| format!("Error while writing '{}': {}", path, e))?;
+    Ok(format!("Wrote '{}'", path))
+}
+
+#[cfg(not(feature = "config"))]
+fn write_default_config(_force: bool) -> Result<String, String> {
+    Err(
+        "validate-commit was built without the config feature, needed to write configuration files"
+            .to_owned(),
+    )
+}
+
+/// Print the options, merged from the defaults, the configuration file, the environment and the
+/// command line, as a configuration file.
+#[cfg(feature = "config")]
+fn print_config(options: &ValidationOptions) {
+    print!("{}", validate_commit::config::config_to_toml(options));
+}
+
+#[cfg(not(feature = "config"))]
+fn print_config(_options: &ValidationOptions) {
+    fail(
+        &"validate-commit was built without the config feature, needed to print the configuration",
+    );
+}
+
+/// Commands reading the commits of the repository instead of commit files
+const COMMIT_COMMANDS: [&str; 8] = [
+    "range",
+    "head",
+    "rev",
+    "pre-receive",
+    "bump",
+    "changelog",
+    "stats",
+    "baseline",
+];
+
+/// Commits read by the commands of [`COMMIT_COMMANDS`]
+#[derive(Debug, PartialEq)]
+enum Commits {
+    /// Commits reachable from the end of the range but not from its start
+    Range(String, String),
+    /// Single commit designated by a revision
+    Rev(String),
+    /// Commits pushed by the reference updates read from the standard input
+    PreReceive,
+    /// All the commits reachable from a revision
+    History(String),
+}
+
+/// Return the commits to validate from the command and its remaining arguments.
+fn select_commits(command: &str, args: &Args) -> Result<Commits, String> {
+    match (command, args.file_paths.as_slice(), &args.from) {
+        ("baseline", [], None) => Ok(Commits::History("HEAD".to_owned())),
+        ("range" | "bump" | "changelog" | "stats" | "baseline", [range], None) => {
+            let (from, to) = range
+                .split_once("..")
+                .ok_or_else(|| format!("Invalid range of commits '{}'", range))?;
+            let to = Some(to).filter(|to| !to.is_empty()).unwrap_or("HEAD");
+            Ok(Commits::Range(from.to_owned(), to.to_owned()))
+        }
+        ("range" | "bump" | "changelog" | "stats" | "baseline", [], Some(from)) => {
+            Ok(Commits::Range(
+                from.clone(),
+                args.to.clone().unwrap_or_else(|| "HEAD".to_owned()),
+            ))
+        }
+        ("range" | "bump" | "changelog" | "stats" | "baseline", _, _) => {
+            Err("Need exactly one range of commits".to_owned())
+        }
+        ("head", [], None) => Ok(Commits::Rev("HEAD".to_owned())),
+        ("rev", [rev], None) => Ok(Commits::Rev(rev.clone())),
+        ("pre-receive", [], None) => Ok(Commits::PreReceive),
+        ("pre-receive", _, _) => Err("Unexpected arguments for pre-receive".to_owned()),
+        _ => Err(format!("Need exactly one revision for {}", command)),
+    }
+}
+
+/// Validate the commits, returning their full ids and their reports.
+#[cfg(feature = "git")]
+fn commit_reports(
+    commits: &Commits,
+    skip_merges: bool,
+    options: &ValidationOptions,
+) -> Vec<(String, ValidationReport)> {
+    use std::io::Read;
+    use validate_commit::git::{
+        validate_commit_history, validate_commit_range, validate_commit_rev, validate_ref_updates,
+        RefUpdate,
+    };
+
+    match commits {
+        Commits::Range(from, to) => validate_commit_range(".", from, to, skip_merges, options),
+        Commits::History(to) => validate_commit_history(".", to, skip_merges, options),
+        Commits::Rev(rev) => validate_commit_rev(".", rev, options).map(|report| vec![report]),
+        Commits::PreReceive => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .unwrap_or_else(|e| fail(&e));
+            RefUpdate::parse_all(&input)
+                .and_then(|updates| validate_ref_updates(".", &updates, skip_merges, options))
+        }
+    }
+    .unwrap_or_else(|e| fail(&e))
+    .into_iter()
+    .map(|(oid, report)| (oid.to_string(), report))
+    .collect()
+}
+
+#[cfg(feature = "git")]
+fn validate_commits(
+    commits: &Commits,
+    skip_merges: bool,
+    strict: bool,
+    dry_run: bool,
+    baseline: Option<&Baseline>,
+    format: Option<Format>,
+    options: &ValidationOptions,
+) {
+    if format == Some(Format::Markdown) {
+        usage_error(&"--format markdown only applies to changelog");
+    }
+    let reports = commit_reports(commits, skip_merges, options);
+    let mut invalid_count = 0;
+    let commit_count = reports.len();
+    let mut json_reports = Vec::new();
+    let mut checkstyle_files = Vec::new();
+    let mut gitlab_issues = Vec::new();
+    let mut sarif_results = Vec::new();
+    for (id, mut report) in reports {
+        if strict {
+            report = report.into_strict();
+        }
+        if let Some(baseline) = baseline {
+            report = baseline.apply(&id, report);
+        }
+        if !report.is_ok() {
+            invalid_count += 1;
+        }
+        let name = &id[..7];
+        match format.unwrap_or(Format::Text) {
+            Format::Text => {
+                let errors = report.errors.iter().map(|e| (e, Severity::Error));
+                let warnings = report.warnings.iter().map(|e| (e, Severity::Warning));
+                for (error, severity) in errors.chain(warnings) {
+                    write_format_error(severity, name, error);
+                    eprintln!();
+                }
+            }
+            Format::Json => json_reports.push(json_commit_report(&id, &report)),
+            // The annotations of the commits are not attached to a file
+            Format::Github => print!("{}", github_report(None, &report)),
+            Format::Checkstyle => checkstyle_files.push(checkstyle_file(&id, &report)),
+            // The commit id already identifies the content of the message
+            Format::Gitlab => {
+                gitlab_issues.extend(gitlab_report(&id, &id, options.comment_char, &report))
+            }
+            Format::Sarif => sarif_results.extend(sarif_report(&id, &report)),
+            Format::Teamcity => print!("{}", teamcity_report(name, &report)),
+            Format::Markdown | Format::Junit => unreachable!(),
+        }
+    }
+    match format {
+        Some(Format::Json) => println!(
+            r#"{{"ok": {}, "commits": [{}]}}"#,
+            invalid_count == 0,
+            json_reports.join(", ")
+        ),
+        Some(Format::Checkstyle) => print!("{}", checkstyle_report(&checkstyle_files)),
+        Some(Format::Gitlab) => println!("[{}]", gitlab_issues.join(", ")),
+        Some(Format::Sarif) => println!("{}", sarif_log(&sarif_results)),
+        _ => {}
+    }
+
+    match commits {
+        Commits::Range(..) | Commits::History(..) => {
+            eprintln!("{} of {} commits invalid", invalid_count, commit_count)
+        }
+        Commits::PreReceive if invalid_count > 0 && !dry_run => eprintln!(
+            "Push rejected: {} of {} commits invalid",
+            invalid_count, commit_count
+        ),
+        _ => {}
+    }
+    if invalid_count > 0 && !dry_run {
+        exit(EXIT_INVALID);
+    }
+}
+
+#[cfg(not(feature = "git"))]
+fn validate_commits(
+    _commits: &Commits,
+    _skip_merges: bool,
+    _strict: bool,
+    _dry_run: bool,
+    _baseline: Option<&Baseline>,
+    _format: Option<Format>,
+    _options: &ValidationOptions,
+) {
+    fail(&"validate-commit was built without the git feature, needed to validate commits");
+}
+
+/// Print the JUnit report of the commits of the range, with a test case per commit.
+#[cfg(feature = "git")]
+fn print_junit(from: &str, to: &str, args: &Args, options: &ValidationOptions) {
+    let baseline = args
+        .baseline
+        .as_ref()
+        .map(|path| Baseline::load(path).unwrap_or_else(|e| fail(&e)));
+    let commits =
+        validate_commit::git::validate_range_commits(".", from, to, !args.no_skip_merges, options)
+            .unwrap_or_else(|e| fail(&e));
+    let mut invalid_count = 0;
+    let mut commit_count = 0;
+    let commits: Vec<_> = commits
+        .into_iter()
+        .map(|commit| {
+            let id = commit.id.to_string();
+            let report = commit.report.map(|mut report| {
+                if args.strict {
+                    report = report.into_strict();
+                }
+                if let Some(baseline) = &baseline {
+                    report = baseline.apply(&id, report);
+                }
+                commit_count += 1;
+                if !report.is_ok() {
+                    invalid_count += 1;
+                }
+                report
+            });
+            (id[..7].to_owned(), commit.header, report)
+        })
+        .collect();
+    print!(
+        "{}",
+        junit_report(&format!("validate-commit {}..{}", from, to), &commits)
+    );
+    eprintln!("{} of {} commits invalid", invalid_count, commit_count);
+    if invalid_count > 0 && !args.dry_run {
+        exit(EXIT_INVALID);
+    }
+}
+
+#[cfg(not(feature = "git"))]
+fn print_junit(_from: &str, _to: &str, _args: &Args, _options: &ValidationOptions) {
+    fail(&"validate-commit was built without the git feature, needed to validate commits");
+}
+
+/// Write the baseline file of the failing commits.
+#[cfg(feature = "git")]
+fn write_baseline(commits: &Commits, args: &Args, options: &ValidationOptions) {
+    let path = args.write.as_ref().unwrap();
+    let reports = commit_reports(commits, !args.no_skip_merges, options);
+    let commit_count = reports.len();
+    let mut baseline = Baseline::new();
+    for (id, mut report) in reports {
+        if args.strict {
+            report = report.into_strict();
+        }
+        baseline.record(&id, &report);
+    }
+    baseline.save(path).unwrap_or_else(|e| fail(&e));
+    eprintln!(
+        "{} of {} commits recorded in '{}'",
+        baseline.len(),
+        commit_count,
+        path.display()
+    );
+}
+
+#[cfg(not(feature = "git"))]
+fn write_baseline(_commits: &Commits, _args: &Args, _options: &ValidationOptions) {
+    fail(&"validate-commit was built without the git feature, needed to read commits");
+}
+
+/// Print the version bump recommended to release the commits of the range.
+#[cfg(feature = "git")]
+fn print_bump(from: &str, to: &str, format: Option<Format>, options: &ValidationOptions) {
+    if !matches!(format, None | Some(Format::Text)) {
+        usage_error(&"The version bump can only be written as text");
+    }
+    let bump = validate_commit::git::recommend_range_bump(".", from, to, options)
+        .unwrap_or_else(|e| fail(&e));
+    println!("{}", bump);
+}
+
+#[cfg(not(feature = "git"))]
+fn print_bump(_from: &str, _to: &str, _format: Option<Format>, _options: &ValidationOptions) {
+    fail(&"validate-commit was built without the git feature, needed to read commits");
+}
+
+/// Print the changelog of the commits of the range, and the commits left out of it.
+#[cfg(feature = "git")]
+fn print_changelog(from: &str, to: &str, format: Option<Format>, options: &ValidationOptions) {
+    let format = match format {
+        None | Some(Format::Markdown) => Format::Markdown,
+        Some(Format::Json) => Format::Json,
+        Some(_) => usage_error(&"The changelog can only be written as markdown or json"),
+    };
+    let range = validate_commit::git::read_commit_range(".", from, to, options)
+        .unwrap_or_else(|e| fail(&e));
+
+    let ids: Vec<_> = range
+        .commits
+        .iter()
+        .map(|(oid, _)| oid.to_string()[..7].to_owned())
+        .collect();
+    let msgs: Vec<_> = range.commits.iter().map(|(_, msg)| msg.as_msg()).collect();
+    let groups = CommitGroups::new(ids.iter().map(String::as_str).zip(&msgs), |&(_, msg)| msg);
+    let skipped: Vec<_> = range
+        .skipped
+        .iter()
+        .map(|(oid, reason)| (oid.to_string()[..7].to_owned(), reason.as_str()))
+        .collect();
+    if format == Format::Json {
+        println!("{}", json_changelog(&groups, &skipped));
+        return;
+    }
+
+    print!("{}", markdown_changelog(&groups));
+    for (id, reason) in &skipped {
+        eprintln!("Skipped {} commit {}", reason, id);
+    }
+}
+
+/// Print the statistics of the messages of the range, failing if too few of them are valid.
+#[cfg(feature = "git")]
+fn print_stats(from: &str, to: &str, args: &Args, options: &ValidationOptions) {
+    let format = match args.format {
+        None | Some(Format::Text) => Format::Text,
+        Some(Format::Json) => Format::Json,
+        Some(_) => usage_error(&"The stats can only be written as text or json"),
+    };
+    let stats = validate_commit::git::range_stats(".", from, to, !args.no_skip_merges, options)
+        .unwrap_or_else(|e| fail(&e));
+    match format {
+        Format::Json => println!("{}", json_stats(&stats)),
+        _ => print!("{}", stats_table(&stats)),
+    }
+
+    if let Some(threshold) = args.fail_under {
+        if stats.pass_rate() < threshold {
+            eprintln!(
+                "{:.1}% of the commits pass, under {}%",
+                stats.pass_rate(),
+                threshold
+            );
+            exit(EXIT_INVALID);
+        }
+    }
+}
+
+#[cfg(not(feature = "git"))]
+fn print_stats(_from: &str, _to: &str, _args: &Args, _options: &ValidationOptions) {
+    fail(&"validate-commit was built without the git feature, needed to read commits");
+}
+
+#[cfg(not(feature = "git"))]
+fn print_changelog(_from: &str, _to: &str, _format: Option<Format>, _options: &ValidationOptions) {
+    fail(&"validate-commit was built without the git feature, needed to read commits");
+}
+
+/// Arguments of the `install-hook` and `uninstall-hook` commands
+#[derive(Debug)]
+struct HookArgs {
+    /// Directory of the repository
+    path: PathBuf,
+    /// Replace an existing hook
+    force: bool,
+}
+
+fn parse_hook_args<I: Iterator<Item = String>>(
+    mut args: I,
+    allow_force: bool,
+) -> Result<HookArgs, String> {
+    let mut path = PathBuf::from(".");
+    let mut force = false;
+    while let Some(arg) = args.next() {
+        if arg == "--path" {
+            path = parse_value(&arg, args.next())?;
+        } else if arg == "--force" && allow_force {
+            force = true;
+        } else {
+            return Err(format!("Unknown option {}", arg));
+        }
+    }
+    Ok(HookArgs { path, force })
+}
+
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Args, String> {
+    let mut file_paths = Vec::new();
+    let mut messages = Vec::new();
+    let mut format = None;
+    let mut print = false;
+    let mut print_ast = false;
+    let mut color = None;
+    let mut lang = None;
+    let mut config_format = ConfigFormat::Native;
+    let mut no_user_config = false;
+    let mut max_header_length = None;
+    let mut max_body_line_length = None;
+    let mut scope_policy = None;
+    let mut comment_char = None;
+    let mut rule_toggles = Vec::new();
+    let mut strict = false;
+    let mut dry_run = false;
+    let mut fix = false;
+    let mut ignore_prefixes = Vec::new();
+    let mut no_default_ignores = false;
+    let mut validate_merges = false;
+    let mut allow_patterns = Vec::new();
+    let mut require_signoff = false;
+    let mut pr_title = false;
+    let mut from = None;
+    let mut to = None;
+    let mut no_skip_merges = false;
+    let mut fail_under = None;
+    let mut baseline = None;
+    let mut write = None;
+    let mut show = false;
+
+    while let Some(arg) = args.next() {
+        if arg == "--max-line-length" {
+            let value = parse_value(&arg, args.next())?;
+            max_header_length = Some(value);
+            max_body_line_length = Some(value);
+        } else if arg == "--max-header-length" {
+            max_header_length = Some(parse_value(&arg, args.next())?);
+        } else if arg == "--max-body-line-length" {
+            max_body_line_length = Some(parse_value(&arg, args.next())?);
+        } else if arg == "--comment-char" {
+            comment_char = Some(parse_value(&arg, args.next())?);
+        } else if arg == "--disable" || arg == "--enable" {
+            let value = args
+                .next()
+                .ok_or_else(|| format!("Missing value for {}", arg))?;
+            let rule = value.parse().map_err(|e| format!("{}: {}", arg, e))?;
+            rule_toggles.push((rule, arg == "--enable"));
+        } else if arg == "--ignore-prefix" {
+            ignore_prefixes.push(parse_value(&arg, args.next())?);
+        } else if arg == "--no-default-ignores" {
+            no_default_ignores = true;
+        } else if arg == "--allow-pattern" {
+            allow_patterns.push(parse_value(&arg, args.next())?);
+        } else if arg == "--validate-merges" {
+            validate_merges = true;
+        } else if arg == "--from" {
+            from = Some(parse_value(&arg, args.next())?);
+        } else if arg == "--to" {
+            to = Some(parse_value(&arg, args.next())?);
+        } else if arg == "--no-skip-merges" {
+            no_skip_merges = true;
+        } else if arg == "--fail-under" {
+            let value: f64 = parse_value(&arg, args.next())?;
+            if !(0.0..=100.0).contains(&value) {
+                return Err(format!("Invalid value for {}: '{}'", arg, value));
+            }
+            fail_under = Some(value);
+        } else if arg == "--baseline" {
+            baseline = Some(parse_value(&arg, args.next())?);
+        } else if arg == "--write" {
+            write = Some(parse_value(&arg, args.next())?);
+        } else if arg == "--require-signoff" {
+            require_signoff = true;
+        } else if arg == "--pr-title" {
+            pr_title = true;
+        } else if arg == "--message" {
+            messages.push(parse_value(&arg, args.next())?);
+        } else if arg == "--strict" {
+            strict = true;
+        } else if arg == "--dry-run" {
+            dry_run = true;
+        } else if arg == "--fix" {
+            fix = true;
+        } else if arg == "--show" {
+            show = true;
+        } else if arg == "--format" {
+            format = Some(parse_value(&arg, args.next())?);
+        } else if arg == "--print" {
+            print = true;
+        } else if arg == "--print-ast" {
+            print_ast = true;
+        } else if arg == "--color" {
+            color = Some(parse_value(&arg, args.next())?);
+        } else if arg == "--lang" {
+            lang = Some(parse_value(&arg, args.next())?);
+        } else if arg == "--config-format" {
+            config_format = parse_value(&arg, args.next())?;
+        } else if arg == "--no-user-config" {
+            no_user_config = true;
+        } else if arg == "--require-scope" || arg == "--forbid-scope" {
+            let policy = if arg == "--require-scope" {
+                ScopePolicy::Required
+            } else {
+                ScopePolicy::Forbidden
+            };
+            if scope_policy.is_some_and(|p| p != policy) {
+                return Err("--require-scope and --forbid-scope are mutually exclusive".to_owned());
+            }
+            scope_policy = Some(policy);
+        } else if arg.starts_with("--") {
+            return Err(format!("Unknown option {}", arg));
+        } else {
+            file_paths.push(arg);
+        }
+    }
+
+    if !messages.is_empty() && !file_paths.is_empty() {
+        return Err("--message cannot be combined with commit files or commands".to_owned());
+    }
+
+    if print_ast && format != Some(Format::Json) {
+        return Err("--print-ast only applies to --format json".to_owned());
+    }
+
+    if print
+        && matches!(
+            format,
+            Some(
+                Format::Github
+                    | Format::Checkstyle
+                    | Format::Gitlab
+                    | Format::Sarif
+                    | Format::Teamcity
+            )
+        )
+    {
+        return Err("--print only applies to --format text and json".to_owned());
+    }
+
+    Ok(Args {
+        format,
+        print,
+        print_ast,
+        color,
+        lang,
+        config_format,
+        no_user_config,
+        file_paths,
+        messages,
+        max_header_length,
+        max_body_line_length,
+        scope_policy,
+        comment_char,
+        rule_toggles,
+        strict,
+        dry_run,
+        fix,
+        ignore_prefixes,
+        no_default_ignores,
+        validate_merges,
+        allow_patterns,
+        require_signoff,
+        pr_title,
+        from,
+        to,
+        no_skip_merges,
+        fail_under,
+        baseline,
+        write,
+        show,
+    })
+}
+
+fn parse_value<T: FromStr>(arg: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("Missing value for {}", arg))?;
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value for {}: '{}'", arg, value))
+}
+
+/// Describe the commands and the options.
+fn help() -> String {
+    let mut text = format!(
+        "validate-commit {}\nValidate commit messages following the Conventional Commits \
+         specification.\n\n{}\n\nCommands:\n",
+        env!("CARGO_PKG_VERSION"),
+        USAGE
+    );
+    for &(command, description) in &COMMANDS {
+        text += &format!("    {:<26}{}\n", command, description);
+    }
+    text += "\nOptions:\n";
+    for &(option, value, description) in &OPTIONS {
+        let usage = match value {
+            Some(value) => format!("{} {}", option, value),
+            None => option.to_owned(),
+        };
+        text += &format!("    {:<26}{}\n", usage, description);
+    }
+    text +=
+        "\nEnvironment:\n    VALIDATE_COMMIT_MAX_LINE_LENGTH, VALIDATE_COMMIT_MAX_HEADER_LENGTH, \
+             VALIDATE_COMMIT_MAX_BODY_LINE_LENGTH,\n    VALIDATE_COMMIT_ALLOWED_TYPES, \
+             VALIDATE_COMMIT_REQUIRE_SCOPE, VALIDATE_COMMIT_COLOR\n        \
+             Override the configuration file, under the options\n";
+    text += "\nExit status:\n    0  The messages are valid\n    1  A message is invalid\n    \
+             2  A message cannot be validated, because of the usage, a file or the configuration\n";
+    text
+}
+
+/// Values accepted by the option, if they can be listed.
+fn option_values(option: &str) -> Option<Vec<&'static str>> {
+    match option {
+        "--format" => Some(vec![
+            "text",
+            "json",
+            "github",
+            "markdown",
+            "checkstyle",
+            "gitlab",
+            "sarif",
+            "teamcity",
+            "junit",
+        ]),
+        "--color" => Some(vec!["always", "auto", "never"]),
+        "--lang" => Some(
+            Language::ALL
+                .iter()
+                .map(|language| language.code())
+                .collect(),
+        ),
+        "--config-format" => Some(vec!["native", "commitlint"]),
+        "--disable" | "--enable" => Some(rule_names()),
+        _ => None,
+    }
+}
+
+/// Generate the completion script of the shell, or `None` if it is not supported.
+fn completions(shell: &str) -> Option<String> {
+    match shell {
+        "bash" => Some(bash_completions()),
+        "zsh" => Some(zsh_completions()),
+        "fish" => Some(fish_completions()),
+        _ => None,
+    }
+}
+
+fn bash_completions() -> String {
+    let mut value_cases = String::new();
+    for &(option, value, _) in &OPTIONS {
+        if value.is_none() {
+            continue;
+        }
+        let words = match option_values(option) {
+            Some(values) => format!(
+                "COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))",
+                values.join(" ")
+            ),
+            None if option == "--path" => "COMPREPLY=($(compgen -d -- \"$cur\"))".to_owned(),
+            None => "COMPREPLY=()".to_owned(),
+        };
+        value_cases += &format!("        {}) {}; return ;;\n", option, words);
+    }
+    let options: Vec<_> = OPTIONS.iter().map(|&(option, _, _)| option).collect();
+    let commands: Vec<_> = COMMANDS.iter().map(|&(command, _)| command).collect();
+    format!(
+        r#"_validate_commit() {{
+    local cur="${{COMP_WORDS[COMP_CWORD]}}"
+    local prev="${{COMP_WORDS[COMP_CWORD-1]}}"
+    case "$prev" in
+{}        explain) COMPREPLY=($(compgen -W "{}" -- "$cur")); return ;;
+        completions) COMPREPLY=($(compgen -W "{}" -- "$cur")); return ;;
+    esac
+    if [[ "$cur" == -* ]]; then
+        COMPREPLY=($(compgen -W "{}" -- "$cur"))
+    elif [[ $COMP_CWORD -eq 1 ]]; then
+        COMPREPLY=($(compgen -W "{}" -- "$cur") $(compgen -f -- "$cur"))
+    else
+        COMPREPLY=($(compgen -f -- "$cur"))
+    fi
+}}
+complete -F _validate_commit validate-commit
+"#,
+        value_cases,
+        rule_names().join(" "),
+        SHELLS.join(" "),
+        options.join(" "),
+        commands.join(" ")
+    )
+}
+
+fn zsh_completions() -> String {
+    let mut arguments = String::new();
+    for &(option, value, description) in &OPTIONS {
+        let description = zsh_escape(description);
+        let repeatable = if value.is_some() { "*" } else { "" };
+        let action = match (value, option_values(option)) {
+            (None, _) => String::new(),
+            (Some(value), Some(values)) => format!(":{}:({})", value, values.join(" ")),
+            (Some(value), None) if option == "--path" => format!(":{}:_directories", value),
+            (Some(value), None) => format!(":{}: ", value),
+        };
+        arguments += &format!(
+            "    '{}{}[{}]{}' \\\n",
+            repeatable, option, description, action
+        );
+    }
+    let commands: Vec<_> = COMMANDS
+        .iter()
+        .map(|&(command, description)| format!("'{}:{}'", command, zsh_escape(description)))
+        .collect();
+    format!(
+        "#compdef validate-commit\n\nlocal state\nlocal -a commands\ncommands=({})\n\n\
+         _arguments -s \\\n{}    '1: :->first' \\\n    '*:file:_files'\n\n\
+         if [[ $state == first ]]; then\n    _describe command commands\n    _files\nfi\n",
+        commands.join(" "),
+        arguments
+    )
+}
+
+fn fish_completions() -> String {
+    let mut script = String::new();
+    for &(option, value, description) in &OPTIONS {
+        let mut line = format!(
+            "complete -c validate-commit -l {} -d {}",
+            &option[2..],
+            fish_quote(description)
+        );
+        match (value, option_values(option)) {
+            (None, _) => {}
+            (Some(_), Some(values)) => line += &format!(" -x -a {}", fish_quote(&values.join(" "))),
+            (Some(_), None) => line += " -r",
+        }
+        script += &line;
+        script.push('\n');
+    }
+    for &(command, description) in &COMMANDS {
+        script += &format!(
+            "complete -c validate-commit -n __fish_use_subcommand -a {} -d {}\n",
+            command,
+            fish_quote(description)
+        );
+    }
+    script += &format!(
+        "complete -c validate-commit -n '__fish_seen_subcommand_from explain' -x -a {}\n\
+         complete -c validate-commit -n '__fish_seen_subcommand_from completions' -x -a {}\n",
+        fish_quote(&rule_names().join(" ")),
+        fish_quote(&SHELLS.join(" "))
+    );
+    script
+}
+
+fn rule_names() -> Vec<&'static str> {
+    RuleId::ALL.iter().map(|rule| rule.as_str()).collect()
+}
+
+/// Escape a description in a zsh `_arguments` specification.
+fn zsh_escape(s: &str) -> String {
+    s.replace('\'', r"'\''")
+        .replace('[', r"\[")
+        .replace(']', r"\]")
+        .replace(':', r"\:")
+}
+
+/// Quote a string for fish.
+fn fish_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\\', r"\\").replace('\'', r"\'"))
+}
+
+/// Describe the rule with the given identifier or error kind name.
+fn explain(code: &str) -> Result<String, UnknownRuleError> {
+    let rule = RuleId::find(code)?;
+    let explanation = rule.explanation();
+    let mut text = format!(
+        "{}: {}\n\n{}\n\nReported as: {}\n",
+        rule,
+        explanation.summary,
+        explanation.rationale,
+        rule.kind_names().join(", ")
+    );
+    for &(title, examples) in &[
+        ("Valid", explanation.valid),
+        ("Invalid", explanation.invalid),
+    ] {
+        text += &format!("\n{}:\n", title);
+        for example in examples {
+            for line in example.lines() {
+                text += &format!("    {}\n", line);
+            }
+        }
+    }
+    Ok(text)
+}
+
+/// Render the validation report as a JSON document.
+fn json_report(report: &ValidationReport, ast: Option<&str>) -> String {
+    format!("{{{}}}", json_report_fields(report, ast))
+}
+
+/// Render the validation report of a file as a JSON document containing its name.
+fn json_file_report(name: &str, report: &ValidationReport, ast: Option<&str>) -> String {
+    format!(
+        r#"{{"file": {}, {}}}"#,
+        json_string(name),
+        json_report_fields(report, ast)
+    )
+}
+
+/// Render the validation report of a commit as a JSON document containing its id.
+fn json_commit_report(id: &str, report: &ValidationReport) -> String {
+    format!(
+        r#"{{"commit": {}, {}}}"#,
+        json_string(id),
+        json_report_fields(report, None)
+    )
+}
+
+fn json_report_fields(report: &ValidationReport, ast: Option<&str>) -> String {
+    let mut fields = format!(r#""ok": {}"#, report.is_ok());
+    if !report.errors.is_empty() {
+        fields += &format!(r#", "errors": {}"#, json_errors(&report.errors));
+    }
+    if !report.warnings.is_empty() {
+        fields += &format!(r#", "warnings": {}"#, json_errors(&report.warnings));
+    }
+    if let Some(ast) = ast {
+        fields += &format!(r#", "ast": {}"#, ast);
+    }
+    fields
+}
+
+/// Render the parsed message in an indented format, for humans to check how it is understood.
+///
+/// Return `None` if the message cannot be parsed.
+fn text_ast(message: &str, options: &ValidationOptions) -> Option<String> {
+    let message = validate_commit::parse_commit_message_with(message, options).ok()?;
+    let header = &message.header;
+    let mut text = String::new();
+    if let Some(ticket) = header.ticket {
+        text += &format!("ticket: {}\n", ticket);
+    }
+    if let Some(emoji) = header.emoji {
+        text += &format!("emoji: {}\n", emoji);
+    }
+    text += &format!(
+        "type: {}\nscope: {}\nbreaking: {}\nsubject: {}\n",
+        header.commit_type,
+        header.scope.unwrap_or("(none)"),
+        message.is_breaking(),
+        header.subject
+    );
+    match message.body {
+        Some(body) => text += &format!("body:\n{}", indent(body)),
+        None => text += "body: (none)\n",
     }
+    if message.footers.is_empty() {
+        text += "footers: (none)\n";
+    } else {
+        text += "footers:\n";
+        for footer in &message.footers {
+            text += &indent(&footer.to_string());
+        }
+    }
+    Some(text)
+}
+
+/// Indent the lines of the text by 4 spaces, except the blank ones.
+fn indent(text: &str) -> String {
+    text.lines()
+        .map(|line| match line {
+            "" => "\n".to_owned(),
+            line => format!("    {}\n", line),
+        })
+        .collect()
+}
+
+/// Render the parsed message, with the byte ranges of the parts of its header in the header line.
+///
+/// The message is `null` if it cannot be parsed.
+fn json_ast(message: &str, options: &ValidationOptions) -> String {
+    let (message, spans) = match validate_commit::parse_commit_message_with_spans(message, options)
+    {
+        Ok(parsed) => parsed,
+        Err(_) => return "null".to_owned(),
+    };
+    let header = &message.header;
+    let json_span = |span: Option<&Range<usize>>| {
+        span.map_or("null".to_owned(), |span| {
+            format!(r#"{{"start": {}, "end": {}}}"#, span.start, span.end)
+        })
+    };
+    let footers: Vec<_> = message
+        .footers
+        .iter()
+        .map(|footer| {
+            format!(
+                r#"{{"token": {}, "value": {}}}"#,
+                json_string(footer.token),
+                json_string(footer.value)
+            )
+        })
+        .collect();
+    format!(
+        r#"{{"header": {{"type": {}, "scope": {}, "breaking": {}, "subject": {}, "ticket": {}, "emoji": {}, "spans": {{"type": {}, "scope": {}, "parentheses": {}, "breaking": {}, "subject": {}, "ticket": {}, "emoji": {}}}}}, "body": {}, "footers": [{}]}}"#,
+        json_string(header.commit_type.as_str()),
+        header.scope.map_or("null".to_owned(), json_string),
+        header.breaking,
+        json_string(header.subject),
+        header.ticket.map_or("null".to_owned(), json_string),
+        header.emoji.map_or("null".to_owned(), json_string),
+        json_span(Some(&spans.commit_type)),
+        json_span(spans.scope.as_ref()),
+        json_span(spans.parentheses.as_ref()),
+        json_span(spans.breaking.as_ref()),
+        json_span(Some(&spans.subject)),
+        json_span(spans.ticket.as_ref()),
+        json_span(spans.emoji.as_ref()),
+        message.body.map_or("null".to_owned(), json_string),
+        footers.join(", ")
+    )
+}
+
+fn json_errors(errors: &[FormatError]) -> String {
+    let errors: Vec<_> = errors
+        .iter()
+        .map(|error| {
+            format!(
+                r#"{{"kind": {}, "code": {}, "message": {}, "line": {}, "column": {}, "text": {}, "suggestion": {}}}"#,
+                json_string(error.kind().name()),
+                json_string(error.kind().code()),
+                json_string(&error.kind().to_string()),
+                json_option(error.line_number()),
+                json_option(error.column()),
+                error.line().map_or("null".to_owned(), json_string),
+                error.suggestion().map_or("null".to_owned(), json_string)
+            )
+        })
+        .collect();
+    format!("[{}]", errors.join(", "))
+}
+
+#[cfg(feature = "git")]
+/// Render the changelog as a JSON document, with the commits left out of it.
+fn json_changelog(groups: &CommitGroups<(&str, &CommitMsg)>, skipped: &[(String, &str)]) -> String {
+    let sections: Vec<_> = groups
+        .types
+        .iter()
+        .map(|(commit_type, commits)| {
+            format!(
+                r#"{{"type": {}, "title": {}, "commits": {}}}"#,
+                json_string(commit_type.as_str()),
+                json_string(section_title(commit_type)),
+                json_changelog_entries(commits)
+            )
+        })
+        .collect();
+    let skipped: Vec<_> = skipped
+        .iter()
+        .map(|(id, reason)| {
+            format!(
+                r#"{{"commit": {}, "reason": {}}}"#,
+                json_string(id),
+                json_string(reason)
+            )
+        })
+        .collect();
+    format!(
+        r#"{{"breaking": {}, "sections": [{}], "skipped": [{}]}}"#,
+        json_changelog_entries(&groups.breaking),
+        sections.join(", "),
+        skipped.join(", ")
+    )
+}
+
+#[cfg(feature = "git")]
+fn json_changelog_entries(commits: &[(&str, &CommitMsg)]) -> String {
+    let entries: Vec<_> = commits
+        .iter()
+        .map(|(id, msg)| {
+            format!(
+                r#"{{"commit": {}, "type": {}, "scope": {}, "subject": {}}}"#,
+                json_string(id),
+                json_string(msg.header.commit_type.as_str()),
+                msg.header.scope.map_or("null".to_owned(), json_string),
+                json_string(msg.header.subject)
+            )
+        })
+        .collect();
+    format!("[{}]", entries.join(", "))
+}
+
+#[cfg(feature = "git")]
+/// Render the statistics as a human readable table.
+fn stats_table(stats: &ValidationStats) -> String {
+    let mut text = format!(
+        "Commits  {}\nPassed   {} ({:.1}%)\nFailed   {} ({:.1}%)\n",
+        stats.total(),
+        stats.passed,
+        stats.pass_rate(),
+        stats.failed,
+        100.0 - stats.pass_rate()
+    );
+    let failures = stats.failures.iter().map(|(&kind, &count)| (kind, count));
+    text += &table_section("Failures by kind", failures.collect());
+    let types = stats.types.iter().map(|(t, &count)| (t.as_str(), count));
+    text += &table_section("Types of the passing commits", types.collect());
+    text
+}
+
+#[cfg(feature = "git")]
+fn table_section(title: &str, rows: Vec<(&str, usize)>) -> String {
+    if rows.is_empty() {
+        return String::new();
+    }
+    let width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    let mut text = format!("\n{}:\n", title);
+    for (name, count) in rows {
+        text += &format!("    {:<width$}  {}\n", name, count, width = width);
+    }
+    text
+}
+
+#[cfg(feature = "git")]
+/// Render the statistics as a JSON document.
+fn json_stats(stats: &ValidationStats) -> String {
+    let failures: Vec<_> = stats
+        .failures
+        .iter()
+        .map(|(kind, count)| format!("{}: {}", json_string(kind), count))
+        .collect();
+    let types: Vec<_> = stats
+        .types
+        .iter()
+        .map(|(t, count)| format!("{}: {}", json_string(t.as_str()), count))
+        .collect();
+    format!(
+        r#"{{"total": {}, "passed": {}, "failed": {}, "pass_rate": {:.1}, "failures": {{{}}}, "types": {{{}}}}}"#,
+        stats.total(),
+        stats.passed,
+        stats.failed,
+        stats.pass_rate(),
+        failures.join(", "),
+        types.join(", ")
+    )
+}
+
+fn json_option(value: Option<usize>) -> String {
+    value.map_or("null".to_owned(), |value| value.to_string())
+}
+
+/// Quote and escape a string for JSON.
+fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Render the validation errors and warnings as GitLab Code Quality issues, located in the
+/// message at the path, the errors being major issues and the warnings minor ones.
+///
+/// The fingerprint of an issue is a hash of its path, its rule, its message, its occurrence among
+/// the issues with the same rule and message, and the content of the commit message without its
+/// comments and with its blank space collapsed, so that it does not change between runs.
+fn gitlab_report(
+    path: &str,
+    content: &str,
+    comment_char: char,
+    report: &ValidationReport,
+) -> Vec<String> {
+    let content: Vec<_> = content
+        .lines()
+        .filter(|line| !line.starts_with(comment_char))
+        .flat_map(str::split_whitespace)
+        .collect();
+    let content = content.join(" ");
+    let errors = report.errors.iter().map(|e| (e, "major"));
+    let warnings = report.warnings.iter().map(|e| (e, "minor"));
+    let mut seen: Vec<(RuleId, String)> = Vec::new();
+    errors
+        .chain(warnings)
+        .map(|(error, severity)| {
+            let description = error.kind().to_string();
+            let key = (error.rule(), description.clone());
+            let occurrence = seen.iter().filter(|&seen| *seen == key).count();
+            seen.push(key);
+            let fingerprint = fnv1a_hash(
+                [
+                    path,
+                    error.rule().as_str(),
+                    &description,
+                    &occurrence.to_string(),
+                    &content,
+                ]
+                .join("\0")
+                .as_bytes(),
+            );
+            format!(
+                r#"{{"description": {}, "check_name": {}, "fingerprint": "{:016x}", "severity": "{}", "location": {{"path": {}, "lines": {{"begin": {}}}}}}}"#,
+                json_string(&description),
+                json_string(error.rule().as_str()),
+                fingerprint,
+                severity,
+                json_string(path),
+                error.line_number().unwrap_or(1)
+            )
+        })
+        .collect()
+}
+
+/// Render the validation errors and warnings as SARIF results, located in the message at the
+/// URI.
+fn sarif_report(uri: &str, report: &ValidationReport) -> Vec<String> {
+    let errors = report.errors.iter().map(|e| (e, Severity::Error));
+    let warnings = report.warnings.iter().map(|e| (e, Severity::Warning));
+    errors
+        .chain(warnings)
+        .map(|(error, severity)| {
+            let rule = error.rule();
+            let mut location = format!(
+                r#"{{"artifactLocation": {{"uri": {}}}"#,
+                json_string(&uri_escape(uri))
+            );
+            if let Some(line) = error.line_number() {
+                location += &format!(r#", "region": {{"startLine": {}"#, line);
+                if let Some(column) = error.column() {
+                    location += &format!(r#", "startColumn": {}"#, column);
+                }
+                location += "}";
+            }
+            location += "}";
+            format!(
+                r#"{{"ruleId": {}, "ruleIndex": {}, "level": "{}", "message": {{"text": {}}}, "locations": [{{"physicalLocation": {}}}]}}"#,
+                json_string(rule.as_str()),
+                sarif_rules().position(|r| r == rule).unwrap(),
+                sarif_level(severity),
+                json_string(&error.kind().to_string()),
+                location
+            )
+        })
+        .collect()
+}
+
+/// Rules described in the SARIF logs, the custom rules of the plugins and validators sharing the
+/// last descriptor
+fn sarif_rules() -> impl Iterator<Item = RuleId> {
+    RuleId::ALL.iter().chain(&[RuleId::Custom]).cloned()
+}
+
+/// Render the SARIF 2.1.0 log of the results, with a single run describing every rule.
+fn sarif_log(results: &[String]) -> String {
+    let rules: Vec<_> = sarif_rules()
+        .map(|rule| {
+            let explanation = rule.explanation();
+            format!(
+                r#"{{"id": {}, "shortDescription": {{"text": {}}}, "fullDescription": {{"text": {}}}, "defaultConfiguration": {{"level": "{}"}}}}"#,
+                json_string(rule.as_str()),
+                json_string(explanation.summary),
+                json_string(explanation.rationale),
+                sarif_level(rule.default_severity())
+            )
+        })
+        .collect();
+    format!(
+        r#"{{"$schema": "https://json.schemastore.org/sarif-2.1.0.json", "version": "2.1.0", "runs": [{{"tool": {{"driver": {{"name": "validate-commit", "version": "{}", "rules": [{}]}}}}, "results": [{}]}}]}}"#,
+        env!("CARGO_PKG_VERSION"),
+        rules.join(", "),
+        results.join(", ")
+    )
+}
+
+fn sarif_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+    }
+}
+
+/// Percent-encode the characters of the path which cannot appear in a URI reference.
+fn uri_escape(path: &str) -> String {
+    let mut escaped = String::with_capacity(path.len());
+    for &byte in path.as_bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                escaped.push(byte as char)
+            }
+            byte => escaped.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    escaped
+}
+
+/// Hash the bytes with the 64-bit FNV-1a function, which unlike the hasher of the standard
+/// library is stable across releases.
+fn fnv1a_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Render the validation errors and warnings as GitHub Actions workflow commands, one per line.
+///
+/// The file is omitted when the message is not read from a commit file.
+fn github_report(file_path: Option<&str>, report: &ValidationReport) -> String {
+    let errors = report.errors.iter().map(|e| ("error", e));
+    let warnings = report.warnings.iter().map(|e| ("warning", e));
+    errors
+        .chain(warnings)
+        .map(|(command, error)| {
+            let mut properties = Vec::new();
+            if let Some(file_path) = file_path {
+                properties.push(format!("file={}", github_escape_property(file_path)));
+                if let (Some(line), Some(column)) = (error.line_number(), error.column()) {
+                    properties.push(format!("line={},col={}", line, column));
+                }
+            }
+            let separator = if properties.is_empty() { "" } else { " " };
+            format!(
+                "::{}{}{}::{}\n",
+                command,
+                separator,
+                properties.join(","),
+                github_escape_data(&error.kind().message(language()))
+            )
+        })
+        .collect()
+}
+
+/// Render the validation errors as TeamCity build problems and the warnings as warning messages,
+/// one service message per line.
+///
+/// The identity of a build problem hashes its location and message, so that TeamCity recognizes
+/// it in the next builds.
+fn teamcity_report(name: &str, report: &ValidationReport) -> String {
+    let errors = report.errors.iter().map(|e| (e, Severity::Error));
+    let warnings = report.warnings.iter().map(|e| (e, Severity::Warning));
+    errors
+        .chain(warnings)
+        .map(|(error, severity)| {
+            let mut location = name.to_owned();
+            if let Some(line) = error.line_number() {
+                location += &format!(":{}", line);
+                if let Some(column) = error.column() {
+                    location += &format!(":{}", column);
+                }
+            }
+            let description = |message| format!("{}: {} [{}]", location, message, error.rule());
+            // The identity does not depend on the language of the description
+            let identity = fnv1a_hash(description(error.kind().to_string()).as_bytes());
+            let description = description(error.kind().message(language()));
+            match severity {
+                Severity::Error => format!(
+                    "##teamcity[buildProblem description='{}' identity='{}-{:016x}']\n",
+                    teamcity_escape(&description),
+                    error.rule(),
+                    identity
+                ),
+                Severity::Warning => format!(
+                    "##teamcity[message text='{}' status='WARNING']\n",
+                    teamcity_escape(&description)
+                ),
+            }
+        })
+        .collect()
+}
+
+/// Escape a value of a TeamCity service message.
+fn teamcity_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '|' => escaped.push_str("||"),
+            '\'' => escaped.push_str("|'"),
+            '\n' => escaped.push_str("|n"),
+            '\r' => escaped.push_str("|r"),
+            '[' => escaped.push_str("|["),
+            ']' => escaped.push_str("|]"),
+            '\u{85}' => escaped.push_str("|x"),
+            '\u{2028}' => escaped.push_str("|l"),
+            '\u{2029}' => escaped.push_str("|p"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Render the `<file>` element of the Checkstyle report of a message, named after its file or
+/// commit.
+fn checkstyle_file(name: &str, report: &ValidationReport) -> String {
+    let errors = report.errors.iter().map(|e| ("error", e));
+    let warnings = report.warnings.iter().map(|e| ("warning", e));
+    let mut element = format!("  <file name=\"{}\">\n", xml_escape(name));
+    for (severity, error) in errors.chain(warnings) {
+        let mut attributes = String::new();
+        if let Some(line) = error.line_number() {
+            attributes += &format!(" line=\"{}\"", line);
+        }
+        if let Some(column) = error.column() {
+            attributes += &format!(" column=\"{}\"", column);
+        }
+        element += &format!(
+            "    <error{} severity=\"{}\" message=\"{}\" source=\"validate-commit.{}\"/>\n",
+            attributes,
+            severity,
+            xml_escape(&error.kind().message(language())),
+            error.rule().as_str()
+        );
+    }
+    element + "  </file>\n"
+}
+
+/// Render the Checkstyle report from its `<file>` elements.
+fn checkstyle_report(files: &[String]) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<checkstyle version=\"4.3\">\n{}</checkstyle>\n",
+        files.concat()
+    )
+}
+
+#[cfg(feature = "git")]
+/// Render the JUnit report of a test suite with a test case per commit, from the short ids and
+/// headers of the commits and the reports of their messages, `None` for the skipped ones.
+///
+/// The errors make the test case fail, and the warnings are written to its standard output.
+fn junit_report(suite: &str, commits: &[(String, String, Option<ValidationReport>)]) -> String {
+    let reports = commits.iter().map(|(_, _, report)| report);
+    let failures = reports
+        .clone()
+        .filter(|report| report.as_ref().is_some_and(|r| !r.errors.is_empty()))
+        .count();
+    let skipped = reports.filter(|report| report.is_none()).count();
+    let mut document = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites>\n  <testsuite name=\"{}\" \
+         tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"{}\">\n",
+        xml_escape(suite),
+        commits.len(),
+        failures,
+        skipped
+    );
+    for (id, header, report) in commits {
+        document += &format!(
+            "    <testcase name=\"{} {}\" classname=\"validate-commit\"",
+            xml_escape(id),
+            xml_escape(header)
+        );
+        let report = match report {
+            None => {
+                document += ">\n      <skipped/>\n    </testcase>\n";
+                continue;
+            }
+            Some(report) if report.errors.is_empty() && report.warnings.is_empty() => {
+                document += "/>\n";
+                continue;
+            }
+            Some(report) => report,
+        };
+        document += ">\n";
+        if let Some(error) = report.errors.first() {
+            document += &format!(
+                "      <failure message=\"{}\" type=\"{}\">{}</failure>\n",
+                xml_escape(&error.kind().message(language())),
+                error.rule().as_str(),
+                xml_escape(&plain_diagnostics(id, &report.errors, Severity::Error))
+            );
+        }
+        if !report.warnings.is_empty() {
+            document += &format!(
+                "      <system-out>{}</system-out>\n",
+                xml_escape(&plain_diagnostics(id, &report.warnings, Severity::Warning))
+            );
+        }
+        document += "    </testcase>\n";
+    }
+    document + "  </testsuite>\n</testsuites>\n"
+}
+
+#[cfg(feature = "git")]
+/// Render the errors of the named message like [`write_format_error_to`], without colors and
+/// separated by blank lines.
+fn plain_diagnostics(name: &str, errors: &[FormatError], severity: Severity) -> String {
+    let diagnostics: Vec<_> = errors
+        .iter()
+        .map(|error| {
+            let mut out = termcolor::NoColor::new(Vec::new());
+            write_format_error_to(&mut out, severity, name, error)
+                .expect("Cannot fail to write to a vector");
+            String::from_utf8_lossy(&out.into_inner()).into_owned()
+        })
+        .collect();
+    diagnostics.join("\n")
+}
 
-    let file_path = std::env::args().nth(1).unwrap();
-    if let Err(e) = validate_commit::validate_commit_file(&file_path) {
-        write_error(&e);
-        exit(1);
+/// Escape a string for an XML attribute value or text, replacing the control characters which
+/// XML 1.0 forbids.
+fn xml_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push_str(&format!("&#{};", c as u32)),
+            c if (c as u32) < 0x20 => escaped.push(char::REPLACEMENT_CHARACTER),
+            c => escaped.push(c),
+        }
     }
+    escaped
+}
+
+/// Escape the message of a workflow command.
+fn github_escape_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a property value of a workflow command.
+fn github_escape_property(s: &str) -> String {
+    github_escape_data(s)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+/// Print the error and the usage, and exit with the status of the usage errors.
+fn usage_error<E: Display>(error: &E) -> ! {
+    eprintln!("{}\n{}", error, USAGE);
+    exit(EXIT_ERROR);
+}
+
+/// Print the error preventing the validation, and exit.
+fn fail<E: Display>(error: &E) -> ! {
+    write_error(error);
+    exit(EXIT_ERROR);
+}
+
+fn write_error<E: Display>(error: &E) {
+    write_diagnostic(Severity::Error, error);
 }
 
-fn write_error(error: &validate_commit::CommitValidationError) {
+/// Error displayed with its sources, like `Error while opening commit file 'x': Not found`
+struct WithSources<'a>(&'a dyn Error);
+
+impl Display for WithSources<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)?;
+        let mut source = self.0.source();
+        while let Some(error) = source {
+            write!(f, ": {}", error)?;
+            source = error.source();
+        }
+        Ok(())
+    }
+}
+
+/// Write the diagnostic on the standard error, colored according to `--color`.
+fn write_diagnostic<E: Display>(severity: Severity, error: &E) {
     let formatted_error = format!("{}", error);
-    let mut stdout = StandardStream::stdout(ColorChoice::Auto);
-    stdout
-        .set_color(ColorSpec::new().set_bold(true).set_fg(Some(Color::Red)))
-        .and_then(|()| stdout.write_all(b"error: "))
-        .and_then(|()| stdout.reset())
-        .and_then(|()| stdout.write_fmt(format_args!("{}\n", formatted_error)))
+    write_diagnostic_to(&mut diagnostic_stream(), severity, &formatted_error)
         .expect(&formatted_error);
 }
+
+/// Write the validation error of the named message on the standard error.
+fn write_format_error(severity: Severity, name: &str, error: &FormatError) {
+    write_format_error_to(&mut diagnostic_stream(), severity, name, error)
+        .unwrap_or_else(|e| panic!("{}: {}", error, e));
+}
+
+fn diagnostic_stream() -> StandardStream {
+    let choice = *COLOR_CHOICE.get_or_init(|| {
+        color_choice(
+            ColorMode::Auto,
+            env_flag("NO_COLOR"),
+            env_flag("CLICOLOR_FORCE"),
+            io::stderr().is_terminal(),
+        )
+    });
+    StandardStream::stderr(choice)
+}
+
+fn write_diagnostic_to<W: WriteColor>(
+    out: &mut W,
+    severity: Severity,
+    error: &str,
+) -> io::Result<()> {
+    write_severity(out, severity)?;
+    writeln!(out, "{}", error)
+}
+
+/// Write the validation error like rustc: its message, its location in the named message, and
+/// the offending line with the offending text underlined.
+///
+/// ```text
+/// error: Invalid commit type 'feet', did you mean 'feat'? [VC004 type-valid]
+///  --> .git/COMMIT_EDITMSG:1:1
+///   |
+/// 1 | feet: add colors
+///   | ^^^^
+/// ```
+fn write_format_error_to<W: WriteColor>(
+    out: &mut W,
+    severity: Severity,
+    name: &str,
+    error: &FormatError,
+) -> io::Result<()> {
+    let mut gutter = ColorSpec::new();
+    gutter.set_bold(true).set_fg(Some(Color::Blue));
+
+    write_severity(out, severity)?;
+    out.set_color(ColorSpec::new().set_bold(true))?;
+    write!(
+        out,
+        "{} [{} {}]",
+        error.kind().message(language()),
+        error.kind().code(),
+        error.rule()
+    )?;
+    out.reset()?;
+    writeln!(out)?;
+
+    let span = match error.span() {
+        Some(span) => span,
+        None => {
+            out.set_color(&gutter)?;
+            write!(out, " --> ")?;
+            out.reset()?;
+            writeln!(out, "{}", name)?;
+            return write_help(out, 0, error);
+        }
+    };
+    let line_number = span.line_number().to_string();
+    let width = line_number.len();
+    out.set_color(&gutter)?;
+    write!(out, "{:width$}--> ", "", width = width)?;
+    out.reset()?;
+    writeln!(out, "{}:{}:{}", name, span.line_number(), span.column())?;
+
+    out.set_color(&gutter)?;
+    writeln!(out, "{:width$} |", "", width = width)?;
+    write!(out, "{} | ", line_number)?;
+    out.reset()?;
+    writeln!(out, "{}", span.display_line())?;
+
+    let (offset, underline) = span.display_range();
+    out.set_color(&gutter)?;
+    write!(
+        out,
+        "{:width$} | {:offset$}",
+        "",
+        "",
+        width = width,
+        offset = offset
+    )?;
+    out.set_color(
+        ColorSpec::new()
+            .set_bold(true)
+            .set_fg(Some(severity_color(severity))),
+    )?;
+    write!(out, "{}", "^".repeat(underline))?;
+    out.reset()?;
+    writeln!(out)?;
+    write_help(out, width, error)
+}
+
+/// Write the suggestion of the error, if any, on a dimmed `help: ` line.
+fn write_help<W: WriteColor>(out: &mut W, width: usize, error: &FormatError) -> io::Result<()> {
+    let suggestion = match error.suggestion_message(language()) {
+        Some(suggestion) => suggestion,
+        None => return Ok(()),
+    };
+    out.set_color(ColorSpec::new().set_bold(true).set_fg(Some(Color::Blue)))?;
+    write!(out, "{:width$} = ", "", width = width)?;
+    out.set_color(
+        ColorSpec::new()
+            .set_fg(Some(Color::Black))
+            .set_intense(true),
+    )?;
+    write!(out, "help: {}", suggestion)?;
+    out.reset()?;
+    writeln!(out)
+}
+
+/// Language of the diagnostics, English until the arguments are parsed.
+fn language() -> Language {
+    LANGUAGE.get().cloned().unwrap_or_default()
+}
+
+/// Write the `error: ` or `warning: ` prefix of a diagnostic.
+fn write_severity<W: WriteColor>(out: &mut W, severity: Severity) -> io::Result<()> {
+    let prefix = match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+    };
+    out.set_color(
+        ColorSpec::new()
+            .set_bold(true)
+            .set_fg(Some(severity_color(severity))),
+    )?;
+    write!(out, "{}", prefix)?;
+    out.reset()?;
+    write!(out, ": ")
+}
+
+fn severity_color(severity: Severity) -> Color {
+    match severity {
+        Severity::Error => Color::Red,
+        Severity::Warning => Color::Yellow,
+    }
+}
+
+/// Choose whether to color the diagnostics.
+///
+/// With `auto`, the `NO_COLOR` and `CLICOLOR_FORCE` conventions are honored, in this order, and
+/// the diagnostics are colored only on a terminal otherwise.
+fn color_choice(
+    mode: ColorMode,
+    no_color: bool,
+    force_color: bool,
+    is_terminal: bool,
+) -> ColorChoice {
+    match mode {
+        ColorMode::Always => ColorChoice::Always,
+        ColorMode::Never => ColorChoice::Never,
+        ColorMode::Auto if no_color => ColorChoice::Never,
+        ColorMode::Auto if force_color => ColorChoice::Always,
+        ColorMode::Auto if is_terminal => ColorChoice::Auto,
+        ColorMode::Auto => ColorChoice::Never,
+    }
+}
+
+/// The environment variables with a valid unicode name and value.
+fn env_vars() -> impl Iterator<Item = (String, String)> {
+    std::env::vars_os()
+        .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+}
+
+/// Color choice of the `VALIDATE_COMMIT_COLOR` variable, if it is set.
+fn env_color() -> Result<Option<ColorMode>, String> {
+    let name = format!("{}COLOR", ENV_PREFIX);
+    match std::env::var(&name) {
+        Ok(value) => value.parse().map(Some).map_err(|_| {
+            format!(
+                "Invalid environment variable {}: '{}', use always, auto or never",
+                name, value
+            )
+        }),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Whether the environment variable is set to a value other than empty or `0`.
+fn env_flag(name: &str) -> bool {
+    std::env::var_os(name).is_some_and(|value| !value.is_empty() && value != "0")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        build_options, checkstyle_file, checkstyle_report, color_choice, completions, explain,
+        fnv1a_hash, github_report, gitlab_report, help, json_ast, json_file_report, json_report,
+        json_string, option_values, parse_args, parse_hook_args, sarif_log, sarif_report,
+        select_commits, teamcity_escape, teamcity_report, write_diagnostic_to,
+        write_format_error_to, xml_escape, ColorMode, Commits, ConfigFormat, Format, OPTIONS,
+    };
+    use serde_json::Value;
+    use std::path::PathBuf;
+    use termcolor::{Ansi, ColorChoice, NoColor};
+    use validate_commit::{
+        FormatErrorKind, Pattern, RuleId, Severity, ValidationOptions, ValidationReport,
+    };
+
+    fn args(args: &[&str]) -> impl Iterator<Item = String> {
+        args.iter()
+            .map(|arg| arg.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn parse_format() {
+        let parsed = parse_args(args(&["COMMIT_EDITMSG"])).unwrap();
+        assert_eq!(parsed.format, None);
+        assert_eq!(parsed.file_paths, vec!["COMMIT_EDITMSG"]);
+        let parsed = parse_args(args(&["--format", "json", "COMMIT_EDITMSG"])).unwrap();
+        assert_eq!(parsed.format, Some(Format::Json));
+        let parsed = parse_args(args(&["--format", "github", "COMMIT_EDITMSG"])).unwrap();
+        assert_eq!(parsed.format, Some(Format::Github));
+        assert!(parse_args(args(&["--format", "xml", "COMMIT_EDITMSG"])).is_err());
+    }
+
+    #[test]
+    fn parse_color() {
+        let parsed = parse_args(args(&["COMMIT_EDITMSG"])).unwrap();
+        assert_eq!(parsed.color, None);
+        let parsed = parse_args(args(&["--color", "never", "COMMIT_EDITMSG"])).unwrap();
+        assert_eq!(parsed.color, Some(ColorMode::Never));
+        let parsed = parse_args(args(&["--color", "always", "COMMIT_EDITMSG"])).unwrap();
+        assert_eq!(parsed.color, Some(ColorMode::Always));
+        assert!(parse_args(args(&["--color", "sometimes", "COMMIT_EDITMSG"])).is_err());
+    }
+
+    #[test]
+    fn choose_color() {
+        assert_eq!(
+            color_choice(ColorMode::Auto, false, false, true),
+            ColorChoice::Auto
+        );
+        assert_eq!(
+            color_choice(ColorMode::Auto, false, false, false),
+            ColorChoice::Never
+        );
+        assert_eq!(
+            color_choice(ColorMode::Auto, true, true, true),
+            ColorChoice::Never
+        );
+        assert_eq!(
+            color_choice(ColorMode::Auto, false, true, false),
+            ColorChoice::Always
+        );
+        assert_eq!(
+            color_choice(ColorMode::Always, true, false, false),
+            ColorChoice::Always
+        );
+        assert_eq!(
+            color_choice(ColorMode::Never, false, true, true),
+            ColorChoice::Never
+        );
+    }
+
+    #[test]
+    fn write_diagnostics() {
+        let mut out = NoColor::new(Vec::new());
+        write_diagnostic_to(&mut out, Severity::Error, "Empty commit message").unwrap();
+        assert_eq!(out.into_inner(), b"error: Empty commit message\n");
+
+        let mut out = Ansi::new(Vec::new());
+        write_diagnostic_to(&mut out, Severity::Warning, "Consecutive blank lines").unwrap();
+        let out = String::from_utf8(out.into_inner()).unwrap();
+        assert!(out.contains('\x1b'));
+        assert!(out.ends_with("warning\x1b[0m: Consecutive blank lines\n"));
+    }
+
+    /// Render the errors and warnings of the message without colors.
+    fn render(name: &str, message: &str, options: &ValidationOptions) -> String {
+        let report = validate_commit::validate_commit_message_report_with(message, options);
+        let errors = report.errors.iter().map(|e| (e, Severity::Error));
+        let warnings = report.warnings.iter().map(|e| (e, Severity::Warning));
+        let mut out = NoColor::new(Vec::new());
+        for (error, severity) in errors.chain(warnings) {
+            write_format_error_to(&mut out, severity, name, error).unwrap();
+        }
+        String::from_utf8(out.into_inner()).unwrap()
+    }
+
+    #[test]
+    fn render_rustc_style_diagnostics() {
+        let options = ValidationOptions::default();
+        assert_eq!(
+            render("COMMIT_EDITMSG", "feet: add colors", &options),
+            "error: Invalid commit type 'feet', did you mean 'feat'? [VC004 type-valid]
+ --> COMMIT_EDITMSG:1:1
+  |
+1 | feet: add colors
+  | ^^^^
+  = help: write 'feat' instead of 'feet'
+"
+        );
+        assert_eq!(
+            render("<stdin>", "fix(\tcli): handle tabs", &options),
+            "error: Misplaced whitespace [VC008 header-whitespace]
+ --> <stdin>:1:5
+  |
+1 | fix(    cli): handle tabs
+  |     ^^^^
+  = help: remove the whitespace: 'fix(cli): handle tabs'
+"
+        );
+        assert_eq!(
+            render("<message 1>", "", &options),
+            "error: Empty commit message [VC013 message-not-empty]
+ --> <message 1>
+ = help: write a header like 'feat: add a feature'
+"
+        );
+
+        let message = format!(
+            "feat: add colors\n\nBody\n\n\n{}{}\n",
+            "Wrap\n".repeat(4),
+            "a".repeat(104)
+        );
+        assert_eq!(
+            render("COMMIT_EDITMSG", &message, &options),
+            "error: Line must not be longer than 100 characters [VC005 line-max-length]
+  --> COMMIT_EDITMSG:10:101
+   |
+10 | aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa
+   |                                                                                                     ^^^^
+   = help: wrap the line at 100 characters
+warning: Consecutive blank lines [VC031 no-consecutive-blank-lines]
+ --> COMMIT_EDITMSG:5:1
+  |
+5 | 
+  | ^
+  = help: remove the extra blank line
+"
+        );
+    }
+
+    #[test]
+    fn render_colored_diagnostics() {
+        let report = validate_commit::validate_commit_message_report("feet: add colors");
+        let mut out = Ansi::new(Vec::new());
+        write_format_error_to(
+            &mut out,
+            Severity::Error,
+            "COMMIT_EDITMSG",
+            &report.errors[0],
+        )
+        .unwrap();
+        let out = String::from_utf8(out.into_inner()).unwrap();
+        assert!(out.starts_with("\x1b[0m\x1b[1m\x1b[31merror\x1b[0m: "));
+        assert!(out.contains("\x1b[0m\x1b[1m\x1b[31m^^^^\x1b[0m\n"));
+        assert!(out.ends_with("\x1b[0m\x1b[38;5;8mhelp: write 'feat' instead of 'feet'\x1b[0m\n"));
+    }
+
+    #[test]
+    fn parse_stdin_path() {
+        let parsed = parse_args(args(&["-"])).unwrap();
+        assert_eq!(parsed.file_paths, vec!["-"]);
+        let parsed = parse_args(args(&["--format", "json"])).unwrap();
+        assert!(parsed.file_paths.is_empty());
+    }
+
+    #[test]
+    fn parse_comment_char() {
+        let parsed = parse_args(args(&["--comment-char", ";", "COMMIT_EDITMSG"])).unwrap();
+        assert_eq!(parsed.comment_char, Some(';'));
+        assert!(parse_args(args(&["--comment-char", ";;", "COMMIT_EDITMSG"])).is_err());
+    }
+
+    #[test]
+    fn parse_messages() {
+        let parsed = parse_args(args(&[
+            "--message",
+            "feat: add thing",
+            "--message",
+            "fix: a",
+        ]))
+        .unwrap();
+        assert_eq!(parsed.messages, vec!["feat: add thing", "fix: a"]);
+        assert!(parsed.file_paths.is_empty());
+        assert!(parse_args(args(&["--message"])).is_err());
+        assert!(parse_args(args(&["--message", "feat: add thing", "COMMIT_EDITMSG"])).is_err());
+        assert!(parse_args(args(&["-", "--message", "feat: add thing"])).is_err());
+        assert!(parse_args(args(&["--message", "feat: add thing", "head"])).is_err());
+    }
+
+    #[test]
+    fn parse_several_paths() {
+        let parsed = parse_args(args(&["a.txt", "--format", "json", "b.txt", "-"])).unwrap();
+        assert_eq!(parsed.file_paths, vec!["a.txt", "b.txt", "-"]);
+    }
+
+    #[test]
+    fn render_json_report() {
+        assert_eq!(
+            json_report(&ValidationReport::default(), None),
+            r#"{"ok": true}"#
+        );
+
+        let result = validate_commit::validate_commit_message_report("feat: Add \"JSON\"\nBody");
+        assert_eq!(
+            json_report(&result, None),
+            r#"{"ok": false, "errors": [{"kind": "CapitalizedFirstLetter", "code": "VC001", "message": "First letter must not be capitalized", "line": 1, "column": 7, "text": "feat: Add \"JSON\"", "suggestion": "write 'add' instead of 'Add'"}, {"kind": "NonEmptySecondLine", "code": "VC010", "message": "Second line must be empty", "line": 2, "column": 1, "text": "Body", "suggestion": "add a blank line between the header and the body"}]}"#
+        );
+
+        let result = validate_commit::validate_commit_message_report("");
+        assert_eq!(
+            json_report(&result, None),
+            r#"{"ok": false, "errors": [{"kind": "EmptyCommitMessage", "code": "VC013", "message": "Empty commit message", "line": null, "column": null, "text": null, "suggestion": "write a header like 'feat: add a feature'"}]}"#
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "git")]
+    fn render_stats() {
+        use super::{json_stats, stats_table};
+        use validate_commit::ValidationStats;
+
+        let options = ValidationOptions::default();
+        let mut stats = ValidationStats::new();
+        for message in &[
+            "feat: add colors",
+            "feat: Add scopes",
+            "Fix colors",
+            "fix: handle input",
+        ] {
+            stats.add(message, &options);
+        }
+        assert_eq!(
+            stats_table(&stats),
+            "Commits  4\n\
+             Passed   2 (50.0%)\n\
+             Failed   2 (50.0%)\n\
+             \n\
+             Failures by kind:\n    \
+                 CapitalizedFirstLetter  1\n    \
+                 NoColumn                1\n\
+             \n\
+             Types of the passing commits:\n    \
+                 feat  1\n    \
+                 fix   1\n"
+        );
+        assert_eq!(
+            json_stats(&stats),
+            r#"{"total": 4, "passed": 2, "failed": 2, "pass_rate": 50.0, "failures": {"CapitalizedFirstLetter": 1, "NoColumn": 1}, "types": {"feat": 1, "fix": 1}}"#
+        );
+        assert_eq!(
+            stats_table(&ValidationStats::new()),
+            "Commits  0\nPassed   0 (100.0%)\nFailed   0 (0.0%)\n"
+        );
+    }
+
+    #[test]
+    fn parse_fail_under() {
+        let parsed = parse_args(args(&["stats", "v1.0..", "--fail-under", "92.5"])).unwrap();
+        assert_eq!(parsed.fail_under, Some(92.5));
+        assert!(parse_args(args(&["stats", "v1.0..", "--fail-under", "120"])).is_err());
+        assert!(parse_args(args(&["stats", "v1.0..", "--fail-under", "most"])).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "git")]
+    fn render_json_changelog() {
+        use super::json_changelog;
+        use validate_commit::{parse_commit_message, CommitGroups};
+
+        let feat = parse_commit_message("feat(cli)!: add \"colors\"").unwrap();
+        let docs = parse_commit_message("docs: describe colors").unwrap();
+        let groups =
+            CommitGroups::new(vec![("aaaaaaa", &feat), ("bbbbbbb", &docs)], |&(_, msg)| {
+                msg
+            });
+        let skipped = [("ccccccc".to_owned(), "merge")];
+        assert_eq!(
+            json_changelog(&groups, &skipped),
+            r#"{"breaking": [{"commit": "aaaaaaa", "type": "feat", "scope": "cli", "subject": "add \"colors\""}], "sections": [{"type": "feat", "title": "Features", "commits": [{"commit": "aaaaaaa", "type": "feat", "scope": "cli", "subject": "add \"colors\""}]}, {"type": "docs", "title": "Documentation", "commits": [{"commit": "bbbbbbb", "type": "docs", "scope": null, "subject": "describe colors"}]}], "skipped": [{"commit": "ccccccc", "reason": "merge"}]}"#
+        );
+    }
+
+    #[test]
+    fn render_github_report() {
+        assert_eq!(
+            github_report(Some(".git/COMMIT_EDITMSG"), &ValidationReport::default()),
+            ""
+        );
+
+        let result = validate_commit::validate_commit_message_report("feet: add annotations\nBody");
+        assert_eq!(
+            github_report(Some(".git/COMMIT_EDITMSG"), &result),
+            "::error file=.git/COMMIT_EDITMSG,line=1,col=1::Invalid commit type 'feet', did you mean 'feat'?
+::error file=.git/COMMIT_EDITMSG,line=2,col=1::Second line must be empty
+"
+        );
+
+        let result = validate_commit::validate_commit_message_report("");
+        assert_eq!(
+            github_report(Some("C:\\msg,1"), &result),
+            "::error file=C%3A\\msg%2C1::Empty commit message\n"
+        );
+        assert_eq!(
+            github_report(None, &result),
+            "::error::Empty commit message\n"
+        );
+    }
+
+    #[test]
+    fn render_warnings() {
+        let mut options = ValidationOptions::default();
+        options
+            .severities
+            .insert(RuleId::SubjectNotCapitalized, Severity::Warning);
+        let report =
+            validate_commit::validate_commit_message_report_with("feat: Add warnings", &options);
+        assert_eq!(
+            json_report(&report, None),
+            r#"{"ok": true, "warnings": [{"kind": "CapitalizedFirstLetter", "code": "VC001", "message": "First letter must not be capitalized", "line": 1, "column": 7, "text": "feat: Add warnings", "suggestion": "write 'add' instead of 'Add'"}]}"#
+        );
+        assert_eq!(
+            github_report(Some("msg"), &report),
+            "::warning file=msg,line=1,col=7::First letter must not be capitalized\n"
+        );
+
+        let report = report.into_strict();
+        assert_eq!(
+            json_report(&report, None),
+            r#"{"ok": false, "errors": [{"kind": "CapitalizedFirstLetter", "code": "VC001", "message": "First letter must not be capitalized", "line": 1, "column": 7, "text": "feat: Add warnings", "suggestion": "write 'add' instead of 'Add'"}]}"#
+        );
+    }
+
+    #[test]
+    fn parse_rule_toggles() {
+        let parsed = parse_args(args(&[
+            "--disable",
+            "subject-not-capitalized",
+            "--enable",
+            "type-valid",
+            "COMMIT_EDITMSG",
+        ]))
+        .unwrap();
+        assert_eq!(
+            parsed.rule_toggles,
+            vec![
+                (RuleId::SubjectNotCapitalized, false),
+                (RuleId::TypeValid, true)
+            ]
+        );
+
+        let error = parse_args(args(&["--disable", "capitals", "COMMIT_EDITMSG"]))
+            .err()
+            .unwrap();
+        assert!(error.starts_with("--disable: Unknown rule 'capitals', expected one of: "));
+        assert!(parse_args(args(&["--enable"])).is_err());
+    }
+
+    #[test]
+    fn explain_rules() {
+        let expected = "subject-not-capitalized: The subject must not start with a capital letter.
+
+The subject follows the type like the rest of a sentence, and the Angular convention writes it in \
+lowercase. Acronyms like `JSON` are accepted, as are the words listed in \
+`allowed_capitalized_words`.
+
+Reported as: CapitalizedFirstLetter
+
+Valid:
+    feat: add scopes
+
+Invalid:
+    feat: Add scopes
+";
+        assert_eq!(explain("subject-not-capitalized").unwrap(), expected);
+        assert_eq!(explain("CapitalizedFirstLetter").unwrap(), expected);
+        assert!(explain("second-line-empty")
+            .unwrap()
+            .ends_with("Invalid:\n    fix: handle empty input\n    The parser used to panic.\n"));
+
+        let error = explain("capitals").unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("Unknown rule 'capitals', expected one of: message-not-empty, "));
+    }
+
+    #[test]
+    fn parse_ignore_prefixes() {
+        let parsed = parse_args(args(&[
+            "--ignore-prefix",
+            "Release:",
+            "--no-default-ignores",
+            "--ignore-prefix",
+            "[bot]",
+            "COMMIT_EDITMSG",
+        ]))
+        .unwrap();
+        assert_eq!(parsed.ignore_prefixes, vec!["Release:", "[bot]"]);
+        assert!(parsed.no_default_ignores);
+        assert!(!parsed.validate_merges);
+        let parsed = parse_args(args(&["--validate-merges", "COMMIT_EDITMSG"])).unwrap();
+        assert!(parsed.validate_merges);
+        assert_eq!(parsed.file_paths, vec!["COMMIT_EDITMSG"]);
+        assert!(parse_args(args(&["--ignore-prefix"])).is_err());
+    }
+
+    #[test]
+    fn parse_allow_patterns() {
+        let parsed = parse_args(args(&[
+            "--allow-pattern",
+            r"^Release \d+",
+            "--allow-pattern",
+            "^v1",
+            "COMMIT_EDITMSG",
+        ]))
+        .unwrap();
+        assert_eq!(
+            parsed.allow_patterns,
+            vec![
+                Pattern::new(r"^Release \d+").unwrap(),
+                Pattern::new("^v1").unwrap()
+            ]
+        );
+        assert_eq!(
+            parse_args(args(&["--allow-pattern", "(", "COMMIT_EDITMSG"]))
+                .err()
+                .unwrap(),
+            "Invalid value for --allow-pattern: '('"
+        );
+    }
+
+    #[test]
+    fn parse_strict() {
+        assert!(!parse_args(args(&["COMMIT_EDITMSG"])).unwrap().strict);
+        assert!(
+            parse_args(args(&["--strict", "COMMIT_EDITMSG"]))
+                .unwrap()
+                .strict
+        );
+    }
+
+    #[test]
+    fn parse_fix() {
+        assert!(!parse_args(args(&["COMMIT_EDITMSG"])).unwrap().fix);
+        assert!(parse_args(args(&["--fix", "COMMIT_EDITMSG"])).unwrap().fix);
+    }
+
+    #[test]
+    fn parse_dry_run() {
+        assert!(!parse_args(args(&["COMMIT_EDITMSG"])).unwrap().dry_run);
+        assert!(
+            parse_args(args(&["--dry-run", "COMMIT_EDITMSG"]))
+                .unwrap()
+                .dry_run
+        );
+    }
+
+    #[test]
+    fn describe_every_option() {
+        let help = help();
+        for &(option, value, _) in &OPTIONS {
+            assert!(help.contains(option), "{}", option);
+            if ["--path", "--force", "--help", "--version"].contains(&option) {
+                continue;
+            }
+            let value = match (value, option_values(option)) {
+                (None, _) => None,
+                (Some(_), Some(values)) => Some(values[0]),
+                (Some("N"), None) => Some("72"),
+                (Some("C"), None) => Some(";"),
+                (Some("PERCENT"), None) => Some("90"),
+                (Some(_), None) => Some("value"),
+            };
+            let mut option_args: Vec<_> = args(&[option]).chain(value.map(str::to_owned)).collect();
+            if option == "--print-ast" {
+                option_args.extend(args(&["--format", "json"]));
+            }
+            let parsed = parse_args(option_args.into_iter());
+            assert!(parsed.is_ok(), "{}: {:?}", option, parsed.err());
+        }
+        assert!(parse_hook_args(args(&["--path", "repo", "--force"]), true).is_ok());
+    }
+
+    #[test]
+    fn generate_completions() {
+        for shell in &["bash", "zsh", "fish"] {
+            let script = completions(shell).unwrap();
+            assert!(script.contains("max-body-line-length"), "{}", shell);
+            assert!(script.contains("subject-imperative"), "{}", shell);
+            assert!(script.contains("pre-receive"), "{}", shell);
+        }
+        assert!(completions("bash").unwrap().contains(
+            "--format) COMPREPLY=($(compgen -W \"text json github markdown checkstyle gitlab sarif teamcity junit\" -- \"$cur\"))"
+        ));
+        assert!(completions("zsh")
+            .unwrap()
+            .starts_with("#compdef validate-commit\n"));
+        assert!(completions("fish")
+            .unwrap()
+            .contains("complete -c validate-commit -l strict -d 'Treat the warnings as errors'\n"));
+        assert_eq!(completions("tcsh"), None);
+    }
+
+    #[test]
+    fn parse_hook_arguments() {
+        let parsed = parse_hook_args(args(&[]), true).unwrap();
+        assert_eq!(parsed.path, PathBuf::from("."));
+        assert!(!parsed.force);
+        let parsed = parse_hook_args(args(&["--path", "../repo", "--force"]), true).unwrap();
+        assert_eq!(parsed.path, PathBuf::from("../repo"));
+        assert!(parsed.force);
+        assert_eq!(
+            parse_hook_args(args(&["--force"]), false).unwrap_err(),
+            "Unknown option --force"
+        );
+        assert!(parse_hook_args(args(&["--path"]), true).is_err());
+    }
+
+    #[test]
+    fn parse_commit_selections() {
+        let select = |arguments: &[&str]| {
+            let mut parsed = parse_args(args(arguments)).unwrap();
+            let command = parsed.file_paths.remove(0);
+            select_commits(&command, &parsed)
+        };
+        let range = |from: &str, to: &str| Ok(Commits::Range(from.to_owned(), to.to_owned()));
+        assert_eq!(
+            select(&["range", "origin/main..HEAD~1"]),
+            range("origin/main", "HEAD~1")
+        );
+        assert_eq!(select(&["range", "v1.0.."]), range("v1.0", "HEAD"));
+        assert_eq!(select(&["range", "--from", "v1.0"]), range("v1.0", "HEAD"));
+        assert_eq!(
+            select(&["range", "--from", "v1.0", "--to", "v2.0"]),
+            range("v1.0", "v2.0")
+        );
+        assert!(select(&["range", "v1.0"]).is_err());
+        assert!(select(&["range"]).is_err());
+        assert!(select(&["range", "v1.0..", "--from", "v1.0"]).is_err());
+        assert!(
+            parse_args(args(&["range", "v1.0..", "--no-skip-merges"]))
+                .unwrap()
+                .no_skip_merges
+        );
+
+        assert_eq!(select(&["head"]), Ok(Commits::Rev("HEAD".to_owned())));
+        assert_eq!(
+            select(&["rev", "HEAD~2"]),
+            Ok(Commits::Rev("HEAD~2".to_owned()))
+        );
+        assert!(select(&["head", "HEAD~2"]).is_err());
+        assert_eq!(
+            select(&["rev"]).unwrap_err(),
+            "Need exactly one revision for rev"
+        );
+        assert_eq!(select(&["pre-receive"]), Ok(Commits::PreReceive));
+        assert_eq!(select(&["bump", "v1.0.."]), range("v1.0", "HEAD"));
+        assert_eq!(select(&["bump", "--from", "v1.0"]), range("v1.0", "HEAD"));
+        assert!(select(&["bump"]).is_err());
+        assert_eq!(
+            select(&["changelog", "v1.0..v2.0", "--format", "markdown"]),
+            range("v1.0", "v2.0")
+        );
+        assert!(select(&["changelog"]).is_err());
+        assert_eq!(select(&["stats", "--from", "v1.0"]), range("v1.0", "HEAD"));
+        assert_eq!(
+            select(&["baseline", "--write", "baseline.txt"]),
+            Ok(Commits::History("HEAD".to_owned()))
+        );
+        assert_eq!(
+            select(&["baseline", "--write", "baseline.txt", "v1.0.."]),
+            range("v1.0", "HEAD")
+        );
+        assert!(select(&["pre-receive", "HEAD"]).is_err());
+    }
+
+    #[test]
+    fn parse_require_signoff() {
+        assert!(
+            !parse_args(args(&["COMMIT_EDITMSG"]))
+                .unwrap()
+                .require_signoff
+        );
+        assert!(
+            parse_args(args(&["--require-signoff", "COMMIT_EDITMSG"]))
+                .unwrap()
+                .require_signoff
+        );
+    }
+
+    #[test]
+    fn parse_pr_title() {
+        let parsed = parse_args(args(&["--pr-title", "--message", "feat: add (#12)"])).unwrap();
+        assert!(parsed.pr_title);
+        assert!(build_options(&parsed).pr_title);
+    }
+
+    #[test]
+    fn parse_config_format() {
+        let parsed = parse_args(args(&["--message", "feat: add colors"])).unwrap();
+        assert_eq!(parsed.config_format, ConfigFormat::Native);
+        let parsed = parse_args(args(&["--config-format", "commitlint"])).unwrap();
+        assert_eq!(parsed.config_format, ConfigFormat::Commitlint);
+        assert!(parse_args(args(&["--config-format", "yaml"])).is_err());
+    }
+
+    #[test]
+    fn render_json_ast() {
+        let options = ValidationOptions::default();
+        assert_eq!(
+            json_ast("feat(lib): add ast\n\nCloses: #5", &options),
+            r##"{"header": {"type": "feat", "scope": "lib", "breaking": false, "subject": "add ast", "ticket": null, "emoji": null, "spans": {"type": {"start": 0, "end": 4}, "scope": {"start": 5, "end": 8}, "parentheses": {"start": 4, "end": 9}, "breaking": null, "subject": {"start": 11, "end": 18}, "ticket": null, "emoji": null}}, "body": null, "footers": [{"token": "Closes", "value": "#5"}]}"##
+        );
+        assert_eq!(json_ast("Add ast", &options), "null");
+        assert!(parse_args(args(&["--print-ast", "--format", "json"])).is_ok());
+        assert!(parse_args(args(&["--print-ast"])).is_err());
+        assert!(parse_args(args(&["--format", "github", "--print-ast"])).is_err());
+    }
+
+    #[test]
+    fn render_json_file_report() {
+        assert_eq!(
+            json_file_report("a.txt", &ValidationReport::default(), None),
+            r#"{"file": "a.txt", "ok": true}"#
+        );
+    }
+
+    #[test]
+    fn render_checkstyle_report() {
+        let options = ValidationOptions {
+            severities: [(RuleId::SubjectNotCapitalized, Severity::Warning)].into(),
+            ..ValidationOptions::default()
+        };
+        let report =
+            validate_commit::validate_commit_message_report_with("f<&\"t: Add 'tags'", &options);
+        let files = [
+            checkstyle_file("a&b.txt", &report),
+            checkstyle_file(
+                "<stdin>",
+                &validate_commit::validate_commit_message_report_with(
+                    "feat: Add <tags> & \"quotes\"",
+                    &options,
+                ),
+            ),
+            checkstyle_file("b.txt", &ValidationReport::default()),
+        ];
+        assert_eq!(
+            checkstyle_report(&files),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<checkstyle version="4.3">
+  <file name="a&amp;b.txt">
+    <error line="1" column="1" severity="error" message="Invalid commit type &apos;f&lt;&amp;&quot;t&apos;" source="validate-commit.type-valid"/>
+  </file>
+  <file name="&lt;stdin&gt;">
+    <error line="1" column="7" severity="warning" message="First letter must not be capitalized" source="validate-commit.subject-not-capitalized"/>
+  </file>
+  <file name="b.txt">
+  </file>
+</checkstyle>
+"#
+        );
+        assert_eq!(xml_escape("a\tb\n\u{1b}"), "a&#9;b&#10;\u{fffd}");
+        let parsed = parse_args(args(&["--format", "checkstyle", "COMMIT_EDITMSG"])).unwrap();
+        assert_eq!(parsed.format, Some(Format::Checkstyle));
+    }
+
+    #[cfg(feature = "git")]
+    /// Check that the document only uses the elements and attributes of the JUnit schema read by
+    /// Jenkins, nested like it requires, and that the counts of the test suite are right.
+    fn check_junit_schema(document: &str) {
+        let schema: &[(&str, &str, &[&str])] = &[
+            ("testsuites", "", &[]),
+            (
+                "testsuite",
+                "testsuites",
+                &["name", "tests", "failures", "errors", "skipped"],
+            ),
+            ("testcase", "testsuite", &["name", "classname"]),
+            ("failure", "testcase", &["message", "type"]),
+            ("skipped", "testcase", &[]),
+            ("system-out", "testcase", &[]),
+        ];
+        let body = document
+            .strip_prefix("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n")
+            .unwrap();
+        let mut open: Vec<&str> = Vec::new();
+        let mut counts = std::collections::HashMap::new();
+        let mut suite_attributes = Vec::new();
+        for tag in body.split('<').skip(1) {
+            let tag = &tag[..tag.find('>').unwrap()];
+            if let Some(name) = tag.strip_prefix('/') {
+                assert_eq!(open.pop(), Some(name));
+                continue;
+            }
+            let (tag, closed) = match tag.strip_suffix('/') {
+                Some(tag) => (tag, true),
+                None => (tag, false),
+            };
+            let name = tag.split(' ').next().unwrap();
+            let &(_, parent, attributes) = schema
+                .iter()
+                .find(|(element, _, _)| *element == name)
+                .unwrap_or_else(|| panic!("Unknown element {}", name));
+            assert_eq!(open.last().cloned().unwrap_or_default(), parent, "{}", name);
+            for attribute in tag[name.len()..].split('"').step_by(2) {
+                let attribute = attribute.trim().trim_end_matches('=');
+                assert!(
+                    attribute.is_empty() || attributes.contains(&attribute),
+                    "Unknown attribute {} of {}",
+                    attribute,
+                    name
+                );
+                if name == "testsuite" && !attribute.is_empty() {
+                    suite_attributes.push(attribute);
+                }
+            }
+            *counts.entry(name).or_insert(0) += 1;
+            if !closed {
+                open.push(name);
+            }
+        }
+        assert!(open.is_empty());
+        assert_eq!(suite_attributes.len(), 5);
+        let attribute = |name: &str| {
+            let start = body.find(&format!(" {}=\"", name)).unwrap() + name.len() + 3;
+            body[start..start + body[start..].find('"').unwrap()]
+                .parse::<usize>()
+                .unwrap()
+        };
+        let count = |name| counts.get(name).cloned().unwrap_or_default();
+        assert_eq!(attribute("tests"), count("testcase"));
+        assert_eq!(attribute("failures"), count("failure"));
+        assert_eq!(attribute("skipped"), count("skipped"));
+    }
+
+    #[cfg(feature = "git")]
+    #[test]
+    fn render_junit_report() {
+        use super::junit_report;
+
+        let options = ValidationOptions {
+            severities: [(RuleId::SubjectNotCapitalized, Severity::Warning)].into(),
+            ..ValidationOptions::default()
+        };
+        let commit = |id: &str, message: &str| {
+            let report = validate_commit::validate_commit_message_report_with(message, &options);
+            (id.to_owned(), message.to_owned(), Some(report))
+        };
+        let commits = [
+            commit("1234567", "feat: add colors"),
+            commit("89abcde", "f<t: add & test"),
+            commit("fedcba9", "fix: Handle input"),
+            ("7654321".to_owned(), "Merge branch 'main'".to_owned(), None),
+        ];
+        let document = junit_report("validate-commit main..HEAD", &commits);
+        assert_eq!(
+            document,
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites>
+  <testsuite name="validate-commit main..HEAD" tests="4" failures="1" errors="0" skipped="1">
+    <testcase name="1234567 feat: add colors" classname="validate-commit"/>
+    <testcase name="89abcde f&lt;t: add &amp; test" classname="validate-commit">
+      <failure message="Invalid commit type &apos;f&lt;t&apos;, did you mean &apos;feat&apos;?" type="type-valid">error: Invalid commit type &apos;f&lt;t&apos;, did you mean &apos;feat&apos;? [VC004 type-valid]&#10; --&gt; 89abcde:1:1&#10;  |&#10;1 | f&lt;t: add &amp; test&#10;  | ^^^&#10;  = help: write &apos;feat&apos; instead of &apos;f&lt;t&apos;&#10;</failure>
+    </testcase>
+    <testcase name="fedcba9 fix: Handle input" classname="validate-commit">
+      <system-out>warning: First letter must not be capitalized [VC001 subject-not-capitalized]&#10; --&gt; fedcba9:1:6&#10;  |&#10;1 | fix: Handle input&#10;  |      ^&#10;  = help: write &apos;handle&apos; instead of &apos;Handle&apos;&#10;</system-out>
+    </testcase>
+    <testcase name="7654321 Merge branch &apos;main&apos;" classname="validate-commit">
+      <skipped/>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+        );
+        check_junit_schema(&document);
+        check_junit_schema(&junit_report("validate-commit", &[]));
+
+        let parsed = parse_args(args(&["--format", "junit", "range", "a..b"])).unwrap();
+        assert_eq!(parsed.format, Some(Format::Junit));
+    }
+
+    #[test]
+    fn render_gitlab_report() {
+        let options = ValidationOptions {
+            max_body_line_length: 20,
+            severities: [(RuleId::SubjectNotCapitalized, Severity::Warning)].into(),
+            ..ValidationOptions::default()
+        };
+        let report =
+            |message| validate_commit::validate_commit_message_report_with(message, &options);
+        let fingerprints = |issues: &[String]| -> Vec<Value> {
+            issues
+                .iter()
+                .map(|issue| serde_json::from_str::<Value>(issue).unwrap()["fingerprint"].clone())
+                .collect()
+        };
+        let message = "feat: Add \"tags\"\n\nTag the commits of the releases\n# Comment\n";
+        let issues = gitlab_report(".git/COMMIT_EDITMSG", message, '#', &report(message));
+        assert_eq!(
+            issues,
+            [
+                r#"{"description": "Line must not be longer than 20 characters", "check_name": "line-max-length", "fingerprint": "3dfd9b60ba185675", "severity": "major", "location": {"path": ".git/COMMIT_EDITMSG", "lines": {"begin": 3}}}"#,
+                r#"{"description": "First letter must not be capitalized", "check_name": "subject-not-capitalized", "fingerprint": "190450cf3ce5def6", "severity": "minor", "location": {"path": ".git/COMMIT_EDITMSG", "lines": {"begin": 1}}}"#,
+            ]
+        );
+
+        // Only the comments and the blank space changed
+        let same = "feat: Add \"tags\"\n\nTag the  commits of the releases\n\n# Other comment";
+        assert_eq!(
+            gitlab_report(".git/COMMIT_EDITMSG", same, '#', &report(same)),
+            issues
+        );
+        let other = "feat: Add \"tags\"\n\nTag the commits of the new releases";
+        let other_issues = gitlab_report(".git/COMMIT_EDITMSG", other, '#', &report(other));
+        assert_eq!(other_issues.len(), 2);
+        assert!(other_issues.iter().all(|issue| !issues.contains(issue)));
+        // The same message in another file is another issue
+        let moved_issues = gitlab_report("<message 1>", message, '#', &report(message));
+        let moved_fingerprints = fingerprints(&moved_issues);
+        assert!(fingerprints(&issues)
+            .iter()
+            .all(|fingerprint| !moved_fingerprints.contains(fingerprint)));
+        assert!(gitlab_report("a.txt", "feat: add", '#', &report("feat: add")).is_empty());
+        assert_eq!(fnv1a_hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+
+        // Each line too long is a distinct issue
+        let message =
+            "feat: add tags\n\nTag the commits of the releases\nTag the commits of the branches";
+        let fingerprints = fingerprints(&gitlab_report("a.txt", message, '#', &report(message)));
+        assert_eq!(fingerprints.len(), 2);
+        assert_ne!(fingerprints[0], fingerprints[1]);
+
+        let parsed = parse_args(args(&["--format", "gitlab", "COMMIT_EDITMSG"])).unwrap();
+        assert_eq!(parsed.format, Some(Format::Gitlab));
+    }
+
+    /// Check the JSON value against the schema, supporting the keywords of the SARIF schema
+    /// fixture.
+    fn check_json_schema(value: &Value, schema: &Value, root: &Value, path: &str) {
+        if let Some(reference) = schema["$ref"].as_str() {
+            let name = reference.strip_prefix("#/definitions/").unwrap();
+            return check_json_schema(value, &root["definitions"][name], root, path);
+        }
+        if let Some(variants) = schema["enum"].as_array() {
+            assert!(
+                variants.contains(value),
+                "{}: {} not in {:?}",
+                path,
+                value,
+                variants
+            );
+        }
+        if let Some(minimum) = schema["minimum"].as_i64() {
+            assert!(value.as_i64().unwrap() >= minimum, "{}: {}", path, value);
+        }
+        match schema["type"].as_str() {
+            Some("object") => {
+                let object = value.as_object().unwrap_or_else(|| panic!("{}", path));
+                for required in schema["required"].as_array().into_iter().flatten() {
+                    let required = required.as_str().unwrap();
+                    assert!(object.contains_key(required), "{}: no {}", path, required);
+                }
+                for (key, value) in object {
+                    let property = &schema["properties"][key];
+                    assert!(!property.is_null(), "{}: unknown property {}", path, key);
+                    check_json_schema(value, property, root, &format!("{}.{}", path, key));
+                }
+            }
+            Some("array") => {
+                let items = value.as_array().unwrap_or_else(|| panic!("{}", path));
+                for (i, item) in items.iter().enumerate() {
+                    check_json_schema(item, &schema["items"], root, &format!("{}[{}]", path, i));
+                }
+            }
+            Some("string") => assert!(value.is_string(), "{}", path),
+            Some("integer") => assert!(value.is_i64(), "{}", path),
+            Some(other) => panic!("Unsupported type {}", other),
+            None => {}
+        }
+    }
+
+    #[test]
+    fn render_sarif_log() {
+        let options = ValidationOptions {
+            severities: [(RuleId::SubjectNotCapitalized, Severity::Warning)].into(),
+            ..ValidationOptions::default()
+        };
+        let report =
+            |message| validate_commit::validate_commit_message_report_with(message, &options);
+        let mut results = sarif_report(".git/COMMIT_EDITMSG", &report("feet: Add colors"));
+        results.extend(sarif_report("<message 2>", &report("")));
+        assert_eq!(
+            results,
+            [
+                r#"{"ruleId": "type-valid", "ruleIndex": 5, "level": "error", "message": {"text": "Invalid commit type 'feet', did you mean 'feat'?"}, "locations": [{"physicalLocation": {"artifactLocation": {"uri": ".git/COMMIT_EDITMSG"}, "region": {"startLine": 1, "startColumn": 1}}}]}"#,
+                r#"{"ruleId": "message-not-empty", "ruleIndex": 0, "level": "error", "message": {"text": "Empty commit message"}, "locations": [{"physicalLocation": {"artifactLocation": {"uri": "%3Cmessage%202%3E"}}}]}"#,
+            ]
+        );
+
+        let log: Value = serde_json::from_str(&sarif_log(&results)).unwrap();
+        let schema: Value =
+            serde_json::from_str(include_str!("../tests/fixtures/sarif/schema.json")).unwrap();
+        check_json_schema(&log, &schema, &schema, "log");
+        let driver = &log["runs"][0]["tool"]["driver"];
+        assert_eq!(driver["version"], env!("CARGO_PKG_VERSION"));
+        let rules = driver["rules"].as_array().unwrap();
+        assert_eq!(rules.len(), RuleId::ALL.len() + 1);
+        for result in log["runs"][0]["results"].as_array().unwrap() {
+            let index = result["ruleIndex"].as_u64().unwrap() as usize;
+            assert_eq!(rules[index]["id"], result["ruleId"]);
+        }
+        let empty: Value = serde_json::from_str(&sarif_log(&[])).unwrap();
+        check_json_schema(&empty, &schema, &schema, "log");
+
+        // The errors of the plugins and custom validators share the descriptor of custom rules
+        let custom = ValidationReport {
+            errors: vec![FormatErrorKind::Custom {
+                rule: "no-temp".to_owned(),
+                message: "Temporary commit".to_owned(),
+            }
+            .into()],
+            warnings: Vec::new(),
+        };
+        let results = sarif_report("<message 1>", &custom);
+        let log: Value = serde_json::from_str(&sarif_log(&results)).unwrap();
+        check_json_schema(&log, &schema, &schema, "log");
+        let result = &log["runs"][0]["results"][0];
+        assert_eq!(result["ruleId"], "custom");
+        let index = result["ruleIndex"].as_u64().unwrap() as usize;
+        assert_eq!(
+            log["runs"][0]["tool"]["driver"]["rules"][index]["id"],
+            "custom"
+        );
+
+        let parsed = parse_args(args(&["--format", "sarif", "COMMIT_EDITMSG"])).unwrap();
+        assert_eq!(parsed.format, Some(Format::Sarif));
+    }
+
+    #[test]
+    fn escape_teamcity_values() {
+        assert_eq!(teamcity_escape("feat: add colors"), "feat: add colors");
+        assert_eq!(teamcity_escape("it's"), "it|'s");
+        assert_eq!(teamcity_escape("a|b"), "a||b");
+        assert_eq!(teamcity_escape("||'"), "|||||'");
+        assert_eq!(teamcity_escape("[type-valid]"), "|[type-valid|]");
+        assert_eq!(teamcity_escape("a\r\nb\n"), "a|r|nb|n");
+        assert_eq!(teamcity_escape("a\u{85}b\u{2028}c\u{2029}"), "a|xb|lc|p");
+        // Escaped characters are not escaped again
+        assert_eq!(teamcity_escape("|n"), "||n");
+        assert_eq!(teamcity_escape("é\t"), "é\t");
+    }
+
+    #[test]
+    fn render_teamcity_report() {
+        let options = ValidationOptions {
+            severities: [(RuleId::SubjectNotCapitalized, Severity::Warning)].into(),
+            ..ValidationOptions::default()
+        };
+        let report =
+            |message| validate_commit::validate_commit_message_report_with(message, &options);
+        assert_eq!(
+            teamcity_report("it's.txt", &report("feet: Add [colors]")),
+            "##teamcity[buildProblem description='it|'s.txt:1:1: Invalid commit type |'feet|', did \
+             you mean |'feat|'? |[type-valid|]' identity='type-valid-86395161c4360f05']\n"
+        );
+        assert_eq!(
+            teamcity_report("1234567", &report("feat: Add colors")),
+            "##teamcity[message text='1234567:1:7: First letter must not be capitalized \
+             |[subject-not-capitalized|]' status='WARNING']\n"
+        );
+        assert_eq!(teamcity_report("a.txt", &report("feat: add colors")), "");
+
+        let parsed = parse_args(args(&["--format", "teamcity", "COMMIT_EDITMSG"])).unwrap();
+        assert_eq!(parsed.format, Some(Format::Teamcity));
+    }
+
+    #[test]
+    fn escape_json_strings() {
+        assert_eq!(json_string("a\"b\\c\td\u{1}"), r#""a\"b\\c\td\u0001""#);
+    }
+}
@@ -0,0 +1,234 @@
+//! Heuristic detection of the subjects which are not in the imperative mood.
+
+/// Words ending like a conjugated verb, but which are not
+const EXCEPTIONS: &[&str] = &[
+    // -s
+    "alias",
+    "always",
+    "bias",
+    "canvas",
+    "deps",
+    "docs",
+    "heroes",
+    "macos",
+    "news",
+    "perhaps",
+    "series",
+    "shoes",
+    "specs",
+    "stats",
+    "towards",
+    "utils",
+    "windows",
+    // -ed
+    "bed",
+    "embed",
+    "shed",
+    "shred",
+    // -ing
+    "anything",
+    "bring",
+    "ceiling",
+    "cling",
+    "during",
+    "everything",
+    "fling",
+    "king",
+    "nothing",
+    "ping",
+    "ring",
+    "sing",
+    "sling",
+    "something",
+    "spring",
+    "sting",
+    "string",
+    "swing",
+    "thing",
+    "wing",
+];
+
+/// Endings of the stems losing their final `e` when conjugated, like `updat` for `updated`
+const E_DROPPING_ENDINGS: &[&str] = &[
+    "ac", "ang", "ar", "as", "at", "bl", "cid", "dl", "gl", "ib", "in", "ir", "is", "iv", "iz",
+    "kl", "let", "ok", "ak", "am", "os", "ov", "pl", "rc", "rg", "rid", "rs", "rv", "tl", "uc",
+    "ud", "ur", "us", "ut", "vid", "yz",
+];
+
+/// Endings of the stems keeping their `e` ending although they match [`E_DROPPING_ENDINGS`]
+const E_KEEPING_ENDINGS: &[&str] = &["ain", "ear", "eat", "ias", "oat", "oin", "out"];
+
+/// Endings of the stems taking `-es` instead of `-s`, like `fix` for `fixes`
+const ES_TAKING_ENDINGS: &[&str] = &["ss", "zz", "x", "ch", "sh", "o"];
+
+/// Stems ending in `-che` although they match [`ES_TAKING_ENDINGS`], like `cach` for `caches`
+const CHE_ENDING_STEMS: &[&str] = &["cach", "nich"];
+
+/// Check if the word looks like a conjugated verb, ignoring the case.
+///
+/// Return `Some` with the base form of the verb if it can be derived, `Some(None)` if the word
+/// is not in the imperative mood but its base form is unknown, and `None` if the word is fine.
+pub fn check_imperative(
+    word: &str,
+    allowed_words: &[String],
+    denied_words: &[String],
+) -> Option<Option<String>> {
+    let word = word.to_lowercase();
+    let denied = denied_words.iter().any(|w| w.to_lowercase() == word);
+    if !denied
+        && (allowed_words.iter().any(|w| w.to_lowercase() == word)
+            || EXCEPTIONS.contains(&word.as_str()))
+    {
+        return None;
+    }
+
+    let base_form = base_form(&word);
+    if denied || base_form.is_some() {
+        Some(base_form)
+    } else {
+        None
+    }
+}
+
+/// Guess the base form of a verb ending in `-s`, `-ed` or `-ing`.
+fn base_form(word: &str) -> Option<String> {
+    let len = word.chars().count();
+    if len > 4 {
+        if let Some(stem) = word.strip_suffix("ing") {
+            return Some(restore_stem(stem));
+        }
+    }
+    if len > 3 {
+        if let Some(stem) = word.strip_suffix("ied") {
+            return Some(format!("{}y", stem));
+        }
+        if let Some(stem) = word.strip_suffix("ed") {
+            if !stem.ends_with('e') {
+                return Some(restore_stem(stem));
+            }
+        }
+        if let Some(stem) = word.strip_suffix("ies") {
+            return Some(format!("{}y", stem));
+        }
+        if let Some(stem) = word.strip_suffix("es") {
+            // fixes, pushes, does, but not uses nor caches
+            if ES_TAKING_ENDINGS.iter().any(|e| stem.ends_with(e))
+                && !CHE_ENDING_STEMS.iter().any(|e| stem.ends_with(e))
+            {
+                return Some(stem.to_owned());
+            }
+        }
+        if ["ss", "us", "is"].iter().any(|e| word.ends_with(e)) {
+            return None;
+        }
+        if let Some(stem) = word.strip_suffix('s') {
+            return Some(stem.to_owned());
+        }
+    }
+    None
+}
+
+/// Restore the base form of a verb from the stem left by removing `-ed` or `-ing`.
+fn restore_stem(stem: &str) -> String {
+    let mut chars = stem.chars().rev();
+    let last = chars.next();
+    let doubled = last.is_some() && last == chars.next();
+    let last = last.unwrap_or_default();
+    if doubled && last.is_ascii_alphabetic() && !"aeioudflsz".contains(last) {
+        // stopped, committing
+        stem[..stem.len() - last.len_utf8()].to_owned()
+    } else if E_DROPPING_ENDINGS.iter().any(|e| stem.ends_with(e))
+        && !E_KEEPING_ENDINGS.iter().any(|e| stem.ends_with(e))
+    {
+        // updated, using
+        format!("{}e", stem)
+    } else {
+        stem.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(word: &str) -> Option<Option<String>> {
+        check_imperative(word, &[], &[])
+    }
+
+    #[test]
+    fn accept_imperative_words() {
+        for word in &[
+            "add", "fix", "update", "process", "focus", "bring", "embed", "need", "use", "is",
+            "Windows", "docs", "macOS",
+        ] {
+            assert_eq!(check(word), None, "{}", word);
+        }
+    }
+
+    #[test]
+    fn derive_base_forms() {
+        for &(word, base_form) in &[
+            ("added", "add"),
+            ("Adds", "add"),
+            ("adding", "add"),
+            ("fixed", "fix"),
+            ("fixes", "fix"),
+            ("updated", "update"),
+            ("updating", "update"),
+            ("removed", "remove"),
+            ("stopped", "stop"),
+            ("committing", "commit"),
+            ("applied", "apply"),
+            ("copies", "copy"),
+            ("installed", "install"),
+            ("used", "use"),
+            ("using", "use"),
+            ("renamed", "rename"),
+            ("handled", "handle"),
+            ("merged", "merge"),
+            ("changed", "change"),
+            ("joined", "join"),
+            ("cleaned", "clean"),
+            ("does", "do"),
+            ("goes", "go"),
+            ("undoes", "undo"),
+            ("echoes", "echo"),
+            ("uses", "use"),
+            ("closes", "close"),
+            ("releases", "release"),
+            ("parses", "parse"),
+            ("optimizes", "optimize"),
+            ("normalizes", "normalize"),
+            ("caches", "cache"),
+            ("released", "release"),
+            ("increased", "increase"),
+            ("rebased", "rebase"),
+            ("aliased", "alias"),
+            ("passes", "pass"),
+            ("buzzes", "buzz"),
+            ("pushes", "push"),
+            ("catches", "catch"),
+        ] {
+            assert_eq!(check(word), Some(Some(base_form.to_owned())), "{}", word);
+        }
+    }
+
+    #[test]
+    fn keep_doubled_non_ascii_letters() {
+        assert_eq!(check("ééing"), Some(Some("éé".to_owned())));
+        assert_eq!(check("日日ed"), Some(Some("日日".to_owned())));
+    }
+
+    #[test]
+    fn configure_words() {
+        let words = vec!["things".to_owned(), "misc".to_owned()];
+        assert_eq!(check("things"), Some(Some("thing".to_owned())));
+        assert_eq!(check_imperative("things", &words, &[]), None);
+        assert_eq!(check("misc"), None);
+        assert_eq!(check_imperative("misc", &[], &words), Some(None));
+        assert_eq!(
+            check_imperative("things", &words, &words),
+            Some(Some("thing".to_owned()))
+        );
+    }
+}
@@ -0,0 +1,400 @@
+use std::collections::{BTreeMap, BTreeSet};
+#[cfg(feature = "regex")]
+use std::fmt;
+#[cfg(feature = "regex")]
+use std::str::FromStr;
+
+#[cfg(feature = "regex")]
+use regex::Regex;
+#[cfg(feature = "config")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use parse::is_git_merge;
+#[cfg(feature = "plugin")]
+use plugin::Plugin;
+use rules::{RuleId, Severity};
+use CommitType;
+
+/// Options to customize the validation of commit messages
+///
+/// The default options follow the Angular convention. With the `config` feature, they can be
+/// loaded from a configuration file, see the [`config`](::config) module.
+///
+/// The options holding a `Pattern` need the `regex` feature. Without it, the issue references
+/// must match the [default pattern](ValidationOptions::DEFAULT_REFERENCE_PATTERN).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "config",
+    derive(Deserialize, Serialize),
+    serde(default, deny_unknown_fields)
+)]
+pub struct ValidationOptions {
+    /// Commit types allowed in the header, overriding the built-in ones if provided
+    ///
+    /// Types which are not built-in are parsed as [`CommitType::Custom`](::CommitType::Custom).
+    pub allowed_types: Option<Vec<String>>,
+    /// Accept commit types with uppercase letters, like `Feat`, and parse them as lowercase
+    pub allow_uppercase_types: bool,
+    /// Spellings accepted for the commit types, like `feature` for `feat`
+    ///
+    /// A header using an alias is parsed with the canonical type, and a `NonCanonicalType`
+    /// warning suggests the canonical spelling. An alias must not be an allowed type itself.
+    pub aliases: BTreeMap<String, CommitType>,
+    /// Report the aliases of the commit types as errors instead of warnings
+    pub strict_aliases: bool,
+    /// Scopes allowed in the header, any scope being allowed if not provided
+    ///
+    /// The scopes can be glob patterns, where `*` matches any sequence of characters except `/`,
+    /// like `api/*`.
+    pub allowed_scopes: Option<Vec<String>>,
+    /// Format the scopes must follow
+    pub scope_pattern: ScopePattern,
+    /// Pattern replacing the `type(scope)!: subject` format of the header
+    ///
+    /// The named groups `type`, `scope` and `subject` fill the parsed header. Without `type`
+    /// group, the commit type is an empty [`CommitType::Custom`](::CommitType::Custom) and is not
+    /// validated. Without `subject` group, the subject is the whole header.
+    #[cfg(feature = "regex")]
+    pub header_pattern: Option<Pattern>,
+    /// Pattern of a ticket tag accepted before the header, like `^\[[A-Z]+-\d+\]`
+    ///
+    /// A tag matching at the start of the header is removed before parsing it, and is kept in
+    /// [`CommitHeader::ticket`](::CommitHeader::ticket). The length of the header includes it.
+    #[cfg(feature = "regex")]
+    pub allow_ticket_prefix: Option<Pattern>,
+    /// Whether a single emoji, like `✨` or `:sparkles:`, is accepted before the header
+    ///
+    /// The emoji and the space after it are removed before parsing the header, and the emoji is
+    /// kept in [`CommitHeader::emoji`](::CommitHeader::emoji). The length of the header includes
+    /// it.
+    pub allow_emoji_prefix: bool,
+    /// Whether the header must, may or must not contain a scope
+    pub scope_policy: ScopePolicy,
+    /// Character starting the comment lines, like git's `core.commentChar`
+    pub comment_char: char,
+    /// Characters the subject must not end with, `.` by default
+    ///
+    /// An ellipsis `...` is a trailing `.` too, as is a period following a version number, like
+    /// in `bump to v1.2.`.
+    pub subject_trailing_punctuation: String,
+    /// Messages whose first line starts with one of these prefixes are not validated
+    pub ignore_prefixes: Vec<String>,
+    /// Messages whose first line matches one of these patterns are not validated
+    #[cfg(feature = "regex")]
+    pub ignore_patterns: Vec<Pattern>,
+    /// Headers accepted as they are, like `Initial commit`
+    pub allowed_literal_headers: Vec<String>,
+    /// Patterns of the headers accepted as they are, like `^Release \d+\.\d+\.\d+$`
+    #[cfg(feature = "regex")]
+    pub allowed_header_regexes: Vec<Pattern>,
+    /// Validate the messages starting with `Merge`, unless they were generated by git, like
+    /// `Merge branch 'develop'`
+    pub validate_merges: bool,
+    /// Validate the header reverted by the messages generated by `git revert`, like
+    /// `Revert "feat: add scopes"`, instead of accepting them
+    pub strict_reverts: bool,
+    /// Validate only the header, as the title of a pull request, like for squash merges
+    ///
+    /// The ` (#123)` suffix appended by GitHub is removed before the validation, and the rules of
+    /// the body and the footers are not checked.
+    pub pr_title: bool,
+    /// Capitalized words accepted at the start of the subject, like proper nouns
+    ///
+    /// Acronyms like `JSON` are always accepted.
+    pub allowed_capitalized_words: Vec<String>,
+    /// Words accepted as the first word of the subject, even if they look like a conjugated verb
+    pub imperative_allowed_words: Vec<String>,
+    /// Words rejected as the first word of the subject, like `misc`
+    pub imperative_denied_words: Vec<String>,
+    /// Length of the subject above which a warning is reported, like 50 characters
+    pub soft_subject_limit: Option<usize>,
+    /// Commit types which must reference an issue, like `fix`
+    pub require_reference: Vec<CommitType>,
+    /// Pattern of the issue references, like `#42` or `PROJ-42` by default
+    ///
+    /// The reference can be anywhere in the subject, the body or the footers, like in a
+    /// `Closes: #42` footer.
+    #[cfg(feature = "regex")]
+    pub reference_pattern: Pattern,
+    /// Require a `Signed-off-by: Name <email>` footer, as added by `git commit --signoff`
+    ///
+    /// Each sign-off must have a name and a plausible email between angle brackets.
+    pub require_signoff: bool,
+    /// Commit types which must have a body, like `feat`
+    ///
+    /// The footers are not part of the body.
+    pub require_body: Vec<CommitType>,
+    /// Maximum length of the header, `0` to disable the check
+    pub max_header_length: usize,
+    /// Maximum length of each line of the body and footers, `0` to disable the check
+    pub max_body_line_length: usize,
+    /// Accept the body lines longer than the maximum length because of a URL, like
+    /// `See https://example.com/...`
+    ///
+    /// The line is accepted if it fits in the maximum length without the URL.
+    pub ignore_long_urls: bool,
+    /// Accept the body lines longer than the maximum length if they are indented by a tab or four
+    /// spaces, like code blocks
+    pub ignore_indented_lines: bool,
+    /// Words the subject and the body must not contain, like `temp` or `do not merge`
+    ///
+    /// The words match whole words, ignoring the case.
+    pub forbidden_words: Vec<String>,
+    /// Patterns the subject and the body must not match, like `(?i)\bfixme\b`
+    #[cfg(feature = "regex")]
+    pub forbidden_patterns: Vec<Pattern>,
+    /// Severities of the rules, overriding their default severity
+    pub severities: BTreeMap<RuleId, Severity>,
+    /// Rules which are not checked
+    pub disabled_rules: BTreeSet<RuleId>,
+    /// External commands checking the messages, run by the [`Validator`](::Validator)
+    ///
+    /// They are configured as `[[plugin]]` tables in the configuration file.
+    #[cfg(feature = "plugin")]
+    #[cfg_attr(
+        feature = "config",
+        serde(rename = "plugin", skip_serializing_if = "Vec::is_empty")
+    )]
+    pub plugins: Vec<Plugin>,
+}
+
+impl ValidationOptions {
+    /// Prefixes of the messages which are not validated by default
+    pub const DEFAULT_IGNORE_PREFIXES: [&'static str; 2] = ["Merge ", "WIP"];
+
+    /// Default pattern of the issue references, like `#42` or `PROJ-42`
+    pub const DEFAULT_REFERENCE_PATTERN: &'static str = r"#\d+|\b[A-Z][A-Z0-9]+-\d+\b";
+
+    /// Whether the message starting with the given line is not validated
+    pub fn is_ignored(&self, first_line: &str) -> bool {
+        if self
+            .allowed_literal_headers
+            .iter()
+            .any(|header| header == first_line)
+        {
+            return true;
+        }
+        #[cfg(feature = "regex")]
+        if self
+            .allowed_header_regexes
+            .iter()
+            .any(|pattern| pattern.is_match(first_line))
+        {
+            return true;
+        }
+
+        if self.validate_merges && first_line.starts_with("Merge") {
+            return is_git_merge(first_line);
+        }
+
+        #[cfg(feature = "regex")]
+        if self
+            .ignore_patterns
+            .iter()
+            .any(|pattern| pattern.is_match(first_line))
+        {
+            return true;
+        }
+        self.ignore_prefixes
+            .iter()
+            .any(|prefix| first_line.starts_with(prefix.as_str()))
+    }
+
+    /// Whether the given rule is checked with these options
+    pub fn is_enabled(&self, rule: RuleId) -> bool {
+        !self.disabled_rules.contains(&rule)
+    }
+
+    /// Severity of the given rule with these options
+    pub fn severity(&self, rule: RuleId) -> Severity {
+        match self.severities.get(&rule) {
+            Some(&severity) => severity,
+            None if rule == RuleId::TypeCanonical && self.strict_aliases => Severity::Error,
+            None => rule.default_severity(),
+        }
+    }
+
+    /// Whether the commit type is allowed as it is written, without alias
+    pub fn is_allowed_type(&self, commit_type: &str) -> bool {
+        match self.allowed_types {
+            Some(ref allowed_types) => allowed_types.iter().any(|t| t == commit_type),
+            None => CommitType::ALL.iter().any(|t| t.as_str() == commit_type),
+        }
+    }
+
+    /// Return the first alias which is an allowed type itself, and would never be used.
+    pub fn colliding_alias(&self) -> Option<&str> {
+        self.aliases
+            .keys()
+            .map(String::as_str)
+            .find(|alias| self.is_allowed_type(alias))
+    }
+}
+
+impl Default for ValidationOptions {
+    fn default() -> Self {
+        ValidationOptions {
+            allowed_types: None,
+            allow_uppercase_types: false,
+            aliases: BTreeMap::new(),
+            strict_aliases: false,
+            allowed_scopes: None,
+            scope_pattern: ScopePattern::default(),
+            #[cfg(feature = "regex")]
+            header_pattern: None,
+            #[cfg(feature = "regex")]
+            allow_ticket_prefix: None,
+            allow_emoji_prefix: false,
+            scope_policy: ScopePolicy::default(),
+            comment_char: '#',
+            subject_trailing_punctuation: ".".to_owned(),
+            ignore_prefixes: Self::DEFAULT_IGNORE_PREFIXES
+                .iter()
+                .map(|prefix| prefix.to_string())
+                .collect(),
+            #[cfg(feature = "regex")]
+            ignore_patterns: Vec::new(),
+            allowed_literal_headers: vec!["Initial commit".to_owned()],
+            #[cfg(feature = "regex")]
+            allowed_header_regexes: Vec::new(),
+            validate_merges: false,
+            strict_reverts: false,
+            pr_title: false,
+            allowed_capitalized_words: Vec::new(),
+            imperative_allowed_words: Vec::new(),
+            imperative_denied_words: Vec::new(),
+            soft_subject_limit: None,
+            require_reference: Vec::new(),
+            #[cfg(feature = "regex")]
+            reference_pattern: Pattern::new(Self::DEFAULT_REFERENCE_PATTERN).unwrap(),
+            require_signoff: false,
+            require_body: Vec::new(),
+            max_header_length: 100,
+            max_body_line_length: 100,
+            ignore_long_urls: true,
+            ignore_indented_lines: true,
+            forbidden_words: Vec::new(),
+            #[cfg(feature = "regex")]
+            forbidden_patterns: Vec::new(),
+            severities: BTreeMap::new(),
+            disabled_rules: BTreeSet::new(),
+            #[cfg(feature = "plugin")]
+            plugins: Vec::new(),
+        }
+    }
+}
+
+/// Regular expression which can be compared and loaded from a configuration file
+///
+/// Only available with the `regex` feature.
+#[cfg(feature = "regex")]
+#[derive(Clone)]
+pub struct Pattern(Regex);
+
+#[cfg(feature = "regex")]
+impl Pattern {
+    /// Compile the regular expression.
+    pub fn new(pattern: &str) -> Result<Pattern, regex::Error> {
+        Regex::new(pattern).map(Pattern)
+    }
+
+    /// Whether the regular expression matches somewhere in the text
+    pub fn is_match(&self, text: &str) -> bool {
+        self.0.is_match(text)
+    }
+
+    /// Compiled regular expression
+    pub fn regex(&self) -> &Regex {
+        &self.0
+    }
+
+    /// Source of the regular expression
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+#[cfg(feature = "regex")]
+impl FromStr for Pattern {
+    type Err = regex::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Pattern::new(s)
+    }
+}
+
+#[cfg(feature = "regex")]
+impl fmt::Debug for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+#[cfg(feature = "regex")]
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Compare the sources of the regular expressions
+#[cfg(feature = "regex")]
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Pattern) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+#[cfg(feature = "regex")]
+impl Eq for Pattern {}
+
+#[cfg(feature = "config")]
+impl<'de> Deserialize<'de> for Pattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        pattern.parse().map_err(::serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "config")]
+impl Serialize for Pattern {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Policy about the scope of the commit headers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "config",
+    derive(Deserialize, Serialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum ScopePolicy {
+    /// Headers may contain a scope
+    #[default]
+    Any,
+    /// Headers must contain a scope
+    Required,
+    /// Headers must not contain a scope
+    Forbidden,
+}
+
+/// Format of the scopes of the commit headers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "config",
+    derive(Deserialize, Serialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum ScopePattern {
+    /// Scopes may contain any character
+    #[default]
+    Any,
+    /// Scopes must not contain uppercase letters
+    Lowercase,
+    /// Scopes must be made of lowercase words separated by `-`, like `error-handling`
+    ///
+    /// Nested scopes are separated by `/`, like `ui/button`.
+    KebabCase,
+}
@@ -0,0 +1,283 @@
+//! Owned versions of the parsed commit messages, which do not borrow the input.
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use errors::FormatError;
+use {parse_commit_message, CommitHeader, CommitMsg, CommitType, Footer};
+
+/// Owned version of [`CommitMsg`]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CommitMsgBuf {
+    /// Commit header
+    pub header: CommitHeaderBuf,
+    /// Commit body, if provided
+    pub body: Option<String>,
+    /// Commit footers
+    pub footers: Vec<FooterBuf>,
+}
+
+/// Owned version of [`CommitHeader`]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CommitHeaderBuf {
+    /// Type of the commit
+    pub commit_type: CommitType,
+    /// Scope of the commit, if provided
+    pub scope: Option<String>,
+    /// Scopes of the commit, split on commas
+    pub scopes: Vec<String>,
+    /// Whether the header contains the breaking change marker `!`
+    pub breaking: bool,
+    /// Subject of the commit
+    pub subject: String,
+    /// Ticket tag before the header, like `[PROJ-42]`
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub ticket: Option<String>,
+    /// Emoji before the header, like `✨` or `:sparkles:`
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub emoji: Option<String>,
+}
+
+/// Owned version of [`Footer`]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FooterBuf {
+    /// Token of the footer
+    pub token: String,
+    /// Value of the footer
+    pub value: String,
+}
+
+impl CommitMsgBuf {
+    /// Borrow the message as a [`CommitMsg`].
+    pub fn as_msg(&self) -> CommitMsg<'_> {
+        CommitMsg {
+            header: self.header.as_header(),
+            body: self.body.as_deref(),
+            footers: self.footers.iter().map(FooterBuf::as_footer).collect(),
+        }
+    }
+
+    /// Whether the commit introduces a breaking change.
+    ///
+    /// See [`CommitMsg::is_breaking`].
+    pub fn is_breaking(&self) -> bool {
+        self.as_msg().is_breaking()
+    }
+}
+
+impl CommitHeaderBuf {
+    /// Borrow the header as a [`CommitHeader`].
+    pub fn as_header(&self) -> CommitHeader<'_> {
+        CommitHeader {
+            commit_type: self.commit_type.clone(),
+            scope: self.scope.as_deref(),
+            scopes: self.scopes.iter().map(String::as_str).collect(),
+            breaking: self.breaking,
+            subject: &self.subject,
+            ticket: self.ticket.as_deref(),
+            emoji: self.emoji.as_deref(),
+        }
+    }
+}
+
+impl FooterBuf {
+    /// Borrow the footer as a [`Footer`].
+    pub fn as_footer(&self) -> Footer<'_> {
+        Footer {
+            token: &self.token,
+            value: &self.value,
+        }
+    }
+}
+
+impl fmt::Display for CommitMsgBuf {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.as_msg().fmt(f)
+    }
+}
+
+impl fmt::Display for CommitHeaderBuf {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.as_header().fmt(f)
+    }
+}
+
+impl fmt::Display for FooterBuf {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.as_footer().fmt(f)
+    }
+}
+
+impl<'a> CommitMsg<'a> {
+    /// Copy the message into a [`CommitMsgBuf`], which does not borrow the input.
+    pub fn to_owned(&self) -> CommitMsgBuf {
+        CommitMsgBuf {
+            header: self.header.to_owned(),
+            body: self.body.map(str::to_owned),
+            footers: self.footers.iter().map(Footer::to_owned).collect(),
+        }
+    }
+}
+
+impl<'a> CommitHeader<'a> {
+    /// Copy the header into a [`CommitHeaderBuf`], which does not borrow the input.
+    pub fn to_owned(&self) -> CommitHeaderBuf {
+        CommitHeaderBuf {
+            commit_type: self.commit_type.clone(),
+            scope: self.scope.map(str::to_owned),
+            scopes: self.scopes.iter().map(|&scope| scope.to_owned()).collect(),
+            breaking: self.breaking,
+            subject: self.subject.to_owned(),
+            ticket: self.ticket.map(str::to_owned),
+            emoji: self.emoji.map(str::to_owned),
+        }
+    }
+}
+
+impl<'a> Footer<'a> {
+    /// Copy the footer into a [`FooterBuf`], which does not borrow the input.
+    pub fn to_owned(&self) -> FooterBuf {
+        FooterBuf {
+            token: self.token.to_owned(),
+            value: self.value.to_owned(),
+        }
+    }
+}
+
+impl<'a> From<CommitMsg<'a>> for CommitMsgBuf {
+    fn from(message: CommitMsg<'a>) -> Self {
+        message.to_owned()
+    }
+}
+
+/// Parse a commit message with the default options, see [`parse_commit_message`]
+///
+/// # Examples
+///
+/// ```
+/// # use validate_commit::{CommitHeaderBuf, CommitMsgBuf};
+/// let message: CommitMsgBuf = "fix(parser): parse owned messages".parse().unwrap();
+/// assert_eq!(message.header.scope, Some("parser".to_owned()));
+///
+/// let header: CommitHeaderBuf = "feat: add owned headers".parse().unwrap();
+/// assert_eq!(header.subject, "add owned headers");
+/// ```
+impl FromStr for CommitMsgBuf {
+    type Err = FormatError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        parse_commit_message(input).map(CommitMsgBuf::from)
+    }
+}
+
+/// Parse a single-line commit header with the default options, like [`CommitHeader::try_from`]
+impl FromStr for CommitHeaderBuf {
+    type Err = FormatError;
+
+    fn from_str(header: &str) -> Result<Self, Self::Err> {
+        CommitHeader::try_from(header).map(|header| header.to_owned())
+    }
+}
+
+impl<'a> PartialEq<CommitMsgBuf> for CommitMsg<'a> {
+    fn eq(&self, other: &CommitMsgBuf) -> bool {
+        *self == other.as_msg()
+    }
+}
+
+impl<'a> PartialEq<CommitMsg<'a>> for CommitMsgBuf {
+    fn eq(&self, other: &CommitMsg<'a>) -> bool {
+        self.as_msg() == *other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parse_commit_message;
+
+    #[test]
+    fn round_trip_owned_message() {
+        let input = "feat(parser,errors)!: add owned messages
+
+Parsed commits can now be stored.
+
+BREAKING CHANGE: the scopes are split
+Closes: #24"
+            .to_owned();
+        let message = parse_commit_message(&input).unwrap();
+        let owned = message.to_owned();
+
+        assert_eq!(owned.header.scopes, vec!["parser", "errors"]);
+        assert_eq!(owned.footers.len(), 2);
+        assert!(owned.is_breaking());
+        assert_eq!(message, owned);
+        assert_eq!(owned, message);
+        assert_eq!(owned.as_msg(), message);
+        assert_eq!(CommitMsgBuf::from(owned.as_msg()), owned);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_owned_message() {
+        let owned: CommitMsgBuf = ::serde_json::from_str(
+            r##"{"header":{"commit_type":"deps","scope":null,"scopes":[],"breaking":false,"subject":"update serde"},"body":null,"footers":[{"token":"Closes","value":"#25"}]}"##,
+        )
+        .unwrap();
+        assert_eq!(
+            owned.header.commit_type,
+            CommitType::Custom("deps".to_owned())
+        );
+        assert_eq!(
+            ::serde_json::to_string(&owned).unwrap(),
+            ::serde_json::to_string(&owned.as_msg()).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_owned_messages() {
+        let input = "feat(parser): parse owned messages\n\nCloses: #30";
+        let message: CommitMsgBuf = input.parse().unwrap();
+        assert_eq!(message, parse_commit_message(input).unwrap());
+        assert_eq!(CommitMsg::try_from(input).unwrap(), message);
+        assert!("Parse owned messages".parse::<CommitMsgBuf>().is_err());
+
+        let header: CommitHeaderBuf = "feat(parser): parse owned messages".parse().unwrap();
+        assert_eq!(header, message.header);
+        let error = "feat: parse owned messages\nClose #30"
+            .parse::<CommitHeaderBuf>()
+            .unwrap_err();
+        assert_eq!(error.kind, ::FormatErrorKind::NonEmptySecondLine);
+        assert_eq!(error.line_number(), Some(2));
+        assert!("feat: parse owned messages\n"
+            .parse::<CommitHeaderBuf>()
+            .is_ok());
+    }
+
+    #[test]
+    fn store_owned_messages() {
+        let inputs = [
+            "feat: add owned messages",
+            "fix(parser): fix owned messages",
+        ];
+        let messages: Vec<CommitMsgBuf> = inputs
+            .iter()
+            .map(|input| input.to_string())
+            .map(|input| parse_commit_message(&input).unwrap().into())
+            .collect();
+
+        assert_eq!(messages[0].header.commit_type, CommitType::Feat);
+        assert_eq!(messages[1].header.scope, Some("parser".to_owned()));
+
+        let mut other = messages[1].clone();
+        other.header.subject = "fix other messages".to_owned();
+        assert_ne!(messages[1], other.as_msg());
+    }
+}
@@ -1,153 +1,1730 @@
 use errors::{FormatError, FormatErrorKind};
-use {CommitHeader, CommitMsg, CommitType};
+#[cfg(feature = "regex")]
+use options::Pattern;
+use options::{ScopePattern, ScopePolicy, ValidationOptions};
+use {CommitHeader, CommitHeaderSpans, CommitMsg, CommitType, Footer};
 
-pub fn parse_commit_message<'a>(lines: &[&'a str]) -> Result<CommitMsg<'a>, FormatError> {
-    if lines.get(1).map_or(false, |l| !l.is_empty()) {
-        return Err(FormatErrorKind::NonEmptySecondLine.into());
+/// Token of the breaking change footer, the only one allowed to contain a space
+const BREAKING_CHANGE_TOKEN: &str = "BREAKING CHANGE";
+
+/// Line separating the message from the text to ignore, with `commit.cleanup=scissors`, after
+/// the comment character
+const SCISSORS_LINE: &str = " ------------------------ >8 ------------------------";
+
+/// Prefixes added to the headers by `git commit --fixup`, `--squash` and `--fixup=amend:`
+const AUTOSQUASH_PREFIXES: [&str; 3] = ["fixup! ", "squash! ", "amend! "];
+
+/// Start of the headers generated by `git revert`, followed by the quoted reverted header
+const REVERT_PREFIX: &str = "Revert \"";
+
+/// What is merged by the merge commits generated by git, followed by a quoted name
+const GIT_MERGE_SOURCES: [&str; 5] = [
+    "branch ",
+    "branches ",
+    "commit ",
+    "remote-tracking branch ",
+    "tag ",
+];
+
+/// Byte order mark written at the start of the messages by some Windows editors
+pub const BYTE_ORDER_MARK: char = '\u{feff}';
+
+/// A line of a commit message which is not a comment
+#[derive(Debug, Clone, Copy)]
+pub struct Line<'a> {
+    /// Line number in the message, starting from 1
+    pub number: usize,
+    /// Byte offset of the line in the message
+    pub pos: usize,
+    /// Content of the line, without the line ending
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    pub fn error(&self, kind: FormatErrorKind, pos: usize) -> FormatError {
+        kind.at(self.text, self.number, pos)
     }
 
-    Ok(CommitMsg {
-        header: parse_commit_header(lines[0])?,
+    pub fn error_spanning(&self, kind: FormatErrorKind, pos: usize, end: usize) -> FormatError {
+        kind.spanning(self.text, self.number, pos, end)
+    }
+
+    pub fn is_blank(&self) -> bool {
+        self.text.trim().is_empty()
+    }
+
+    fn end(&self) -> usize {
+        self.pos + self.text.len()
+    }
+}
+
+#[cfg(test)]
+pub fn parse_commit_message(input: &str) -> Result<CommitMsg<'_>, FormatError> {
+    parse_commit_message_with(input, &ValidationOptions::default())
+}
+
+#[cfg(test)]
+pub fn parse_commit_message_with<'a>(
+    input: &'a str,
+    options: &ValidationOptions,
+) -> Result<CommitMsg<'a>, FormatError> {
+    let mut errors = Vec::new();
+    let lines = message_lines(input, options.comment_char);
+    let message = parse_commit_message_collect(input, lines, options, &mut errors);
+    match message {
+        Some(message) if errors.is_empty() => Ok(message),
+        _ => Err(errors.remove(0)),
+    }
+}
+
+/// Parse a commit message from its lines, collecting the errors instead of stopping at the first
+/// one.
+///
+/// The lines are read once, in a single pass, so that they can be checked while they are parsed.
+/// Return the message if its header could be parsed, even if errors were found in the other
+/// lines.
+pub fn parse_commit_message_collect<'a, I>(
+    input: &'a str,
+    lines: I,
+    options: &ValidationOptions,
+    errors: &mut Vec<FormatError>,
+) -> Option<CommitMsg<'a>>
+where
+    I: Iterator<Item = Line<'a>>,
+{
+    let mut lines = lines.peekable();
+
+    // The header is still parsed, so that the message is not validated from the wrong line
+    let first_blank_line = lines.peek().filter(|line| line.is_blank()).copied();
+    while lines.next_if(Line::is_blank).is_some() {}
+    let header = match lines.next() {
+        Some(header) => header,
+        None => {
+            errors.push(FormatErrorKind::EmptyCommitMessage.into());
+            return None;
+        }
+    };
+    if let Some(line) = first_blank_line {
+        errors.push(line.error(FormatErrorKind::LeadingBlankLine, 0));
+    }
+
+    // git appends the new message below the header of the `squash!` and `amend!` commits
+    let (prefixes, _) = split_autosquash(header.text);
+    let appends_message = prefixes.contains("squash! ") || prefixes.contains("amend! ");
+    let header = parse_commit_header(header, options, errors)
+        .map_err(|e| errors.push(e))
+        .ok();
+
+    if let Some(line) = lines.next().filter(|line| !line.text.is_empty()) {
+        if !appends_message {
+            errors.push(line.error(FormatErrorKind::NonEmptySecondLine, 0));
+        }
+    }
+
+    // Only the last paragraph is kept, as it may be the footers
+    let mut body_start = None;
+    let mut body_end = None;
+    let mut paragraph: Vec<Line> = Vec::new();
+    let mut after_blank_line = false;
+    for line in lines {
+        if line.is_blank() {
+            after_blank_line = true;
+            continue;
+        }
+        if after_blank_line && !paragraph.is_empty() {
+            body_end = paragraph.last().map(Line::end);
+            paragraph.clear();
+        }
+        after_blank_line = false;
+        body_start.get_or_insert(line.pos);
+        paragraph.push(line);
+    }
+
+    let footers = parse_commit_footers(input, &paragraph, errors);
+    if footers.is_none() {
+        body_end = paragraph.last().map(Line::end);
+    }
+
+    Some(CommitMsg {
+        header: header?,
+        body: body_start
+            .zip(body_end)
+            .map(|(start, end)| &input[start..end]),
+        footers: footers.unwrap_or_default(),
     })
 }
 
-fn parse_commit_header(line: &str) -> Result<CommitHeader, FormatError> {
-    let line = discard_autosquash(line);
+/// Iterate over the lines of a message which are not comments.
+///
+/// A leading byte order mark and the carriage returns of CRLF line endings are not part of the
+/// lines.
+///
+/// The lines after the scissors line, like the diff added by `git commit --verbose`, are not part
+/// of the message.
+pub fn message_lines(input: &str, comment_char: char) -> impl Iterator<Item = Line<'_>> {
+    input
+        .strip_prefix(BYTE_ORDER_MARK)
+        .unwrap_or(input)
+        .lines()
+        .map(|l| l.strip_suffix('\r').unwrap_or(l))
+        .enumerate()
+        .take_while(move |(_, l)| l.strip_prefix(comment_char) != Some(SCISSORS_LINE))
+        .filter(move |(_, l)| !l.starts_with(comment_char))
+        .map(move |(index, l)| Line {
+            number: index + 1,
+            pos: l.as_ptr() as usize - input.as_ptr() as usize,
+            text: l,
+        })
+}
+
+/// Return the line of the message containing the given byte offset.
+pub fn line_at(input: &str, pos: usize) -> Line<'_> {
+    let start = match input[..pos].rfind('\n') {
+        Some(newline) => newline + 1,
+        None if input.starts_with(BYTE_ORDER_MARK) => BYTE_ORDER_MARK.len_utf8(),
+        None => 0,
+    };
+    let end = input[pos..]
+        .find('\n')
+        .map_or(input.len(), |newline| pos + newline);
+    let text = &input[start..end];
+    Line {
+        number: input[..start].matches('\n').count() + 1,
+        pos: start,
+        text: text.strip_suffix('\r').unwrap_or(text),
+    }
+}
+
+/// Parse the given paragraph as a block of footers, following the git trailer convention.
+///
+/// Each footer is made of a token, a `: ` or ` #` separator, and a value which continues on the
+/// following lines as long as they are indented. Return `None` if the paragraph contains a line
+/// which is neither a footer nor a continuation.
+///
+/// Footers with an empty value are reported in `errors`.
+fn parse_commit_footers<'a>(
+    input: &'a str,
+    lines: &[Line<'a>],
+    errors: &mut Vec<FormatError>,
+) -> Option<Vec<Footer<'a>>> {
+    let mut footers: Vec<(&Line, &str, usize, usize)> = Vec::new();
+    for line in lines {
+        if let Some((token, value_pos)) = parse_footer_token(line.text) {
+            footers.push((line, token, value_pos, line.end()));
+        } else if line.text.starts_with(char::is_whitespace) && !footers.is_empty() {
+            footers.last_mut().unwrap().3 = line.end();
+        } else {
+            return None;
+        }
+    }
+
+    if footers.is_empty() {
+        return None;
+    }
+
+    let footers = footers
+        .into_iter()
+        .map(|(line, token, value_pos, end)| {
+            let value = input[line.pos + value_pos..end].trim_start();
+            if value.is_empty() {
+                errors.push(line.error(FormatErrorKind::EmptyFooterValue, value_pos));
+            }
+
+            Footer { token, value }
+        })
+        .collect();
+    Some(footers)
+}
+
+/// Return the token of a footer line, and the position of its value.
+///
+/// With the ` #` separator, the `#` is kept in the value. A token followed by a lone `:` gives an
+/// empty value.
+fn parse_footer_token(line: &str) -> Option<(&str, usize)> {
+    let token_len = if line.starts_with(BREAKING_CHANGE_TOKEN) {
+        BREAKING_CHANGE_TOKEN.len()
+    } else {
+        line.find(|c: char| !c.is_alphanumeric() && c != '-')
+            .unwrap_or(line.len())
+    };
+
+    if token_len == 0 {
+        return None;
+    }
 
-    let column_pos = line.find(':').ok_or(FormatErrorKind::NoColumn)?;
-    let (commit_type, scope) = parse_commit_type_and_scope(&line[0..column_pos])?;
-    let commit_type: CommitType = commit_type.parse().map_err(|e: FormatError| e.at(line, 0))?;
+    let separator = &line[token_len..];
+    if separator.starts_with(": ") {
+        Some((&line[..token_len], token_len + 2))
+    } else if separator.trim_end() == ":" {
+        Some((&line[..token_len], line.len()))
+    } else if separator.starts_with(" #") {
+        Some((&line[..token_len], token_len + 1))
+    } else {
+        None
+    }
+}
+
+/// Parse the header, reporting errors at their position in the whole line.
+///
+/// The aliases of the commit types are reported in `errors`, as they do not prevent the parsing.
+fn parse_commit_header<'a>(
+    line: Line<'a>,
+    options: &ValidationOptions,
+    errors: &mut Vec<FormatError>,
+) -> Result<CommitHeader<'a>, FormatError> {
+    let (ticket, emoji, header) = split_header_prefixes(line.text, options);
+    let offset = line.text.len() - header.len();
+    #[cfg(feature = "regex")]
+    let parsed = match options.header_pattern {
+        Some(ref pattern) => parse_header_with_pattern(header, pattern, options),
+        None => parse_header(header, options),
+    };
+    #[cfg(not(feature = "regex"))]
+    let parsed = parse_header(header, options);
+    if parsed.is_ok() && !options.aliases.is_empty() {
+        if let Some((pos, end)) = commit_type_span(header, options) {
+            let commit_type = &header[pos..end];
+            if let Some(canonical) = options.aliases.get(commit_type) {
+                let kind = FormatErrorKind::NonCanonicalType {
+                    commit_type: commit_type.to_owned(),
+                    canonical: canonical.to_string(),
+                };
+                errors.push(line.error_spanning(kind, offset + pos, offset + end));
+            }
+        }
+    }
+    // The whitespaces ending the subject are trimmed from it, but do not hide the rest of the header
+    let trimmed_len = line.text.trim_end().len();
+    if parsed.is_ok() && trimmed_len < line.text.len() {
+        errors.push(line.error_spanning(
+            FormatErrorKind::MisplacedWhitespace,
+            trimmed_len,
+            line.text.len(),
+        ));
+    }
+    parsed
+        .map(|parsed| CommitHeader {
+            ticket,
+            emoji,
+            ..parsed
+        })
+        .map_err(|e| match split_emoji(header) {
+            Some((emoji, _)) => HeaderError {
+                kind: FormatErrorKind::EmojiPrefix {
+                    emoji: emoji.to_owned(),
+                },
+                pos: 0,
+                end: Some(emoji.len()),
+            },
+            None => e,
+        })
+        .map_err(|e| match e.end {
+            Some(end) => line.error_spanning(e.kind, offset + e.pos, offset + end),
+            None => line.error(e.kind, offset + e.pos),
+        })
+}
+
+/// Split the prefixes of the header line: the autosquash prefixes, which are discarded, then the
+/// ticket tag and the emoji, if they are allowed.
+fn split_header_prefixes<'a>(
+    line: &'a str,
+    options: &ValidationOptions,
+) -> (Option<&'a str>, Option<&'a str>, &'a str) {
+    let (_, header) = split_autosquash(line);
+    let (ticket, header) = split_ticket(header, options);
+    match split_emoji(header) {
+        Some((emoji, rest)) if options.allow_emoji_prefix => (ticket, Some(emoji), rest),
+        _ => (ticket, None, header),
+    }
+}
+
+/// Locate the parts of the header parsed from the given line.
+///
+/// The parts of the header must be borrowed from the line.
+pub fn header_spans(
+    line: &str,
+    header: &CommitHeader,
+    options: &ValidationOptions,
+) -> CommitHeaderSpans {
+    let range = |part: &str| {
+        let start = part.as_ptr() as usize - line.as_ptr() as usize;
+        start..start + part.len()
+    };
+    let (_, _, stripped) = split_header_prefixes(line, options);
+    let offset = line.len() - stripped.len();
+    let commit_type = match commit_type_span(stripped, options) {
+        Some((pos, end)) => offset + pos..offset + end,
+        None => offset..offset,
+    };
+    let scope = header.scope.map(range);
+    let parentheses = scope
+        .as_ref()
+        .filter(|scope| line[..scope.start].ends_with('(') && line[scope.end..].starts_with(')'))
+        .map(|scope| scope.start - 1..scope.end + 1);
+    // The marker is right before the colon
+    let breaking = match stripped.find(':') {
+        Some(column_pos) if header.breaking => Some(offset + column_pos - 1..offset + column_pos),
+        _ => None,
+    };
+    CommitHeaderSpans {
+        ticket: header.ticket.map(range),
+        emoji: header.emoji.map(range),
+        commit_type,
+        scope,
+        parentheses,
+        breaking,
+        subject: range(header.subject),
+    }
+}
+
+/// Split the ticket tag allowed before the header, like `[PROJ-42] `.
+#[cfg(feature = "regex")]
+fn split_ticket<'a>(header: &'a str, options: &ValidationOptions) -> (Option<&'a str>, &'a str) {
+    let ticket = options
+        .allow_ticket_prefix
+        .as_ref()
+        .and_then(|pattern| pattern.regex().find(header))
+        .filter(|ticket| ticket.start() == 0 && !ticket.as_str().trim().is_empty());
+    match ticket {
+        Some(ticket) => (
+            Some(ticket.as_str().trim_end()),
+            header[ticket.end()..].trim_start_matches(' '),
+        ),
+        None => (None, header),
+    }
+}
+
+/// Split the ticket tag allowed before the header, which needs a pattern.
+#[cfg(not(feature = "regex"))]
+fn split_ticket<'a>(header: &'a str, _options: &ValidationOptions) -> (Option<&'a str>, &'a str) {
+    (None, header)
+}
+
+/// Byte offsets of the commit type in the header, if it can be found.
+#[cfg_attr(not(feature = "regex"), allow(unused_variables))]
+fn commit_type_span(header: &str, options: &ValidationOptions) -> Option<(usize, usize)> {
+    #[cfg(feature = "regex")]
+    if let Some(ref pattern) = options.header_pattern {
+        return pattern
+            .regex()
+            .captures(header)?
+            .name("type")
+            .map(|commit_type| (commit_type.start(), commit_type.end()));
+    }
+    header.find(['(', '!', ':']).map(|end| (0, end))
+}
+
+/// Split a single emoji followed by a space, like `✨ ` or `:sparkles: `, from the rest of the
+/// header.
+fn split_emoji(header: &str) -> Option<(&str, &str)> {
+    let len = if let Some(name) = header.strip_prefix(':') {
+        let name_len = name
+            .find(|c: char| !(c.is_ascii_lowercase() || c.is_ascii_digit() || "_+-".contains(c)))
+            .unwrap_or(name.len());
+        if name_len == 0 || !name[name_len..].starts_with(':') {
+            return None;
+        }
+        name_len + 2
+    } else {
+        unicode_emoji_len(header)?
+    };
+    let rest = header[len..].strip_prefix(' ')?;
+    Some((&header[..len], rest))
+}
+
+/// Length of the unicode emoji at the start of the text, with its modifiers and joined emojis.
+fn unicode_emoji_len(text: &str) -> Option<usize> {
+    let mut chars = text.char_indices().peekable();
+    let (_, first) = chars.next().filter(|&(_, c)| is_emoji(c))?;
+    let mut len = first.len_utf8();
+    if is_regional_indicator(first) {
+        if let Some(&(_, c)) = chars.peek().filter(|&&(_, c)| is_regional_indicator(c)) {
+            chars.next();
+            len += c.len_utf8();
+        }
+    }
+    while let Some((pos, c)) = chars.next() {
+        match c {
+            '\u{fe0f}' | '\u{20e3}' | '\u{1f3fb}'..='\u{1f3ff}' => len = pos + c.len_utf8(),
+            '\u{200d}' => match chars.next() {
+                Some((pos, c)) if is_emoji(c) => len = pos + c.len_utf8(),
+                _ => break,
+            },
+            _ => break,
+        }
+    }
+    Some(len)
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(
+        c,
+        '\u{2190}'..='\u{21ff}'
+            | '\u{2300}'..='\u{23ff}'
+            | '\u{2600}'..='\u{27bf}'
+            | '\u{2b00}'..='\u{2bff}'
+            | '\u{1f000}'..='\u{1faff}'
+    )
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1f1e6}'..='\u{1f1ff}').contains(&c)
+}
+
+/// Error found in a header, located by byte offsets in the header
+struct HeaderError {
+    kind: FormatErrorKind,
+    pos: usize,
+    /// End of the offending text, if it is longer than one character
+    end: Option<usize>,
+}
+
+impl From<(FormatErrorKind, usize)> for HeaderError {
+    fn from((kind, pos): (FormatErrorKind, usize)) -> Self {
+        HeaderError {
+            kind,
+            pos,
+            end: None,
+        }
+    }
+}
+
+/// Parse the header without its autosquash prefix.
+fn parse_header<'a>(
+    line: &'a str,
+    options: &ValidationOptions,
+) -> Result<CommitHeader<'a>, HeaderError> {
+    let column_pos = line.find(':').ok_or((FormatErrorKind::NoColumn, 0))?;
+    let (commit_type, scope, breaking) = parse_commit_type_and_scope(&line[0..column_pos])?;
+    let commit_type = parse_commit_type(commit_type, options).map_err(|kind| HeaderError {
+        kind,
+        pos: 0,
+        end: Some(commit_type.len()),
+    })?;
+
+    let scopes = match scope {
+        Some(scope) => {
+            let scope_pos = line.find('(').unwrap() + 1;
+            if options.scope_policy == ScopePolicy::Forbidden {
+                return Err(HeaderError {
+                    kind: FormatErrorKind::ScopeNotAllowed,
+                    pos: scope_pos - 1,
+                    end: Some(scope_pos + scope.len() + 1),
+                });
+            }
+            parse_scopes(scope, scope_pos, options)?
+        }
+        None if options.scope_policy == ScopePolicy::Required => {
+            return Err((FormatErrorKind::MissingScope, column_pos).into());
+        }
+        None => Vec::new(),
+    };
 
     if line.get(column_pos + 1..column_pos + 2) != Some(" ") {
-        return Err(FormatErrorKind::MissingWhitespace.at(line, column_pos + 1));
+        return Err((FormatErrorKind::MissingWhitespace, column_pos + 1).into());
     }
 
     let subject_pos = column_pos + 2;
     let subject = &line[subject_pos..];
     if subject.is_empty() {
-        return Err(FormatErrorKind::EmptyCommitSubject.into());
+        return Err((FormatErrorKind::EmptyCommitSubject, subject_pos).into());
     }
 
-    if !is_left_trimmed(subject) {
-        return Err(FormatErrorKind::MisplacedWhitespace.at(line, subject_pos + 1));
-    }
+    let subject = check_subject_whitespace(subject, subject_pos)?;
 
-    if !is_right_trimmed(subject) {
-        return Err(FormatErrorKind::MisplacedWhitespace.at(line, line.len()));
+    Ok(CommitHeader {
+        commit_type,
+        scope,
+        scopes,
+        breaking,
+        subject,
+        ticket: None,
+        emoji: None,
+    })
+}
+
+/// Parse the header with the `type`, `scope` and `subject` groups of a custom pattern.
+///
+/// Without `type` group, the commit type is an empty custom type. Without `subject` group, the
+/// subject is the whole header.
+#[cfg(feature = "regex")]
+fn parse_header_with_pattern<'a>(
+    line: &'a str,
+    pattern: &Pattern,
+    options: &ValidationOptions,
+) -> Result<CommitHeader<'a>, HeaderError> {
+    let captures = pattern.regex().captures(line).ok_or_else(|| HeaderError {
+        kind: FormatErrorKind::HeaderPatternMismatch {
+            pattern: pattern.to_string(),
+        },
+        pos: 0,
+        end: Some(line.len()),
+    })?;
+
+    let commit_type = match captures.name("type") {
+        Some(commit_type) if commit_type.as_str().is_empty() => {
+            return Err((FormatErrorKind::EmptyCommitType, commit_type.start()).into());
+        }
+        Some(commit_type) => {
+            parse_commit_type(commit_type.as_str(), options).map_err(|kind| HeaderError {
+                kind,
+                pos: commit_type.start(),
+                end: Some(commit_type.end()),
+            })?
+        }
+        None => CommitType::Custom(String::new()),
+    };
+
+    let has_scope_group = pattern.regex().capture_names().any(|n| n == Some("scope"));
+    let scope = captures.name("scope");
+    let scopes = match scope {
+        Some(scope) if options.scope_policy == ScopePolicy::Forbidden => {
+            return Err(HeaderError {
+                kind: FormatErrorKind::ScopeNotAllowed,
+                pos: scope.start(),
+                end: Some(scope.end()),
+            });
+        }
+        Some(scope) => parse_scopes(scope.as_str(), scope.start(), options)?,
+        None if has_scope_group && options.scope_policy == ScopePolicy::Required => {
+            return Err((FormatErrorKind::MissingScope, 0).into());
+        }
+        None => Vec::new(),
+    };
+
+    let (subject, subject_pos) = captures
+        .name("subject")
+        .map_or((line, 0), |subject| (subject.as_str(), subject.start()));
+    if subject.is_empty() {
+        return Err((FormatErrorKind::EmptyCommitSubject, subject_pos).into());
     }
+    let subject = check_subject_whitespace(subject, subject_pos)?;
 
     Ok(CommitHeader {
         commit_type,
-        scope,
+        scope: scope.map(|scope| scope.as_str()),
+        scopes,
+        breaking: false,
         subject,
+        ticket: None,
+        emoji: None,
     })
 }
 
-/// Return the string whitout `squash! ` or `fixup! `
-fn discard_autosquash(line: &str) -> &str {
-    if line.starts_with("fixup! ") {
-        &line[7..]
-    } else if line.starts_with("squash! ") {
-        &line[8..]
-    } else {
-        line
+fn check_subject_whitespace(subject: &str, subject_pos: usize) -> Result<&str, HeaderError> {
+    if !is_left_trimmed(subject) {
+        return Err(HeaderError {
+            kind: FormatErrorKind::MisplacedWhitespace,
+            pos: subject_pos,
+            end: Some(subject_pos + subject.len() - subject.trim_start().len()),
+        });
+    }
+
+    Ok(subject.trim_end())
+}
+
+/// Parse the commit type, allowing the types configured in the options.
+///
+/// When the type is rejected, the error suggests the closest allowed type.
+fn parse_commit_type(
+    commit_type: &str,
+    options: &ValidationOptions,
+) -> Result<CommitType, FormatErrorKind> {
+    let allowed_types: Vec<&str> = match options.allowed_types {
+        None => CommitType::ALL.iter().map(CommitType::as_str).collect(),
+        Some(ref allowed_types) => allowed_types.iter().map(String::as_str).collect(),
+    };
+
+    if allowed_types.contains(&commit_type) {
+        return Ok(to_commit_type(commit_type));
+    }
+
+    if let Some(canonical) = options.aliases.get(commit_type) {
+        return Ok(canonical.clone());
+    }
+
+    let lowercase = commit_type.to_lowercase();
+    if allowed_types.contains(&lowercase.as_str()) {
+        if options.allow_uppercase_types {
+            return Ok(to_commit_type(&lowercase));
+        }
+        return Err(FormatErrorKind::CommitTypeNotLowercase {
+            commit_type: commit_type.to_owned(),
+            expected: lowercase,
+        });
     }
+
+    Err(FormatErrorKind::InvalidCommitType {
+        commit_type: commit_type.to_owned(),
+        suggestion: suggest_commit_type(commit_type, allowed_types.into_iter()),
+    })
+}
+
+/// Split the comma-separated scopes, checking each of them.
+fn parse_scopes<'a>(
+    scope: &'a str,
+    scope_pos: usize,
+    options: &ValidationOptions,
+) -> Result<Vec<&'a str>, HeaderError> {
+    let scope_end = scope_pos + scope.len();
+    let mut scopes = Vec::new();
+    let mut element_pos = scope_pos;
+    for element in scope.split(',') {
+        if element.is_empty() {
+            // Point at the comma following the empty scope, or preceding it if it is the last one
+            let comma_pos = if element_pos < scope_end {
+                element_pos
+            } else {
+                element_pos - 1
+            };
+            return Err((FormatErrorKind::EmptyScope, comma_pos).into());
+        }
+
+        if !is_left_trimmed(element) {
+            return Err((FormatErrorKind::MisplacedWhitespace, element_pos).into());
+        }
+
+        if !is_right_trimmed(element) {
+            let trailing_whitespace_pos = element_pos + element.trim_end().len();
+            return Err((
+                FormatErrorKind::MisplacedWhitespace,
+                trailing_whitespace_pos,
+            )
+                .into());
+        }
+
+        check_scope(element, element_pos, options)?;
+        scopes.push(element);
+        element_pos += element.len() + 1;
+    }
+
+    Ok(scopes)
+}
+
+/// Check the scope against the scopes allowed in the options.
+fn check_scope(
+    scope: &str,
+    scope_pos: usize,
+    options: &ValidationOptions,
+) -> Result<(), HeaderError> {
+    if let Some(pos) = find_scope_format_error(scope, options.scope_pattern) {
+        return Err((FormatErrorKind::InvalidScopeFormat, scope_pos + pos).into());
+    }
+
+    match options.allowed_scopes {
+        Some(ref allowed_scopes)
+            if !allowed_scopes
+                .iter()
+                .any(|pattern| glob_matches(pattern, scope)) =>
+        {
+            Err(HeaderError {
+                kind: FormatErrorKind::UnknownScope {
+                    scope: scope.to_owned(),
+                },
+                pos: scope_pos,
+                end: Some(scope_pos + scope.len()),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Return the position of the first character of the scope not matching the pattern, if any.
+fn find_scope_format_error(scope: &str, pattern: ScopePattern) -> Option<usize> {
+    match pattern {
+        ScopePattern::Any => None,
+        ScopePattern::Lowercase => scope
+            .char_indices()
+            .find(|&(_, c)| c.is_uppercase())
+            .map(|(pos, _)| pos),
+        ScopePattern::KebabCase => {
+            let is_separator = |c| c == '-' || c == '/';
+            let mut previous = None;
+            for (pos, c) in scope.char_indices() {
+                let valid = if is_separator(c) {
+                    previous.is_some_and(|p| !is_separator(p))
+                } else {
+                    c.is_ascii_lowercase() || c.is_ascii_digit()
+                };
+                if !valid {
+                    return Some(pos);
+                }
+                previous = Some(c);
+            }
+            match scope.char_indices().last() {
+                Some((pos, c)) if is_separator(c) => Some(pos),
+                _ => None,
+            }
+        }
+    }
+}
+
+/// Whether the text matches the glob pattern.
+///
+/// `*` matches any sequence of characters except `/`, and `?` matches any single character
+/// except `/`.
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let mut pattern_chars = pattern.chars();
+    match pattern_chars.next() {
+        None => text.is_empty(),
+        Some('*') => {
+            let rest = pattern_chars.as_str();
+            let mut text_chars = text.chars();
+            loop {
+                if glob_matches(rest, text_chars.as_str()) {
+                    return true;
+                }
+                match text_chars.next() {
+                    Some(c) if c != '/' => {}
+                    _ => return false,
+                }
+            }
+        }
+        Some(p) => {
+            let mut text_chars = text.chars();
+            match text_chars.next() {
+                Some(c) if c == p || (p == '?' && c != '/') => {
+                    glob_matches(pattern_chars.as_str(), text_chars.as_str())
+                }
+                _ => false,
+            }
+        }
+    }
+}
+
+/// Convert an allowed commit type, falling back to a custom one if it is not built-in.
+fn to_commit_type(commit_type: &str) -> CommitType {
+    commit_type
+        .parse()
+        .unwrap_or_else(|_| CommitType::Custom(commit_type.to_owned()))
+}
+
+/// Maximum edit distance between a rejected commit type and a suggested one
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// Return the allowed type closest to the rejected one, if it is close enough.
+///
+/// The comparison ignores the case, so that `FEAT` suggests `feat`.
+fn suggest_commit_type<'a, I>(commit_type: &str, allowed_types: I) -> Option<String>
+where
+    I: Iterator<Item = &'a str>,
+{
+    let commit_type = commit_type.to_lowercase();
+    allowed_types
+        .map(|allowed| (levenshtein(&commit_type, &allowed.to_lowercase()), allowed))
+        .filter(|&(distance, _)| distance <= MAX_SUGGESTION_DISTANCE)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, allowed)| allowed.to_owned())
+}
+
+/// Compute the Levenshtein distance between two strings, in characters.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut distances: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous_diagonal = distances[0];
+        distances[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous_diagonal + if ca == cb { 0 } else { 1 };
+            previous_diagonal = distances[j + 1];
+            distances[j + 1] = substitution
+                .min(distances[j] + 1)
+                .min(previous_diagonal + 1);
+        }
+    }
+
+    distances[b.len()]
+}
+
+/// Return the header reverted by a `Revert "<header>"` line generated by `git revert`, with its
+/// byte offset in the line.
+///
+/// The reverts of reverts, like `Revert "Revert "<header>""`, are unwrapped.
+pub fn reverted_header(line: &str) -> Option<(usize, &str)> {
+    let mut header = unquote_revert(line)?;
+    let mut offset = REVERT_PREFIX.len();
+    while let Some(reverted) = unquote_revert(header) {
+        header = reverted;
+        offset += REVERT_PREFIX.len();
+    }
+    Some((offset, header))
+}
+
+fn unquote_revert(header: &str) -> Option<&str> {
+    header.strip_prefix(REVERT_PREFIX)?.strip_suffix('"')
+}
+
+/// Whether the line is the header of a merge commit generated by git or GitHub, like
+/// `Merge branch 'develop'` or `Merge pull request #42 from user/branch`.
+pub fn is_git_merge(line: &str) -> bool {
+    let rest = match line.strip_prefix("Merge ") {
+        Some(rest) => rest,
+        None => return false,
+    };
+
+    if let Some(rest) = rest.strip_prefix("pull request #") {
+        let digits = rest.chars().take_while(char::is_ascii_digit).count();
+        return digits > 0 && rest[digits..].starts_with(" from ");
+    }
+
+    GIT_MERGE_SOURCES.iter().any(|source| {
+        rest.strip_prefix(source)
+            .and_then(|rest| rest.strip_prefix('\''))
+            .is_some_and(|name| name.contains('\''))
+    })
+}
+
+/// Split the `fixup! `, `squash! ` and `amend! ` prefixes from the rest of the header.
+///
+/// The prefixes can be stacked, like `fixup! fixup! feat: ...`.
+pub fn split_autosquash(line: &str) -> (&str, &str) {
+    let mut header = line;
+    while let Some(rest) = AUTOSQUASH_PREFIXES
+        .iter()
+        .find_map(|prefix| header.strip_prefix(prefix))
+    {
+        header = rest;
+    }
+    line.split_at(line.len() - header.len())
 }
 
 fn is_left_trimmed(s: &str) -> bool {
-    s == s.trim_left()
+    s == s.trim_start()
 }
 
 fn is_right_trimmed(s: &str) -> bool {
-    s == s.trim_right()
+    s == s.trim_end()
 }
 
+/// Return the commit type, the optional scope, and whether the breaking change marker `!` is
+/// present.
 fn parse_commit_type_and_scope(
-    commit_type_and_scope: &str,
-) -> Result<(&str, Option<&str>), FormatError> {
+    header_start: &str,
+) -> Result<(&str, Option<&str>, bool), (FormatErrorKind, usize)> {
+    let (commit_type_and_scope, breaking) = match header_start.strip_suffix('!') {
+        Some(commit_type_and_scope) => (commit_type_and_scope, true),
+        None => (header_start, false),
+    };
+
     if commit_type_and_scope.is_empty() {
-        return Err(FormatErrorKind::EmptyCommitType.into());
+        return Err((FormatErrorKind::EmptyCommitType, 0));
     }
 
     let first_char = commit_type_and_scope.chars().next().unwrap();
     if first_char.is_whitespace() {
-        return Err(FormatErrorKind::MisplacedWhitespace.at(commit_type_and_scope, 0));
+        return Err((FormatErrorKind::MisplacedWhitespace, 0));
     }
 
     let last_char = commit_type_and_scope.chars().last().unwrap();
     if last_char.is_whitespace() {
-        return Err(FormatErrorKind::MisplacedWhitespace
-            .at(commit_type_and_scope, commit_type_and_scope.len() - 1));
+        return Err((
+            FormatErrorKind::MisplacedWhitespace,
+            commit_type_and_scope.len() - last_char.len_utf8(),
+        ));
     }
 
     Ok(if last_char == ')' {
+        let closing_parenthesis = commit_type_and_scope.len() - 1;
         let opening_parenthesis: usize = commit_type_and_scope
             .find('(')
-            .ok_or_else(|| FormatError::from(FormatErrorKind::MissingParenthesis))?;
-        (
-            &commit_type_and_scope[..opening_parenthesis],
-            Some(&commit_type_and_scope[opening_parenthesis + 1..commit_type_and_scope.len() - 1]),
-        )
+            .ok_or((FormatErrorKind::MissingParenthesis, closing_parenthesis))?;
+        let commit_type = &commit_type_and_scope[..opening_parenthesis];
+        if commit_type.ends_with('!') {
+            return Err((
+                FormatErrorKind::MisplacedBreakingChangeMarker,
+                opening_parenthesis - 1,
+            ));
+        }
+
+        let scope = &commit_type_and_scope[opening_parenthesis + 1..closing_parenthesis];
+        if scope.trim().is_empty() {
+            return Err((FormatErrorKind::EmptyScope, opening_parenthesis));
+        }
+        if let Some(pos) = find_unbalanced_parenthesis(scope) {
+            return Err((
+                FormatErrorKind::MissingParenthesis,
+                opening_parenthesis + 1 + pos,
+            ));
+        }
+
+        (commit_type, Some(scope), breaking)
     } else {
-        (commit_type_and_scope, None)
+        (commit_type_and_scope, None, breaking)
     })
 }
 
+/// Return the position of the first parenthesis without a match in the text, if any.
+fn find_unbalanced_parenthesis(text: &str) -> Option<usize> {
+    let mut opened = Vec::new();
+    for (pos, c) in text.char_indices() {
+        match c {
+            '(' => opened.push(pos),
+            ')' if opened.pop().is_none() => return Some(pos),
+            _ => {}
+        }
+    }
+    opened.first().cloned()
+}
+
 #[cfg(test)]
 mod tests {
-    use super::parse_commit_message;
-    use CommitType;
+    use super::{
+        glob_matches, is_git_merge, levenshtein, line_at, parse_commit_message,
+        parse_commit_message_with, reverted_header, split_emoji,
+    };
     use errors::*;
+    use options::{ScopePattern, ScopePolicy, ValidationOptions};
+    use {CommitType, Footer};
 
     #[test]
     fn test_parse_header() {
-        assert!(parse_commit_message(&["refactor: add commit parsing"]).is_ok());
+        assert!(parse_commit_message("refactor: add commit parsing").is_ok());
 
-        let commit_msg = parse_commit_message(&["refactor(scope): add commit parsing"]);
+        let commit_msg = parse_commit_message("refactor(scope): add commit parsing");
         assert!(commit_msg.is_ok());
 
         let commit_msg = commit_msg.unwrap();
         assert_eq!(commit_msg.header.subject, "add commit parsing");
         assert_eq!(commit_msg.header.commit_type, CommitType::Refactor);
         assert_eq!(commit_msg.header.scope, Some("scope"));
+        assert_eq!(commit_msg.body, None);
+    }
+
+    #[test]
+    fn test_parse_angular_types() {
+        let commit_msg = parse_commit_message("build: update failure to 0.1.2").unwrap();
+        assert_eq!(commit_msg.header.commit_type, CommitType::Build);
+        let commit_msg = parse_commit_message("ci: add GitHub Actions workflow").unwrap();
+        assert_eq!(commit_msg.header.commit_type, CommitType::Ci);
+        let commit_msg = parse_commit_message("revert: add commit body parsing").unwrap();
+        assert_eq!(commit_msg.header.commit_type, CommitType::Revert);
+    }
+
+    #[test]
+    fn test_parse_custom_types() {
+        let options = ValidationOptions {
+            allowed_types: Some(vec!["fix".to_owned(), "deps".to_owned()]),
+            ..ValidationOptions::default()
+        };
+
+        let commit_msg = parse_commit_message_with("deps: update failure", &options).unwrap();
+        assert_eq!(
+            commit_msg.header.commit_type,
+            CommitType::Custom("deps".to_owned())
+        );
+        let commit_msg = parse_commit_message_with("fix: fix custom types", &options).unwrap();
+        assert_eq!(commit_msg.header.commit_type, CommitType::Fix);
+
+        let res = parse_commit_message_with("feat: add custom types", &options);
+        assert!(res.is_err());
+        let error = res.unwrap_err();
+        assert_eq!(
+            FormatErrorKind::InvalidCommitType {
+                commit_type: "feat".to_owned(),
+                suggestion: None,
+            },
+            error.kind
+        );
+        assert_eq!(
+            "Invalid commit type 'feat' [VC004 type-valid]\nline 1, column 1:\nfeat: add custom types\n^^^^",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn test_parse_body() {
+        let commit_msg = parse_commit_message(
+            "feat: add commit body parsing
+
+The body can contain several paragraphs.
+
+- They are kept
+- As they are
+",
+        )
+        .unwrap();
+        assert_eq!(
+            commit_msg.body,
+            Some("The body can contain several paragraphs.\n\n- They are kept\n- As they are")
+        );
+    }
+
+    #[test]
+    fn test_parse_body_with_comments() {
+        let commit_msg = parse_commit_message(
+            "feat: add commit body parsing
+# A comment between the header and the blank line
+
+Body of the commit
+
+# Please enter the commit message for your changes. Lines starting
+# with '#' will be ignored, and an empty message aborts the commit.
+",
+        )
+        .unwrap();
+        assert_eq!(commit_msg.body, Some("Body of the commit"));
+
+        let commit_msg = parse_commit_message(
+            "feat: add commit body parsing
+
+# On branch master
+",
+        )
+        .unwrap();
+        assert_eq!(commit_msg.body, None);
+    }
+
+    #[test]
+    fn test_parse_footers() {
+        let commit_msg = parse_commit_message(
+            "fix: handle empty messages
+
+Closes #42
+Signed-off-by: Hugo Laloge <hugo.laloge@gmail.com>
+",
+        )
+        .unwrap();
+        assert_eq!(commit_msg.body, None);
+        assert_eq!(
+            commit_msg.footers,
+            vec![
+                Footer {
+                    token: "Closes",
+                    value: "#42",
+                },
+                Footer {
+                    token: "Signed-off-by",
+                    value: "Hugo Laloge <hugo.laloge@gmail.com>",
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_body_and_footers() {
+        let commit_msg = parse_commit_message(
+            "feat: add footers parsing
+
+Footers are read from the last paragraph.
+Reviewed-by: is not a footer here.
+
+BREAKING CHANGE: the commit message now
+  contains footers,
+  which can span several lines.
+Refs: #12
+",
+        )
+        .unwrap();
+        assert_eq!(
+            commit_msg.body,
+            Some("Footers are read from the last paragraph.\nReviewed-by: is not a footer here.")
+        );
+        assert_eq!(
+            commit_msg.footers,
+            vec![
+                Footer {
+                    token: "BREAKING CHANGE",
+                    value: "the commit message now\n  contains footers,\n  which can span several lines.",
+                },
+                Footer {
+                    token: "Refs",
+                    value: "#12",
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_body_is_not_footers() {
+        let commit_msg = parse_commit_message(
+            "feat: add footers parsing
+
+Closes: #42
+This line is not a footer.
+",
+        )
+        .unwrap();
+        assert_eq!(
+            commit_msg.body,
+            Some("Closes: #42\nThis line is not a footer.")
+        );
+        assert!(commit_msg.footers.is_empty());
+    }
+
+    #[test]
+    fn test_breaking_change_marker() {
+        let commit_msg = parse_commit_message("feat!: drop support for Node 6").unwrap();
+        assert_eq!(commit_msg.header.commit_type, CommitType::Feat);
+        assert_eq!(commit_msg.header.scope, None);
+        assert!(commit_msg.header.breaking);
+
+        let commit_msg = parse_commit_message("feat(api)!: remove endpoint").unwrap();
+        assert_eq!(commit_msg.header.commit_type, CommitType::Feat);
+        assert_eq!(commit_msg.header.scope, Some("api"));
+        assert!(commit_msg.header.breaking);
+
+        let commit_msg = parse_commit_message("feat(api): add endpoint").unwrap();
+        assert!(!commit_msg.header.breaking);
+    }
+
+    #[test]
+    fn test_discard_misplaced_breaking_change_marker() {
+        let res = parse_commit_message("fe!at: add feature");
+        assert!(res.is_err());
+        assert!(matches!(
+            res.unwrap_err().kind,
+            FormatErrorKind::InvalidCommitType { .. }
+        ));
+
+        let res = parse_commit_message("feat!(api): remove endpoint");
+        assert!(res.is_err());
+        let error = res.unwrap_err();
+        assert_eq!(FormatErrorKind::MisplacedBreakingChangeMarker, error.kind);
+        assert!(error
+            .to_string()
+            .ends_with("feat!(api): remove endpoint\n    ^"));
+    }
+
+    #[test]
+    fn test_discard_empty_footer_value() {
+        let res = parse_commit_message(
+            "feat!: remove the old API
+
+BREAKING CHANGE:
+",
+        );
+        assert!(res.is_err());
+        let error = res.unwrap_err();
+        assert_eq!(FormatErrorKind::EmptyFooterValue, error.kind);
+        assert_eq!(
+            "Empty footer value [VC012 footer-value-not-empty]\nline 3, column 17:\nBREAKING CHANGE:\n                ^",
+            error.to_string()
+        );
+
+        let res = parse_commit_message(
+            "feat!: remove the old API
+
+Refs: #12
+BREAKING-CHANGE: 
+",
+        );
+        assert!(res.is_err());
+        assert_eq!(FormatErrorKind::EmptyFooterValue, res.unwrap_err().kind);
+    }
+
+    #[test]
+    fn test_discard_empty_message() {
+        for message in &[
+            "",
+            "\n\n",
+            "  \n\t\n",
+            "# Only a comment\n# And another one\n",
+        ] {
+            let res = parse_commit_message(message);
+            assert!(res.is_err());
+            assert_eq!(FormatErrorKind::EmptyCommitMessage, res.unwrap_err().kind);
+        }
     }
 
     #[test]
     fn test_discard_invalid_commit_type() {
-        let res = parse_commit_message(&["feet: add feeture"]);
+        let res = parse_commit_message("feet: add feeture");
         assert!(res.is_err());
-        assert_eq!(FormatErrorKind::InvalidCommitType, res.unwrap_err().kind);
+        assert!(matches!(
+            res.unwrap_err().kind,
+            FormatErrorKind::InvalidCommitType { .. }
+        ));
+    }
+
+    #[test]
+    fn test_suggest_close_commit_type() {
+        let error = parse_commit_message("feta: add suggestions").unwrap_err();
+        assert_eq!(
+            FormatErrorKind::InvalidCommitType {
+                commit_type: "feta".to_owned(),
+                suggestion: Some("feat".to_owned()),
+            },
+            error.kind
+        );
+        assert!(error
+            .to_string()
+            .starts_with("Invalid commit type 'feta', did you mean 'feat'? [VC004 type-valid]\n"));
+
+        let error = parse_commit_message("fxi: suggest fix").unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("Invalid commit type 'fxi', did you mean 'fix'? [VC004 type-valid]\n"));
+    }
+
+    #[test]
+    fn test_suggest_commit_type_ignoring_case() {
+        let error = parse_commit_message("FETA: add suggestions").unwrap_err();
+        assert_eq!(
+            FormatErrorKind::InvalidCommitType {
+                commit_type: "FETA".to_owned(),
+                suggestion: Some("feat".to_owned()),
+            },
+            error.kind
+        );
+    }
+
+    #[test]
+    fn test_discard_capitalized_commit_type() {
+        let error = parse_commit_message("Feat: add lowercase check").unwrap_err();
+        assert_eq!(
+            FormatErrorKind::CommitTypeNotLowercase {
+                commit_type: "Feat".to_owned(),
+                expected: "feat".to_owned(),
+            },
+            error.kind
+        );
+        assert_eq!(
+            "Commit type 'Feat' must be lowercase, use 'feat' instead [VC014 type-case]\nline 1, column 1:\nFeat: add lowercase check\n^^^^",
+            error.to_string()
+        );
+
+        let error = parse_commit_message("FIX: fix lowercase check").unwrap_err();
+        assert_eq!(
+            FormatErrorKind::CommitTypeNotLowercase {
+                commit_type: "FIX".to_owned(),
+                expected: "fix".to_owned(),
+            },
+            error.kind
+        );
+
+        let error = parse_commit_message("Whatever: add lowercase check").unwrap_err();
+        assert!(matches!(
+            error.kind,
+            FormatErrorKind::InvalidCommitType { .. }
+        ));
+    }
+
+    #[test]
+    fn test_allow_uppercase_commit_types() {
+        let options = ValidationOptions {
+            allowed_types: Some(vec!["feat".to_owned(), "deps".to_owned()]),
+            allow_uppercase_types: true,
+            ..ValidationOptions::default()
+        };
+
+        let commit_msg = parse_commit_message_with("Feat: add lowercase check", &options).unwrap();
+        assert_eq!(commit_msg.header.commit_type, CommitType::Feat);
+        let commit_msg = parse_commit_message_with("DEPS: update failure", &options).unwrap();
+        assert_eq!(
+            commit_msg.header.commit_type,
+            CommitType::Custom("deps".to_owned())
+        );
+        assert!(parse_commit_message_with("Fix: fix lowercase check", &options).is_err());
+    }
+
+    #[test]
+    fn test_no_suggestion_for_unrelated_commit_type() {
+        let error = parse_commit_message("whatever: add suggestions").unwrap_err();
+        assert_eq!(
+            FormatErrorKind::InvalidCommitType {
+                commit_type: "whatever".to_owned(),
+                suggestion: None,
+            },
+            error.kind
+        );
+        assert!(error
+            .to_string()
+            .starts_with("Invalid commit type 'whatever' [VC004 type-valid]\n"));
+    }
+
+    #[test]
+    fn test_allowed_scopes() {
+        let options = ValidationOptions {
+            allowed_scopes: Some(vec!["lib".to_owned(), "api/*".to_owned()]),
+            ..ValidationOptions::default()
+        };
+
+        assert!(parse_commit_message_with("feat(lib): add scope check", &options).is_ok());
+        assert!(parse_commit_message_with("feat(api/users): add scope check", &options).is_ok());
+        assert!(parse_commit_message_with("feat: add scope check", &options).is_ok());
+
+        let error = parse_commit_message_with("feat(cli): add scope check", &options).unwrap_err();
+        assert_eq!(
+            FormatErrorKind::UnknownScope {
+                scope: "cli".to_owned()
+            },
+            error.kind
+        );
+        assert_eq!(
+            "Unknown scope 'cli' [VC015 scope-allowed]\nline 1, column 6:\nfeat(cli): add scope check\n     ^^^",
+            error.to_string()
+        );
+
+        let error =
+            parse_commit_message_with("fixup! feat(api): add scope check", &options).unwrap_err();
+        assert_eq!(error.column(), Some(13));
+        assert!(
+            parse_commit_message_with("feat(api/users/list): add scope check", &options).is_err()
+        );
+    }
+
+    #[test]
+    fn test_scope_policy() {
+        let mut options = ValidationOptions {
+            scope_policy: ScopePolicy::Required,
+            ..ValidationOptions::default()
+        };
+        assert!(parse_commit_message_with("feat(lib): add scope policy", &options).is_ok());
+        let error = parse_commit_message_with("feat!: add scope policy", &options).unwrap_err();
+        assert_eq!(FormatErrorKind::MissingScope, error.kind);
+        assert_eq!(
+            "Missing scope [VC016 scope-policy]\nline 1, column 6:\nfeat!: add scope policy\n     ^",
+            error.to_string()
+        );
+
+        options.scope_policy = ScopePolicy::Forbidden;
+        assert!(parse_commit_message_with("feat: add scope policy", &options).is_ok());
+        let error = parse_commit_message_with("feat(lib): add scope policy", &options).unwrap_err();
+        assert_eq!(FormatErrorKind::ScopeNotAllowed, error.kind);
+        assert_eq!(
+            "Scope is not allowed [VC017 scope-policy]\nline 1, column 5:\nfeat(lib): add scope policy\n    ^^^^^",
+            error.to_string()
+        );
+
+        options.scope_policy = ScopePolicy::Any;
+        assert!(parse_commit_message_with("feat: add scope policy", &options).is_ok());
+        assert!(parse_commit_message_with("feat(lib): add scope policy", &options).is_ok());
+    }
+
+    #[test]
+    fn test_discard_empty_scope() {
+        for header in &["feat(): add thing", "feat(  )!: add thing"] {
+            let error = parse_commit_message(header).unwrap_err();
+            assert_eq!(FormatErrorKind::EmptyScope, error.kind);
+            assert_eq!(error.column(), Some(5));
+        }
+    }
+
+    #[test]
+    fn test_discard_unbalanced_scope_parentheses() {
+        let error = parse_commit_message("feat(a(b): add thing").unwrap_err();
+        assert_eq!(FormatErrorKind::MissingParenthesis, error.kind);
+        assert_eq!(error.column(), Some(7));
+
+        let error = parse_commit_message("feat(a)b): add thing").unwrap_err();
+        assert_eq!(FormatErrorKind::MissingParenthesis, error.kind);
+        assert_eq!(error.column(), Some(7));
+
+        let commit_msg = parse_commit_message("feat(a(b)): add thing").unwrap();
+        assert_eq!(commit_msg.header.scope, Some("a(b)"));
+    }
+
+    #[test]
+    fn test_parse_scope_with_hyphens_and_slashes() {
+        let commit_msg = parse_commit_message("feat(api/user-list): add thing").unwrap();
+        assert_eq!(commit_msg.header.scope, Some("api/user-list"));
+    }
+
+    #[test]
+    fn test_scope_pattern() {
+        let mut options = ValidationOptions {
+            scope_pattern: ScopePattern::KebabCase,
+            ..ValidationOptions::default()
+        };
+        for header in &[
+            "feat(lib): add scope pattern",
+            "feat(ui/button): add scope pattern",
+            "feat(error-2/span): add scope pattern",
+        ] {
+            assert!(parse_commit_message_with(header, &options).is_ok());
+        }
+        for &(header, column) in &[
+            ("feat(Lib): add scope pattern", 6),
+            ("feat(ui/Button): add scope pattern", 9),
+            ("feat(ui_button): add scope pattern", 8),
+            ("feat(ui--button): add scope pattern", 9),
+            ("feat(-ui): add scope pattern", 6),
+            ("feat(ui/): add scope pattern", 8),
+        ] {
+            let error = parse_commit_message_with(header, &options).unwrap_err();
+            assert_eq!(FormatErrorKind::InvalidScopeFormat, error.kind);
+            assert_eq!(error.column(), Some(column), "{}", header);
+        }
+
+        options.scope_pattern = ScopePattern::Lowercase;
+        assert!(parse_commit_message_with("feat(ui_button): add scope pattern", &options).is_ok());
+        let error =
+            parse_commit_message_with("feat(ui/Button): add scope pattern", &options).unwrap_err();
+        assert_eq!(
+            "Invalid scope format [VC019 scope-format]\nline 1, column 9:\nfeat(ui/Button): add scope pattern\n        ^",
+            error.to_string()
+        );
+
+        options.scope_pattern = ScopePattern::Any;
+        assert!(parse_commit_message_with("feat(UI_Button): add scope pattern", &options).is_ok());
+    }
+
+    #[test]
+    fn test_parse_multiple_scopes() {
+        let commit_msg = parse_commit_message("fix(parser,errors): handle empty input").unwrap();
+        assert_eq!(commit_msg.header.scope, Some("parser,errors"));
+        assert_eq!(commit_msg.header.scopes, vec!["parser", "errors"]);
+
+        let commit_msg = parse_commit_message("fix(parser): handle empty input").unwrap();
+        assert_eq!(commit_msg.header.scopes, vec!["parser"]);
+
+        let commit_msg = parse_commit_message("fix: handle empty input").unwrap();
+        assert!(commit_msg.header.scopes.is_empty());
+    }
+
+    #[test]
+    fn test_discard_malformed_multiple_scopes() {
+        for (header, kind, column) in [
+            ("feat(a,): x", FormatErrorKind::EmptyScope, 7),
+            ("feat(,a): x", FormatErrorKind::EmptyScope, 6),
+            ("feat(a,,b): x", FormatErrorKind::EmptyScope, 8),
+            ("feat(a, b): x", FormatErrorKind::MisplacedWhitespace, 8),
+            ("feat(a ,b): x", FormatErrorKind::MisplacedWhitespace, 7),
+        ] {
+            let error = parse_commit_message(header).unwrap_err();
+            assert_eq!(kind, error.kind, "{}", header);
+            assert_eq!(error.column(), Some(column), "{}", header);
+        }
+    }
+
+    #[test]
+    fn test_check_each_scope() {
+        let options = ValidationOptions {
+            allowed_scopes: Some(vec!["parser".to_owned(), "errors".to_owned()]),
+            ..ValidationOptions::default()
+        };
+        assert!(
+            parse_commit_message_with("fix(parser,errors): handle empty input", &options).is_ok()
+        );
+
+        let error =
+            parse_commit_message_with("fix(parser,cli): handle empty input", &options).unwrap_err();
+        assert_eq!(
+            FormatErrorKind::UnknownScope {
+                scope: "cli".to_owned()
+            },
+            error.kind
+        );
+        assert_eq!(
+            "Unknown scope 'cli' [VC015 scope-allowed]\nline 1, column 12:\nfix(parser,cli): handle empty input\n           ^^^",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn test_ignore_lines_after_scissors() {
+        let commit_msg = parse_commit_message(
+            "feat: add scissors
+
+Body
+# ------------------------ >8 ------------------------
+Not a footer: but a diff line",
+        )
+        .unwrap();
+        assert_eq!(commit_msg.body, Some("Body"));
+        assert!(commit_msg.footers.is_empty());
+    }
+
+    #[test]
+    fn test_line_at() {
+        let input = "\u{feff}feat: add lines\r\n\r\nBody\r\nLast";
+        let line = line_at(input, 5);
+        assert_eq!(
+            (line.number, line.pos, line.text),
+            (1, 3, "feat: add lines")
+        );
+        let line = line_at(input, 22);
+        assert_eq!((line.number, line.pos, line.text), (3, 22, "Body"));
+        let line = line_at(input, input.len());
+        assert_eq!((line.number, line.text), (4, "Last"));
+    }
+
+    #[test]
+    fn test_custom_comment_char() {
+        let options = ValidationOptions {
+            comment_char: ';',
+            ..ValidationOptions::default()
+        };
+        let commit_msg = parse_commit_message_with(
+            "; Please enter the commit message
+feat: add comment char
+
+#42 is fixed
+; ------------------------ >8 ------------------------
+Not a footer: but a diff line",
+            &options,
+        )
+        .unwrap();
+        assert_eq!(commit_msg.body, Some("#42 is fixed"));
+        assert!(commit_msg.footers.is_empty());
+
+        let error = parse_commit_message_with("# feat: add comment char", &options).unwrap_err();
+        assert!(matches!(
+            error.kind,
+            FormatErrorKind::InvalidCommitType { .. }
+        ));
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("lib", "lib"));
+        assert!(!glob_matches("lib", "libs"));
+        assert!(glob_matches("api/*", "api/users"));
+        assert!(glob_matches("api/*", "api/"));
+        assert!(!glob_matches("api/*", "api"));
+        assert!(!glob_matches("api/*", "api/users/list"));
+        assert!(glob_matches("api/*/*", "api/users/list"));
+        assert!(glob_matches("*-cli", "validate-commit-cli"));
+        assert!(glob_matches("v?", "v2"));
+        assert!(!glob_matches("v?", "v"));
+    }
+
+    #[test]
+    fn test_split_emoji() {
+        assert_eq!(split_emoji("✨ feat: x"), Some(("✨", "feat: x")));
+        assert_eq!(split_emoji("❤️ fix: x"), Some(("❤️", "fix: x")));
+        assert_eq!(split_emoji("👩‍💻 fix: x"), Some(("👩‍💻", "fix: x")));
+        assert_eq!(split_emoji("👍🏽 fix: x"), Some(("👍🏽", "fix: x")));
+        assert_eq!(split_emoji("🇫🇷 fix: x"), Some(("🇫🇷", "fix: x")));
+        assert_eq!(
+            split_emoji(":sparkles: feat: x"),
+            Some((":sparkles:", "feat: x"))
+        );
+        assert_eq!(split_emoji(":+1: feat: x"), Some((":+1:", "feat: x")));
+        assert_eq!(split_emoji("✨feat: x"), None);
+        assert_eq!(split_emoji("✨✨ feat: x"), None);
+        assert_eq!(split_emoji(":: feat: x"), None);
+        assert_eq!(split_emoji(":Sparkles: feat: x"), None);
+        assert_eq!(split_emoji(":sparkles feat: x"), None);
+        assert_eq!(split_emoji("feat: x"), None);
+    }
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(0, levenshtein("feat", "feat"));
+        assert_eq!(1, levenshtein("feet", "feat"));
+        assert_eq!(2, levenshtein("feta", "feat"));
+        assert_eq!(3, levenshtein("", "fix"));
+        assert_eq!(1, levenshtein("café", "cafe"));
     }
 
     #[test]
     fn discard_not_trimmed_subject() {
-        assert!(parse_commit_message(&["feat: add commit message validation "]).is_err());
-        let res = parse_commit_message(&["feat:  add commit message validation"]);
+        assert!(parse_commit_message("feat: add commit message validation ").is_err());
+        let res = parse_commit_message("feat:  add commit message validation");
         assert!(res.is_err());
         assert_eq!(FormatErrorKind::MisplacedWhitespace, res.unwrap_err().kind);
     }
 
     #[test]
     fn discard_missing_whitespace() {
-        let res = parse_commit_message(&["feat:add commit message validation"]);
+        let res = parse_commit_message("feat:add commit message validation");
         assert!(res.is_err());
         assert_eq!(FormatErrorKind::MissingWhitespace, res.unwrap_err().kind);
     }
 
+    #[test]
+    fn discard_multi_byte_whitespace_after_type() {
+        for space in ['\u{a0}', '\u{85}', '\u{2028}'] {
+            let header = format!("fix{}: add x", space);
+            let error = parse_commit_message(&header).unwrap_err();
+            assert_eq!(
+                FormatErrorKind::MisplacedWhitespace,
+                error.kind,
+                "{:?}",
+                header
+            );
+            assert_eq!(error.column(), Some(4), "{:?}", header);
+            let span = error.span().unwrap();
+            assert_eq!(&span.line()[span.pos()..span.end()], space.to_string());
+        }
+    }
+
     #[test]
     fn test_second_line_empty() {
-        let res = parse_commit_message(&[
-            "feat: add commit message validation",
-"- Validate commit type",
-"- Validate subject",
-        ]);
+        let res = parse_commit_message(
+            "feat: add commit message validation
+- Validate commit type
+- Validate subject",
+        );
         assert!(res.is_err());
         assert_eq!(FormatErrorKind::NonEmptySecondLine, res.unwrap_err().kind);
     }
 
     #[test]
     fn test_fixup_or_squash() {
-        assert!(parse_commit_message(&["fixup! feat: add commit message validation"]).is_ok());
-        assert!(parse_commit_message(&["squash! feat: add commit message validation"]).is_ok());
+        assert!(parse_commit_message("fixup! feat: add commit message validation").is_ok());
+        assert!(parse_commit_message("squash! feat: add commit message validation").is_ok());
+        assert!(parse_commit_message("amend! feat: add commit message validation").is_ok());
+    }
+
+    #[test]
+    fn test_stacked_autosquash_prefixes() {
+        let message = parse_commit_message("fixup! fixup! feat: add validation").unwrap();
+        assert_eq!(message.header.commit_type, CommitType::Feat);
+        assert_eq!(message.header.subject, "add validation");
+        let message = parse_commit_message("fixup! amend! squash! fix: handle input").unwrap();
+        assert_eq!(message.header.subject, "handle input");
+
+        let error = parse_commit_message("fixup! fixup! feta: add validation").unwrap_err();
+        assert_eq!(error.column(), Some(15));
+    }
+
+    #[test]
+    fn test_keep_autosquash_prefix_without_space() {
+        let error = parse_commit_message("fixup!feat: add validation").unwrap_err();
+        assert!(matches!(
+            error.kind,
+            FormatErrorKind::InvalidCommitType { .. }
+        ));
+        assert!(parse_commit_message("fixup! fixup!feat: add validation").is_err());
+    }
+
+    #[test]
+    fn test_reverted_header() {
+        assert_eq!(
+            reverted_header(r#"Revert "feat: add validation""#),
+            Some((8, "feat: add validation"))
+        );
+        assert_eq!(
+            reverted_header(r#"Revert "Revert "feat: add validation"""#),
+            Some((16, "feat: add validation"))
+        );
+        assert_eq!(
+            reverted_header(r#"Revert "Revert "feat: "quoted" things"""#),
+            Some((16, r#"feat: "quoted" things"#))
+        );
+        assert_eq!(reverted_header("Revert feat: add validation"), None);
+        assert_eq!(reverted_header(r#"Revert "feat: add validation"#), None);
+        assert_eq!(reverted_header("revert: add validation"), None);
+    }
+
+    #[test]
+    fn test_is_git_merge() {
+        assert!(is_git_merge("Merge branch 'develop'"));
+        assert!(is_git_merge("Merge branch 'develop' into main"));
+        assert!(is_git_merge("Merge branch 'main' of github.com:user/repo"));
+        assert!(is_git_merge("Merge branches 'a' and 'b'"));
+        assert!(is_git_merge("Merge tag 'v1.2.0'"));
+        assert!(is_git_merge("Merge remote-tracking branch 'origin/main'"));
+        assert!(is_git_merge("Merge commit '3f2c1a9'"));
+        assert!(is_git_merge("Merge pull request #42 from user/branch"));
+        assert!(!is_git_merge("Merge the two parser modules"));
+        assert!(!is_git_merge("Merge branch develop"));
+        assert!(!is_git_merge("Merge branch 'develop"));
+        assert!(!is_git_merge("Merge pull request # from user/branch"));
+        assert!(!is_git_merge("Merge pull request #42"));
+        assert!(!is_git_merge("feat: merge branch 'develop'"));
+    }
+
+    #[test]
+    fn test_autosquash_second_line() {
+        assert!(parse_commit_message("amend! feat: add validation\nfeat: add validation").is_ok());
+        assert!(parse_commit_message("squash! feat: add validation\nSquashed body").is_ok());
+        let error = parse_commit_message("fixup! feat: add validation\nFixed body").unwrap_err();
+        assert_eq!(error.kind, FormatErrorKind::NonEmptySecondLine);
+        let error = parse_commit_message("feat: add validation\nBody").unwrap_err();
+        assert_eq!(error.kind, FormatErrorKind::NonEmptySecondLine);
     }
 }
@@ -0,0 +1,255 @@
+//! External commands checking the commit messages.
+//!
+//! A plugin is configured in the configuration file as:
+//!
+//! ```toml
+//! [[plugin]]
+//! command = "./scripts/check-msg.sh"
+//! args = ["--strict"]
+//! timeout = 10
+//! ```
+//!
+//! The message is written to the standard input of the command. The message is invalid if the
+//! command exits with a non-zero status, its standard output being the message of the error. The
+//! output can start with a `line:column:` prefix, numbered from 1, to locate the error in the
+//! message.
+
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "config")]
+use serde::{Deserialize, Serialize};
+
+use errors::{ConfigError, FormatError, FormatErrorKind};
+
+/// Default timeout of the plugins, in seconds
+pub const DEFAULT_TIMEOUT: u64 = 10;
+
+/// Interval between the checks of the end of the command
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// External command checking the commit messages
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "config",
+    derive(Deserialize, Serialize),
+    serde(deny_unknown_fields)
+)]
+pub struct Plugin {
+    /// Path or name of the executable
+    ///
+    /// Loaded from a configuration file, a relative path like `./scripts/check-msg.sh` is
+    /// relative to the directory of the file.
+    pub command: String,
+    /// Arguments of the command
+    #[cfg_attr(feature = "config", serde(default))]
+    pub args: Vec<String>,
+    /// Time after which the command is killed, in seconds
+    #[cfg_attr(feature = "config", serde(default = "default_timeout"))]
+    pub timeout: u64,
+    /// Configuration file declaring the plugin
+    #[cfg_attr(feature = "config", serde(skip))]
+    config_path: Option<PathBuf>,
+}
+
+#[cfg(feature = "config")]
+fn default_timeout() -> u64 {
+    DEFAULT_TIMEOUT
+}
+
+impl Plugin {
+    /// Create a plugin running the command without arguments.
+    pub fn new<S: Into<String>>(command: S) -> Self {
+        Plugin {
+            command: command.into(),
+            args: Vec::new(),
+            timeout: DEFAULT_TIMEOUT,
+            config_path: None,
+        }
+    }
+
+    /// Set the arguments of the command.
+    pub fn with_args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args = args.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set the time after which the command is killed, in seconds.
+    pub fn with_timeout(mut self, timeout: u64) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Resolve the relative path of the command from the configuration file declaring it.
+    #[cfg(feature = "config")]
+    pub(crate) fn declared_in(&mut self, config_path: &Path) {
+        let command = Path::new(&self.command);
+        if command.is_relative() && command.components().count() > 1 {
+            if let Some(dir) = config_path.parent() {
+                self.command = dir.join(command).to_string_lossy().into_owned();
+            }
+        }
+        self.config_path = Some(config_path.to_owned());
+    }
+
+    /// Run the command on the message.
+    ///
+    /// Return the error reported by the command, if it fails. A command which cannot be run or
+    /// which times out is a configuration error.
+    pub fn check(&self, raw: &str) -> Result<Option<FormatError>, ConfigError> {
+        let mut child = Command::new(&self.command)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| match e.kind() {
+                io::ErrorKind::NotFound => self.error("command not found"),
+                _ => self.error(e),
+            })?;
+
+        // The pipes are handled by threads so that a command reading or writing a lot does not
+        // block, and a command not reading its input does not fail
+        let mut stdin = child.stdin.take().unwrap();
+        let input = raw.to_owned();
+        thread::spawn(move || stdin.write_all(input.as_bytes()));
+        let mut stdout = child.stdout.take().unwrap();
+        let output = thread::spawn(move || {
+            let mut output = String::new();
+            stdout.read_to_string(&mut output).map(|_| output)
+        });
+
+        let timeout = Duration::from_secs(self.timeout);
+        let start = Instant::now();
+        let status = loop {
+            if let Some(status) = child.try_wait().map_err(|e| self.error(e))? {
+                break status;
+            }
+            if start.elapsed() >= timeout {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(self.error(format_args!("timed out after {} s", self.timeout)));
+            }
+            thread::sleep(POLL_INTERVAL);
+        };
+        let output = output.join().unwrap().map_err(|e| self.error(e))?;
+        if status.success() {
+            return Ok(None);
+        }
+
+        let output = output.trim();
+        let (location, message) = parse_location(output);
+        let message = if message.is_empty() {
+            format!("'{}' failed with {}", self.command, status)
+        } else {
+            message.to_owned()
+        };
+        let kind = FormatErrorKind::Custom {
+            rule: self.command.clone(),
+            message,
+        };
+        let error = location
+            .and_then(|(line_number, column)| {
+                let line = raw.lines().nth(line_number - 1)?;
+                let line = line.strip_suffix('\r').unwrap_or(line);
+                let pos = line
+                    .char_indices()
+                    .nth(column - 1)
+                    .map_or(line.len(), |(pos, _)| pos);
+                Some(kind.clone().at(line, line_number, pos))
+            })
+            .unwrap_or_else(|| kind.into());
+        Ok(Some(error))
+    }
+
+    fn error<M: ::std::fmt::Display>(&self, message: M) -> ConfigError {
+        let path = match self.config_path {
+            Some(ref path) => path.as_path(),
+            None => Path::new(&self.command),
+        };
+        ConfigError::new(path, format_args!("plugin '{}': {}", self.command, message))
+    }
+}
+
+/// Split the `line:column:` prefix of the output of a plugin, numbered from 1.
+fn parse_location(output: &str) -> (Option<(usize, usize)>, &str) {
+    let mut parts = output.splitn(3, ':');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(line), Some(column), Some(message)) => {
+            match (line.parse::<usize>(), column.parse::<usize>()) {
+                (Ok(line), Ok(column)) if line > 0 && column > 0 => {
+                    (Some((line, column)), message.trim_start())
+                }
+                _ => (None, output),
+            }
+        }
+        _ => (None, output),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shell(script: &str) -> Plugin {
+        Plugin::new("sh").with_args(vec!["-c", script])
+    }
+
+    #[test]
+    fn test_parse_location() {
+        assert_eq!(parse_location("2:5: no"), (Some((2, 5)), "no"));
+        assert_eq!(parse_location("no: never"), (None, "no: never"));
+        assert_eq!(parse_location("0:1: no"), (None, "0:1: no"));
+        assert_eq!(parse_location("no"), (None, "no"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn report_failing_commands() {
+        let plugin = shell("grep -q temp || exit 0; echo '1:11: Temporary commit'; exit 1");
+        assert_eq!(plugin.check("feat: add rules").unwrap(), None);
+
+        let error = plugin.check("feat: add temp rules").unwrap().unwrap();
+        assert_eq!(
+            error.kind,
+            FormatErrorKind::Custom {
+                rule: "sh".to_owned(),
+                message: "Temporary commit".to_owned(),
+            }
+        );
+        assert_eq!((error.line_number(), error.column()), (Some(1), Some(11)));
+
+        let error = shell("exit 2").check("feat: add rules").unwrap().unwrap();
+        assert_eq!(error.line_number(), None);
+        assert_eq!(
+            error.to_string(),
+            "'sh' failed with exit status: 2 [VC032 custom]"
+        );
+    }
+
+    #[test]
+    fn missing_command_is_a_config_error() {
+        let error = Plugin::new("./missing-plugin").check("feat: add rules");
+        assert_eq!(
+            error.unwrap_err().to_string(),
+            "Invalid configuration file './missing-plugin': \
+             plugin './missing-plugin': command not found"
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn timeout_is_a_config_error() {
+        let plugin = shell("sleep 5").with_timeout(1);
+        let start = Instant::now();
+        let error = plugin.check("feat: add rules").unwrap_err();
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(error.to_string().ends_with("timed out after 1 s"));
+    }
+}
@@ -0,0 +1,711 @@
+//! Identifiers and severities of the validation rules.
+
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use errors::FormatErrorKind;
+
+/// Identifier of a validation rule
+///
+/// Each [`FormatErrorKind`] is reported by exactly one rule, see [`RuleId::of`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RuleId {
+    /// The message must not be empty
+    MessageNotEmpty,
+    /// The message must not start with a blank line
+    NoLeadingBlankLine,
+    /// The header must follow the `type(scope): subject` format
+    HeaderFormat,
+    /// The header must not contain misplaced whitespaces
+    HeaderWhitespace,
+    /// The commit type must not be empty
+    TypeNotEmpty,
+    /// The commit type must be one of the allowed types
+    TypeValid,
+    /// The commit type must be lowercase
+    TypeCase,
+    /// The commit type should be written in its canonical form, not as an alias
+    TypeCanonical,
+    /// The scope must not be empty
+    ScopeNotEmpty,
+    /// The scope must be one of the allowed scopes
+    ScopeAllowed,
+    /// The scope must follow the configured pattern
+    ScopeFormat,
+    /// The scope must be present or absent, according to the scope policy
+    ScopePolicy,
+    /// The subject must not be empty
+    SubjectNotEmpty,
+    /// The subject must not start with a capital letter
+    SubjectNotCapitalized,
+    /// The subject must not end with a punctuation character
+    SubjectNoTrailingPunctuation,
+    /// The subject should not start with the commit type
+    SubjectNoTypeEcho,
+    /// The subject should be in the imperative mood
+    SubjectImperative,
+    /// The subject should not be longer than the soft limit
+    SubjectMaxLength,
+    /// The lines must not be longer than the configured limits
+    LineMaxLength,
+    /// The lines must not end with whitespaces
+    NoTrailingWhitespace,
+    /// The second line must be empty
+    SecondLineEmpty,
+    /// The message should not contain several blank lines in a row
+    NoConsecutiveBlankLines,
+    /// The message must have a body, for the configured commit types
+    BodyRequired,
+    /// The footers must have a value
+    FooterValueNotEmpty,
+    /// The message must reference an issue, for the configured commit types
+    IssueReference,
+    /// The message must be signed off, when required
+    SignOff,
+    /// The co-authors must be formatted like `Name <email>`
+    CoAuthorFormat,
+    /// The subject and the body must not contain the forbidden words
+    NoForbiddenWords,
+    /// The rules added to a [`Validator`](::Validator)
+    ///
+    /// They are not part of [`RuleId::ALL`], which only lists the built-in rules.
+    Custom,
+}
+
+impl RuleId {
+    /// All the built-in rules
+    pub const ALL: [RuleId; 28] = [
+        RuleId::MessageNotEmpty,
+        RuleId::NoLeadingBlankLine,
+        RuleId::HeaderFormat,
+        RuleId::HeaderWhitespace,
+        RuleId::TypeNotEmpty,
+        RuleId::TypeValid,
+        RuleId::TypeCase,
+        RuleId::TypeCanonical,
+        RuleId::ScopeNotEmpty,
+        RuleId::ScopeAllowed,
+        RuleId::ScopeFormat,
+        RuleId::ScopePolicy,
+        RuleId::SubjectNotEmpty,
+        RuleId::SubjectNotCapitalized,
+        RuleId::SubjectNoTrailingPunctuation,
+        RuleId::SubjectNoTypeEcho,
+        RuleId::SubjectImperative,
+        RuleId::SubjectMaxLength,
+        RuleId::LineMaxLength,
+        RuleId::NoTrailingWhitespace,
+        RuleId::SecondLineEmpty,
+        RuleId::NoConsecutiveBlankLines,
+        RuleId::BodyRequired,
+        RuleId::FooterValueNotEmpty,
+        RuleId::IssueReference,
+        RuleId::SignOff,
+        RuleId::CoAuthorFormat,
+        RuleId::NoForbiddenWords,
+    ];
+
+    /// Return the rule reporting the errors of the given kind.
+    pub fn of(kind: &FormatErrorKind) -> RuleId {
+        use errors::FormatErrorKind::*;
+
+        match kind {
+            CapitalizedFirstLetter => RuleId::SubjectNotCapitalized,
+            CommitTypeNotLowercase { .. } => RuleId::TypeCase,
+            ConsecutiveBlankLines => RuleId::NoConsecutiveBlankLines,
+            Custom { .. } => RuleId::Custom,
+            EmptyCommitMessage => RuleId::MessageNotEmpty,
+            EmptyCommitSubject => RuleId::SubjectNotEmpty,
+            EmptyCommitType => RuleId::TypeNotEmpty,
+            EmptyFooterValue => RuleId::FooterValueNotEmpty,
+            ForbiddenWord { .. } => RuleId::NoForbiddenWords,
+            EmptyScope => RuleId::ScopeNotEmpty,
+            InvalidCommitType { .. } => RuleId::TypeValid,
+            InvalidScopeFormat => RuleId::ScopeFormat,
+            LeadingBlankLine => RuleId::NoLeadingBlankLine,
+            LineTooLong(_) => RuleId::LineMaxLength,
+            EmojiPrefix { .. }
+            | HeaderPatternMismatch { .. }
+            | MisplacedBreakingChangeMarker
+            | MissingParenthesis
+            | MissingWhitespace
+            | NoColumn => RuleId::HeaderFormat,
+            MissingScope | ScopeNotAllowed => RuleId::ScopePolicy,
+            MissingBody { .. } => RuleId::BodyRequired,
+            MissingIssueReference { .. } => RuleId::IssueReference,
+            MalformedCoAuthor => RuleId::CoAuthorFormat,
+            MalformedSignOff | MissingSignOff => RuleId::SignOff,
+            MisplacedWhitespace => RuleId::HeaderWhitespace,
+            NonCanonicalType { .. } => RuleId::TypeCanonical,
+            NonEmptySecondLine => RuleId::SecondLineEmpty,
+            NonImperativeSubject { .. } => RuleId::SubjectImperative,
+            SubjectEchoesType => RuleId::SubjectNoTypeEcho,
+            SubjectTooLong(_) => RuleId::SubjectMaxLength,
+            TrailingPunctuation(_) => RuleId::SubjectNoTrailingPunctuation,
+            TrailingWhitespace => RuleId::NoTrailingWhitespace,
+            UnknownScope { .. } => RuleId::ScopeAllowed,
+        }
+    }
+
+    /// Stable identifier of the rule, like `subject-not-capitalized`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RuleId::MessageNotEmpty => "message-not-empty",
+            RuleId::NoLeadingBlankLine => "no-leading-blank-line",
+            RuleId::HeaderFormat => "header-format",
+            RuleId::HeaderWhitespace => "header-whitespace",
+            RuleId::TypeNotEmpty => "type-not-empty",
+            RuleId::TypeValid => "type-valid",
+            RuleId::TypeCase => "type-case",
+            RuleId::TypeCanonical => "type-canonical",
+            RuleId::ScopeNotEmpty => "scope-not-empty",
+            RuleId::ScopeAllowed => "scope-allowed",
+            RuleId::ScopeFormat => "scope-format",
+            RuleId::ScopePolicy => "scope-policy",
+            RuleId::SubjectNotEmpty => "subject-not-empty",
+            RuleId::SubjectNotCapitalized => "subject-not-capitalized",
+            RuleId::SubjectNoTrailingPunctuation => "subject-no-trailing-punctuation",
+            RuleId::SubjectNoTypeEcho => "subject-no-type-echo",
+            RuleId::SubjectImperative => "subject-imperative",
+            RuleId::SubjectMaxLength => "subject-max-length",
+            RuleId::LineMaxLength => "line-max-length",
+            RuleId::NoTrailingWhitespace => "no-trailing-whitespace",
+            RuleId::SecondLineEmpty => "second-line-empty",
+            RuleId::NoConsecutiveBlankLines => "no-consecutive-blank-lines",
+            RuleId::BodyRequired => "body-required",
+            RuleId::FooterValueNotEmpty => "footer-value-not-empty",
+            RuleId::IssueReference => "issue-reference",
+            RuleId::SignOff => "sign-off",
+            RuleId::CoAuthorFormat => "co-author-format",
+            RuleId::NoForbiddenWords => "no-forbidden-words",
+            RuleId::Custom => "custom",
+        }
+    }
+
+    /// Names of the [`FormatErrorKind`]s reported by the rule
+    pub fn kind_names(self) -> &'static [&'static str] {
+        match self {
+            RuleId::MessageNotEmpty => &["EmptyCommitMessage"],
+            RuleId::NoLeadingBlankLine => &["LeadingBlankLine"],
+            RuleId::HeaderFormat => &[
+                "EmojiPrefix",
+                "HeaderPatternMismatch",
+                "MisplacedBreakingChangeMarker",
+                "MissingParenthesis",
+                "MissingWhitespace",
+                "NoColumn",
+            ],
+            RuleId::HeaderWhitespace => &["MisplacedWhitespace"],
+            RuleId::TypeNotEmpty => &["EmptyCommitType"],
+            RuleId::TypeValid => &["InvalidCommitType"],
+            RuleId::TypeCase => &["CommitTypeNotLowercase"],
+            RuleId::TypeCanonical => &["NonCanonicalType"],
+            RuleId::ScopeNotEmpty => &["EmptyScope"],
+            RuleId::ScopeAllowed => &["UnknownScope"],
+            RuleId::ScopeFormat => &["InvalidScopeFormat"],
+            RuleId::ScopePolicy => &["MissingScope", "ScopeNotAllowed"],
+            RuleId::SubjectNotEmpty => &["EmptyCommitSubject"],
+            RuleId::SubjectNotCapitalized => &["CapitalizedFirstLetter"],
+            RuleId::SubjectNoTrailingPunctuation => &["TrailingPunctuation"],
+            RuleId::SubjectNoTypeEcho => &["SubjectEchoesType"],
+            RuleId::SubjectImperative => &["NonImperativeSubject"],
+            RuleId::SubjectMaxLength => &["SubjectTooLong"],
+            RuleId::LineMaxLength => &["LineTooLong"],
+            RuleId::NoTrailingWhitespace => &["TrailingWhitespace"],
+            RuleId::SecondLineEmpty => &["NonEmptySecondLine"],
+            RuleId::NoConsecutiveBlankLines => &["ConsecutiveBlankLines"],
+            RuleId::BodyRequired => &["MissingBody"],
+            RuleId::FooterValueNotEmpty => &["EmptyFooterValue"],
+            RuleId::IssueReference => &["MissingIssueReference"],
+            RuleId::SignOff => &["MissingSignOff", "MalformedSignOff"],
+            RuleId::CoAuthorFormat => &["MalformedCoAuthor"],
+            RuleId::NoForbiddenWords => &["ForbiddenWord"],
+            RuleId::Custom => &["Custom"],
+        }
+    }
+
+    /// Longer description of the rule, with examples
+    pub fn explanation(self) -> Explanation {
+        match self {
+            RuleId::MessageNotEmpty => Explanation {
+                summary: "The commit message must not be empty.",
+                rationale: "Git aborts the commit when the message is empty, and an empty message \
+                            tells nothing about the change. Comment lines do not count.",
+                valid: &["feat: add commit message validation"],
+                invalid: &["# Please enter the commit message for your changes."],
+            },
+            RuleId::NoLeadingBlankLine => Explanation {
+                summary: "The message must not start with a blank line.",
+                rationale: "Depending on the `commit.cleanup` mode, git may keep the blank line, \
+                            leaving the commit without summary. The header is still validated \
+                            from the first line which is not blank.",
+                valid: &["fix: handle empty input"],
+                invalid: &["\nfix: handle empty input"],
+            },
+            RuleId::HeaderFormat => Explanation {
+                summary: "The header must follow the `type(scope)!: subject` format.",
+                rationale: "The tools generating changelogs and computing versions rely on this \
+                            format to find the type, the scope and the breaking changes. Another \
+                            format can be set with `header_pattern`.",
+                valid: &["fix(parser): handle empty input", "feat!: drop the old API"],
+                invalid: &[
+                    "handle empty input",
+                    "fix(parser) handle empty input",
+                    "fix:handle empty input",
+                    "feat!(api): drop the old API",
+                ],
+            },
+            RuleId::HeaderWhitespace => Explanation {
+                summary: "The header must not contain whitespaces around the type and the scopes.",
+                rationale: "Misplaced whitespaces are easy to miss and make the type or the scope \
+                            unrecognizable for the tools reading the history.",
+                valid: &["fix(parser): handle empty input"],
+                invalid: &[
+                    "fix(parser) : handle empty input",
+                    "fix( parser): handle empty input",
+                ],
+            },
+            RuleId::TypeNotEmpty => Explanation {
+                summary: "The commit type must not be empty.",
+                rationale: "The type tells what kind of change the commit is, and decides whether \
+                            it appears in the changelog.",
+                valid: &["docs: fix typo"],
+                invalid: &[": fix typo"],
+            },
+            RuleId::TypeValid => Explanation {
+                summary: "The commit type must be one of the allowed types.",
+                rationale: "A fixed set of types keeps the history consistent. The allowed types \
+                            are the Angular ones by default, and can be set with `allowed_types` \
+                            in the configuration file.",
+                valid: &["feat: add scopes", "chore: update dependencies"],
+                invalid: &["feature: add scopes", "update: bump dependencies"],
+            },
+            RuleId::TypeCase => Explanation {
+                summary: "The commit type must be lowercase.",
+                rationale: "Mixing `Feat` and `feat` in the history makes it harder to search. \
+                            Uppercase types can be accepted with `allow_uppercase_types`.",
+                valid: &["feat: add scopes"],
+                invalid: &["Feat: add scopes", "FIX: handle empty input"],
+            },
+            RuleId::TypeCanonical => Explanation {
+                summary: "The commit type should be written in its canonical form.",
+                rationale: "The aliases set with `aliases` in the configuration file, like \
+                            `feature` for `feat`, are accepted and read as their canonical type, \
+                            but the history is easier to search with a single spelling. It is a \
+                            warning by default, and an error with `strict_aliases`.",
+                valid: &["feat: add scopes"],
+                invalid: &["feature: add scopes"],
+            },
+            RuleId::ScopeNotEmpty => Explanation {
+                summary: "The scope must not be empty when the parentheses are present.",
+                rationale: "Empty parentheses are a leftover of an unfinished header.",
+                valid: &["fix(parser): handle empty input", "fix: handle empty input"],
+                invalid: &[
+                    "fix(): handle empty input",
+                    "fix(parser,): handle empty input",
+                ],
+            },
+            RuleId::ScopeAllowed => Explanation {
+                summary: "The scopes must be among the allowed scopes.",
+                rationale: "A fixed set of scopes avoids having several names for the same part \
+                            of the project. The allowed scopes are set with `allowed_scopes` in \
+                            the configuration file, and can be glob patterns like `api/*`.",
+                valid: &["fix(api/users): handle empty names"],
+                invalid: &["fix(users-api): handle empty names"],
+            },
+            RuleId::ScopeFormat => Explanation {
+                summary: "The scopes must follow the configured pattern.",
+                rationale: "A consistent case keeps the scopes easy to search. The pattern is set \
+                            with `scope_pattern` in the configuration file.",
+                valid: &["fix(user-api): handle empty names"],
+                invalid: &[
+                    "fix(UserApi): handle empty names",
+                    "fix(user_api): handle empty names",
+                ],
+            },
+            RuleId::ScopePolicy => Explanation {
+                summary: "The scope must be present or absent, according to the scope policy.",
+                rationale: "Some projects want every commit to name the part it changes, others \
+                            have no use for scopes. The policy is set with `scope_policy` in the \
+                            configuration file, or with `--require-scope` and `--forbid-scope`.",
+                valid: &["fix(parser): handle empty input"],
+                invalid: &["fix: handle empty input"],
+            },
+            RuleId::SubjectNotEmpty => Explanation {
+                summary: "The subject must not be empty.",
+                rationale: "The subject is the summary of the change shown by `git log --oneline`.",
+                valid: &["fix: handle empty input"],
+                invalid: &["fix: "],
+            },
+            RuleId::SubjectNotCapitalized => Explanation {
+                summary: "The subject must not start with a capital letter.",
+                rationale: "The subject follows the type like the rest of a sentence, and the \
+                            Angular convention writes it in lowercase. Acronyms like `JSON` are \
+                            accepted, as are the words listed in `allowed_capitalized_words`.",
+                valid: &["feat: add scopes"],
+                invalid: &["feat: Add scopes"],
+            },
+            RuleId::SubjectNoTrailingPunctuation => Explanation {
+                summary: "The subject must not end with a punctuation character.",
+                rationale: "The subject is a title, not a sentence. The forbidden characters are \
+                            set with `subject_trailing_punctuation`, only `.` by default, which \
+                            also rejects an ellipsis or a period after a version number.",
+                valid: &["build: bump to v1.2", "feat: add scopes?"],
+                invalid: &[
+                    "feat: add scopes.",
+                    "build: bump to v1.2.",
+                    "feat: add scopes...",
+                ],
+            },
+            RuleId::SubjectNoTypeEcho => Explanation {
+                summary: "The subject should not start with the commit type.",
+                rationale:
+                    "The changelogs group the commits by type, so repeating it reads badly, \
+                            like in `fix: fix crash`. Only whole words are compared, ignoring the \
+                            case. It is a warning by default.",
+                valid: &["fix: handle empty input", "fix: fixture loading"],
+                invalid: &["fix: fix crash in parser", "docs: docs update"],
+            },
+            RuleId::SubjectImperative => Explanation {
+                summary: "The subject should be in the imperative mood.",
+                rationale: "The subject tells what applying the commit does, like the messages \
+                            generated by git: \"Merge branch\", \"Revert\". The first word is \
+                            reported if it ends in `-ed`, `-s` or `-ing`. The check is a \
+                            heuristic, so it is a warning by default, and the words can be \
+                            allowed or denied with `imperative_allowed_words` and \
+                            `imperative_denied_words`.",
+                valid: &["feat: add colors", "fix: process empty input"],
+                invalid: &[
+                    "feat: added colors",
+                    "feat: adds colors",
+                    "feat: adding colors",
+                ],
+            },
+            RuleId::SubjectMaxLength => Explanation {
+                summary: "The subject should not be longer than the soft limit.",
+                rationale: "A short subject is easier to read in `git log --oneline` and in the \
+                            changelogs. The limit is set with `soft_subject_limit`, like 50 \
+                            characters, and only counts the subject after `type(scope): `. It \
+                            is a warning by default, which does not make the validation fail.",
+                valid: &["feat: add scopes"],
+                invalid: &[
+                    "feat: add scopes, which are written between parentheses after the type",
+                ],
+            },
+            RuleId::LineMaxLength => Explanation {
+                summary: "The lines must not be longer than the configured limits.",
+                rationale: "Long lines are truncated or wrapped by the tools displaying the \
+                            history. The limits are set with `max_header_length` and \
+                            `max_body_line_length`, 100 characters by default. The body lines \
+                            which are too long because of a URL, or indented like code blocks, \
+                            are accepted unless `ignore_long_urls` or `ignore_indented_lines` is \
+                            disabled.",
+                valid: &["feat: add scopes"],
+                invalid: &[
+                    "feat: add scopes, which are the part of the project changed by the \
+                            commit, written between parentheses after the type",
+                ],
+            },
+            RuleId::NoTrailingWhitespace => Explanation {
+                summary: "The lines must not end with whitespaces.",
+                rationale: "Trailing whitespaces are invisible in most editors, and make noisy \
+                            diffs in the tools reading the history, like the changelog \
+                            generators. The lines made only of whitespaces are reported too.",
+                valid: &["fix: handle empty input\n\nThe parser used to panic."],
+                invalid: &[
+                    "fix: handle empty input ",
+                    "fix: handle empty input\n\nThe parser used to panic.\t",
+                    "fix: handle empty input\n\nThe parser used to panic.\n  \nCloses: #42",
+                ],
+            },
+            RuleId::SecondLineEmpty => Explanation {
+                summary: "The second line must be empty.",
+                rationale: "Git and the tools built on it take the first paragraph as the \
+                            summary, so the body must be separated from the header.",
+                valid: &["fix: handle empty input\n\nThe parser used to panic."],
+                invalid: &["fix: handle empty input\nThe parser used to panic."],
+            },
+            RuleId::NoConsecutiveBlankLines => Explanation {
+                summary: "The message should not contain several blank lines in a row.",
+                rationale:
+                    "A single blank line is enough to separate the paragraphs, and git only \
+                            collapses the blank lines with some `commit.cleanup` modes. It is a \
+                            warning by default.",
+                valid: &["fix: handle empty input\n\nThe parser used to panic."],
+                invalid: &[
+                    "fix: handle empty input\n\n\nThe parser used to panic.",
+                    "fix: handle empty input\n\nThe parser used to panic.\n\n\nCloses: #42",
+                ],
+            },
+            RuleId::BodyRequired => Explanation {
+                summary: "The message must have a body, for the configured commit types.",
+                rationale: "A one-line feature rarely explains enough for the reviewers. The \
+                            commit types are set with `require_body`, and the footers are not \
+                            part of the body. The `fixup!` and `squash!` commits and the \
+                            reverts are exempt.",
+                valid: &["feat: add colors\n\nThe output is easier to read in terminals."],
+                invalid: &["feat: add colors", "feat: add colors\n\nCloses: #42"],
+            },
+            RuleId::FooterValueNotEmpty => Explanation {
+                summary: "The footers must have a value.",
+                rationale: "A footer like `BREAKING CHANGE:` without description tells nothing \
+                            to the users upgrading.",
+                valid: &["feat!: drop the old API\n\nBREAKING CHANGE: use `run` instead"],
+                invalid: &["feat!: drop the old API\n\nBREAKING CHANGE:"],
+            },
+            RuleId::IssueReference => Explanation {
+                summary: "The message must reference an issue, for the configured commit types.",
+                rationale: "Linking the fixes to their issue keeps track of why they were made. \
+                            The commit types are set with `require_reference`, and the pattern of \
+                            the references with `reference_pattern`, like `#42` or `PROJ-42` by \
+                            default. The reference can be anywhere in the message.",
+                valid: &[
+                    "fix: handle empty input\n\nCloses: #42",
+                    "fix(PROJ-42): handle empty input",
+                ],
+                invalid: &["fix: handle empty input"],
+            },
+            RuleId::SignOff => Explanation {
+                summary: "The message must be signed off, when required.",
+                rationale: "Projects using the Developer Certificate of Origin need every commit \
+                            to carry a `Signed-off-by: Name <email>` footer, as added by \
+                            `git commit --signoff`. The footer is required with `require_signoff` \
+                            or `--require-signoff`, and every sign-off must have a name and an \
+                            email.",
+                valid: &["fix: handle empty input\n\nSigned-off-by: Jane Doe <jane@example.com>"],
+                invalid: &[
+                    "fix: handle empty input",
+                    "fix: handle empty input\n\nSigned-off-by: Jane Doe",
+                    "fix: handle empty input\n\nSigned-off-by: <jane@example.com>",
+                ],
+            },
+            RuleId::CoAuthorFormat => Explanation {
+                summary: "The co-authors must be formatted like `Name <email>`.",
+                rationale: "GitHub silently ignores the malformed `Co-authored-by` footers, so \
+                            the co-authors are not credited. The token is compared ignoring the \
+                            case, like GitHub does. It is a warning by default.",
+                valid: &["feat: add colors\n\nCo-authored-by: Jane Doe <jane@example.com>"],
+                invalid: &[
+                    "feat: add colors\n\nCo-authored-by: Jane Doe jane@example.com",
+                    "feat: add colors\n\nco-authored-by: Jane Doe",
+                ],
+            },
+            RuleId::NoForbiddenWords => Explanation {
+                summary: "The subject and the body must not contain the forbidden words.",
+                rationale: "Some words, like `temp`, `do not merge` or internal codenames, mark \
+                            the commits which must not be published. The words are set with \
+                            `forbidden_words`, and match whole words ignoring the case. The \
+                            regular expressions of `forbidden_patterns` are matched as they \
+                            are. No word is forbidden by default.",
+                valid: &["feat: add temporal queries"],
+                invalid: &[
+                    "feat: add temp queries",
+                    "feat: add queries\n\nThis is a TEMP implementation.",
+                ],
+            },
+            RuleId::Custom => Explanation {
+                summary: "The messages must pass the rules added to the validator.",
+                rationale: "Organizations can add their own checks, like forbidding a word, with \
+                            the `Rule` trait. The messages of the errors tell which rule failed.",
+                valid: &[],
+                invalid: &[],
+            },
+        }
+    }
+
+    /// Find the rule from its identifier or the name of one of its [`FormatErrorKind`]s.
+    pub fn find(code: &str) -> Result<RuleId, UnknownRuleError> {
+        code.parse().or_else(|e| {
+            RuleId::ALL
+                .iter()
+                .cloned()
+                .find(|rule| rule.kind_names().contains(&code))
+                .ok_or(e)
+        })
+    }
+
+    /// Severity of the rule, unless overridden in the options
+    pub fn default_severity(self) -> Severity {
+        match self {
+            RuleId::TypeCanonical
+            | RuleId::SubjectNoTypeEcho
+            | RuleId::SubjectImperative
+            | RuleId::SubjectMaxLength
+            | RuleId::NoConsecutiveBlankLines
+            | RuleId::CoAuthorFormat => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+impl fmt::Display for RuleId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RuleId {
+    type Err = UnknownRuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RuleId::ALL
+            .iter()
+            .chain(&[RuleId::Custom])
+            .cloned()
+            .find(|rule| rule.as_str() == s)
+            .ok_or_else(|| UnknownRuleError(s.to_owned()))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for RuleId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for RuleId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let rule = String::deserialize(deserializer)?;
+        rule.parse().map_err(::serde::de::Error::custom)
+    }
+}
+
+/// Longer description of a rule, shown by the `explain` command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Explanation {
+    /// One sentence stating the rule
+    pub summary: &'static str,
+    /// Why the rule exists
+    pub rationale: &'static str,
+    /// Messages following the rule
+    pub valid: &'static [&'static str],
+    /// Messages breaking the rule
+    pub invalid: &'static [&'static str],
+}
+
+/// Error returned when parsing an unknown rule identifier
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownRuleError(String);
+
+impl fmt::Display for UnknownRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rules: Vec<_> = RuleId::ALL.iter().map(|rule| rule.as_str()).collect();
+        write!(
+            f,
+            "Unknown rule '{}', expected one of: {}",
+            self.0,
+            rules.join(", ")
+        )
+    }
+}
+
+/// Severity of a rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Severity {
+    /// The violations make the validation fail
+    Error,
+    /// The violations are reported without making the validation fail
+    Warning,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_rule_ids() {
+        for &rule in RuleId::ALL.iter() {
+            assert_eq!(rule.as_str().parse(), Ok(rule));
+        }
+
+        let error = "subject-capitalized".parse::<RuleId>().unwrap_err();
+        assert!(error.to_string().starts_with(
+            "Unknown rule 'subject-capitalized', expected one of: message-not-empty, "
+        ));
+    }
+
+    #[test]
+    fn map_kinds_to_rules() {
+        assert_eq!(
+            RuleId::of(&FormatErrorKind::CapitalizedFirstLetter),
+            RuleId::SubjectNotCapitalized
+        );
+        assert_eq!(
+            RuleId::of(&FormatErrorKind::LineTooLong(100)),
+            RuleId::LineMaxLength
+        );
+        assert_eq!(RuleId::of(&FormatErrorKind::NoColumn), RuleId::HeaderFormat);
+    }
+
+    #[test]
+    fn check_explanation_examples() {
+        use options::{ScopePattern, ScopePolicy, ValidationOptions};
+
+        for &rule in RuleId::ALL.iter() {
+            let mut options = ValidationOptions::default();
+            match rule {
+                RuleId::ScopeAllowed => options.allowed_scopes = Some(vec!["api/*".to_owned()]),
+                RuleId::ScopeFormat => options.scope_pattern = ScopePattern::KebabCase,
+                RuleId::ScopePolicy => options.scope_policy = ScopePolicy::Required,
+                RuleId::IssueReference => options.require_reference = vec![::CommitType::Fix],
+                RuleId::SignOff => options.require_signoff = true,
+                RuleId::BodyRequired => options.require_body = vec![::CommitType::Feat],
+                RuleId::NoForbiddenWords => options.forbidden_words = vec!["temp".to_owned()],
+                RuleId::TypeCanonical => {
+                    options
+                        .aliases
+                        .insert("feature".to_owned(), ::CommitType::Feat);
+                }
+                _ => {}
+            }
+
+            options.soft_subject_limit = Some(50);
+            options.severities.insert(rule, Severity::Error);
+
+            let explanation = rule.explanation();
+            for valid in explanation.valid {
+                assert!(
+                    ::validate_commit_message_with(valid, &options).is_ok(),
+                    "{}: {:?}",
+                    rule,
+                    valid
+                );
+            }
+            for invalid in explanation.invalid {
+                let errors =
+                    ::validate_commit_message_all_with(invalid, &options).expect_err(invalid);
+                assert_eq!(errors[0].rule(), rule, "{:?}", invalid);
+            }
+        }
+    }
+
+    #[test]
+    fn find_rules_by_kind_name() {
+        assert_eq!(
+            RuleId::find("subject-not-capitalized"),
+            Ok(RuleId::SubjectNotCapitalized)
+        );
+        assert_eq!(
+            RuleId::find("CapitalizedFirstLetter"),
+            Ok(RuleId::SubjectNotCapitalized)
+        );
+        assert_eq!(RuleId::find("MissingScope"), Ok(RuleId::ScopePolicy));
+        assert!(RuleId::find("TrailingSpace").is_err());
+
+        let kind = FormatErrorKind::InvalidCommitType {
+            commit_type: "feta".to_owned(),
+            suggestion: None,
+        };
+        assert!(RuleId::of(&kind).kind_names().contains(&kind.name()));
+    }
+}
@@ -0,0 +1,112 @@
+//! Statistics of the validation of a set of commits, to track the adoption of the conventions.
+
+use std::collections::BTreeMap;
+
+use options::ValidationOptions;
+use {check_commit_message, validate_commit_message_report_with, CommitType};
+
+/// Counts of the valid and invalid messages of a set of commits
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ValidationStats {
+    /// Number of valid messages
+    pub passed: usize,
+    /// Number of invalid messages
+    pub failed: usize,
+    /// Number of invalid messages reporting each kind of error, by name like `SubjectTooLong`
+    ///
+    /// A message reporting the same kind of error several times is counted once.
+    pub failures: BTreeMap<&'static str, usize>,
+    /// Number of valid messages of each commit type
+    ///
+    /// The ignored messages, like the merge messages, have no type.
+    pub types: BTreeMap<CommitType, usize>,
+}
+
+impl ValidationStats {
+    /// Create empty statistics.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validate the message and count it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use validate_commit::{ValidationOptions, ValidationStats};
+    /// let options = ValidationOptions::default();
+    /// let mut stats = ValidationStats::new();
+    /// stats.add("feat: add colors", &options);
+    /// stats.add("Fix the colors", &options);
+    /// assert_eq!((stats.passed, stats.failed), (1, 1));
+    /// assert_eq!(stats.pass_rate(), 50.0);
+    /// ```
+    pub fn add(&mut self, message: &str, options: &ValidationOptions) {
+        let report = validate_commit_message_report_with(message, options);
+        if report.is_ok() {
+            self.passed += 1;
+            if let Some(msg) = check_commit_message(message, options, &[], &mut Vec::new()) {
+                *self.types.entry(msg.header.commit_type).or_insert(0) += 1;
+            }
+            return;
+        }
+
+        self.failed += 1;
+        let mut kinds: Vec<_> = report.errors.iter().map(|e| e.kind.name()).collect();
+        kinds.sort_unstable();
+        kinds.dedup();
+        for kind in kinds {
+            *self.failures.entry(kind).or_insert(0) += 1;
+        }
+    }
+
+    /// Number of messages counted.
+    pub fn total(&self) -> usize {
+        self.passed + self.failed
+    }
+
+    /// Percentage of valid messages, 100 without messages.
+    pub fn pass_rate(&self) -> f64 {
+        if self.total() == 0 {
+            return 100.0;
+        }
+        self.passed as f64 * 100.0 / self.total() as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_messages() {
+        let options = ValidationOptions::default();
+        let mut stats = ValidationStats::new();
+        assert_eq!(stats.pass_rate(), 100.0);
+        for message in &[
+            "feat: add colors",
+            "fix(cli): handle empty input",
+            "feat: add scopes",
+            "Merge branch 'main' into feature",
+            "feat: Add colors.",
+            "Add colors",
+            "fix: Handle empty input",
+        ] {
+            stats.add(message, &options);
+        }
+
+        assert_eq!((stats.passed, stats.failed, stats.total()), (4, 3, 7));
+        let failures: Vec<_> = stats.failures.iter().map(|(&k, &n)| (k, n)).collect();
+        assert_eq!(
+            failures,
+            vec![
+                ("CapitalizedFirstLetter", 2),
+                ("NoColumn", 1),
+                ("TrailingPunctuation", 1),
+            ]
+        );
+        let types: Vec<_> = stats.types.iter().map(|(t, &n)| (t.as_str(), n)).collect();
+        assert_eq!(types, vec![("feat", 2), ("fix", 1)]);
+        assert!((stats.pass_rate() - 400.0 / 7.0).abs() < 1e-9);
+    }
+}
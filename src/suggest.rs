@@ -0,0 +1,410 @@
+//! Suggestions of how to fix the validation errors, shown below the errors.
+//!
+//! Like the messages of the errors, the suggestions are templates keyed by the name of the
+//! [`FormatErrorKind`](errors::FormatErrorKind), whose `{placeholders}` are replaced by the
+//! offending text, so that they can be translated.
+
+use errors::FormatError;
+use lang::{fill_template, Language};
+use options::{ScopePattern, ValidationOptions};
+use {CommitType, CO_AUTHOR_TOKEN, SIGN_OFF_TOKEN};
+
+/// Suggestion of how to fix an error, rendered in each language from the template of its key
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Suggestion {
+    /// Key of the template in the catalogs of suggestions
+    key: &'static str,
+    /// Values of the placeholders of the template
+    arguments: Vec<(&'static str, String)>,
+    /// Suggestion in English
+    text: String,
+}
+
+impl Suggestion {
+    fn new(key: &'static str, arguments: Vec<(&'static str, String)>) -> Suggestion {
+        let text = fill_template(english_template(key), &arguments);
+        Suggestion {
+            key,
+            arguments,
+            text,
+        }
+    }
+
+    /// Suggestion in English
+    pub(crate) fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Suggestion in the language, in English if it has no translation.
+    pub(crate) fn message(&self, language: Language) -> String {
+        match language.suggestion_template(self.key) {
+            Some(template) => fill_template(template, &self.arguments),
+            None => self.text.clone(),
+        }
+    }
+}
+
+fn english_template(key: &str) -> &'static str {
+    ENGLISH
+        .iter()
+        .find(|&&(name, _)| name == key)
+        .map(|&(_, template)| template)
+        .unwrap_or_else(|| panic!("No suggestion {}", key))
+}
+
+/// Suggest how to fix the error, quoting the offending text of the message when it helps.
+pub(crate) fn suggest(error: &FormatError, options: &ValidationOptions) -> Option<Suggestion> {
+    use errors::FormatErrorKind::*;
+
+    // Offending line, split around the offending text
+    let (before, offending, after) = match error.span() {
+        Some(span) => (
+            &span.line()[..span.pos()],
+            &span.line()[span.pos()..span.end()],
+            &span.line()[span.end()..],
+        ),
+        None => ("", "", ""),
+    };
+
+    let (key, arguments) = match &error.kind {
+        CapitalizedFirstLetter => {
+            let word = first_word(&format!("{}{}", offending, after));
+            let lowercase = lowercase_first(&word);
+            (
+                "CapitalizedFirstLetter",
+                vec![("lowercase", lowercase), ("word", word)],
+            )
+        }
+        CommitTypeNotLowercase { expected, .. } => (
+            "CommitTypeNotLowercase",
+            vec![("header", format!("{}{}{}", before, expected, after))],
+        ),
+        ConsecutiveBlankLines => ("ConsecutiveBlankLines", Vec::new()),
+        Custom { .. } => return None,
+        EmptyCommitMessage => ("EmptyCommitMessage", Vec::new()),
+        EmptyCommitSubject => ("EmptyCommitSubject", Vec::new()),
+        EmptyCommitType => ("EmptyCommitType", Vec::new()),
+        EmptyFooterValue => ("EmptyFooterValue", Vec::new()),
+        EmptyScope => ("EmptyScope", Vec::new()),
+        EmojiPrefix { emoji } => ("EmojiPrefix", vec![("emoji", emoji.clone())]),
+        ForbiddenWord { word } => ("ForbiddenWord", vec![("word", word.clone())]),
+        HeaderPatternMismatch { .. } => return None,
+        InvalidCommitType {
+            commit_type,
+            suggestion: Some(suggestion),
+        } => (
+            "InvalidCommitType.suggestion",
+            vec![
+                ("suggestion", suggestion.clone()),
+                ("commit_type", commit_type.clone()),
+            ],
+        ),
+        InvalidCommitType {
+            suggestion: None, ..
+        } => {
+            let allowed_types: Vec<&str> = match options.allowed_types {
+                Some(ref allowed_types) => allowed_types.iter().map(String::as_str).collect(),
+                None => CommitType::ALL.iter().map(CommitType::as_str).collect(),
+            };
+            (
+                "InvalidCommitType",
+                vec![("types", allowed_types.join(", "))],
+            )
+        }
+        InvalidScopeFormat => match options.scope_pattern {
+            ScopePattern::Any => return None,
+            ScopePattern::Lowercase => ("InvalidScopeFormat.lowercase", Vec::new()),
+            ScopePattern::KebabCase => ("InvalidScopeFormat.kebab-case", Vec::new()),
+        },
+        LeadingBlankLine => ("LeadingBlankLine", Vec::new()),
+        LineTooLong(max) => ("LineTooLong", vec![("max", max.to_string())]),
+        MalformedCoAuthor => (
+            "MalformedCoAuthor",
+            vec![("token", CO_AUTHOR_TOKEN.to_owned())],
+        ),
+        MalformedSignOff => (
+            "MalformedSignOff",
+            vec![("token", SIGN_OFF_TOKEN.to_owned())],
+        ),
+        MisplacedBreakingChangeMarker => ("MisplacedBreakingChangeMarker", Vec::new()),
+        MissingBody { .. } => ("MissingBody", Vec::new()),
+        MissingIssueReference { .. } => ("MissingIssueReference", Vec::new()),
+        MissingParenthesis => ("MissingParenthesis", Vec::new()),
+        MissingScope => ("MissingScope", vec![("commit_type", before.to_owned())]),
+        MissingSignOff => ("MissingSignOff", Vec::new()),
+        MissingWhitespace => (
+            "MissingWhitespace",
+            vec![("header", format!("{} {}{}", before, offending, after))],
+        ),
+        MisplacedWhitespace => {
+            if offending.trim().is_empty() && !offending.is_empty() {
+                (
+                    "MisplacedWhitespace.header",
+                    vec![("header", format!("{}{}", before, after))],
+                )
+            } else {
+                ("MisplacedWhitespace", Vec::new())
+            }
+        }
+        NoColumn => ("NoColumn", Vec::new()),
+        NonCanonicalType {
+            commit_type,
+            canonical,
+        } => (
+            "NonCanonicalType",
+            vec![
+                ("canonical", canonical.clone()),
+                ("commit_type", commit_type.clone()),
+            ],
+        ),
+        NonEmptySecondLine => ("NonEmptySecondLine", Vec::new()),
+        NonImperativeSubject {
+            word,
+            suggestion: Some(suggestion),
+        } => (
+            "NonImperativeSubject.suggestion",
+            vec![("suggestion", suggestion.clone()), ("word", word.clone())],
+        ),
+        NonImperativeSubject {
+            suggestion: None, ..
+        } => ("NonImperativeSubject", Vec::new()),
+        ScopeNotAllowed => ("ScopeNotAllowed", vec![("scope", offending.to_owned())]),
+        SubjectEchoesType => ("SubjectEchoesType", vec![("word", offending.to_owned())]),
+        SubjectTooLong(max) => ("SubjectTooLong", vec![("max", max.to_string())]),
+        TrailingPunctuation(c) => ("TrailingPunctuation", vec![("char", c.to_string())]),
+        TrailingWhitespace => ("TrailingWhitespace", Vec::new()),
+        UnknownScope { .. } => match options.allowed_scopes {
+            Some(ref allowed_scopes) => {
+                ("UnknownScope", vec![("scopes", allowed_scopes.join(", "))])
+            }
+            None => return None,
+        },
+    };
+    Some(Suggestion::new(key, arguments))
+}
+
+/// English suggestions, the reference of the translations
+pub(crate) static ENGLISH: [(&str, &str); 38] = [
+    (
+        "CapitalizedFirstLetter",
+        "write '{lowercase}' instead of '{word}'",
+    ),
+    ("CommitTypeNotLowercase", "write '{header}'"),
+    ("ConsecutiveBlankLines", "remove the extra blank line"),
+    (
+        "EmptyCommitMessage",
+        "write a header like 'feat: add a feature'",
+    ),
+    ("EmptyCommitSubject", "describe the change after the colon"),
+    (
+        "EmptyCommitType",
+        "add a commit type before the colon, like 'feat'",
+    ),
+    ("EmptyFooterValue", "add a value after the footer token"),
+    ("EmptyScope", "remove the empty scope"),
+    (
+        "EmojiPrefix",
+        "remove '{emoji}', or set 'allow_emoji_prefix = true' in the configuration",
+    ),
+    ("ForbiddenWord", "remove '{word}'"),
+    (
+        "InvalidCommitType.suggestion",
+        "write '{suggestion}' instead of '{commit_type}'",
+    ),
+    ("InvalidCommitType", "use one of the types {types}"),
+    (
+        "InvalidScopeFormat.lowercase",
+        "write the scope in lowercase",
+    ),
+    (
+        "InvalidScopeFormat.kebab-case",
+        "write the scope in lowercase words separated by '-', like 'error-handling'",
+    ),
+    (
+        "LeadingBlankLine",
+        "remove the blank lines before the header",
+    ),
+    ("LineTooLong", "wrap the line at {max} characters"),
+    (
+        "MalformedCoAuthor",
+        "write '{token}: Name <name@example.com>'",
+    ),
+    (
+        "MalformedSignOff",
+        "write '{token}: Name <name@example.com>'",
+    ),
+    (
+        "MisplacedBreakingChangeMarker",
+        "move the '!' right before the colon",
+    ),
+    (
+        "MissingBody",
+        "explain the change in a body, after a blank line",
+    ),
+    (
+        "MissingIssueReference",
+        "reference the issue in the message, like 'Closes #123'",
+    ),
+    ("MissingParenthesis", "close the parenthesis of the scope"),
+    (
+        "MissingScope",
+        "add a scope before the colon, like '{commit_type}(scope):'",
+    ),
+    (
+        "MissingSignOff",
+        "sign off the commit with 'git commit --signoff'",
+    ),
+    (
+        "MissingWhitespace",
+        "add a space after the colon: '{header}'",
+    ),
+    (
+        "MisplacedWhitespace.header",
+        "remove the whitespace: '{header}'",
+    ),
+    ("MisplacedWhitespace", "remove the whitespace"),
+    (
+        "NoColumn",
+        "write the header like 'type: subject', like 'feat: add a feature'",
+    ),
+    (
+        "NonCanonicalType",
+        "write '{canonical}' instead of '{commit_type}'",
+    ),
+    (
+        "NonEmptySecondLine",
+        "add a blank line between the header and the body",
+    ),
+    (
+        "NonImperativeSubject.suggestion",
+        "write '{suggestion}' instead of '{word}'",
+    ),
+    (
+        "NonImperativeSubject",
+        "describe what the commit does, like 'add' or 'fix'",
+    ),
+    ("ScopeNotAllowed", "remove '{scope}'"),
+    (
+        "SubjectEchoesType",
+        "remove '{word}' from the start of the subject",
+    ),
+    ("SubjectTooLong", "shorten the subject to {max} characters"),
+    ("TrailingPunctuation", "remove the trailing '{char}'"),
+    ("TrailingWhitespace", "remove the trailing whitespace"),
+    ("UnknownScope", "use one of the scopes {scopes}"),
+];
+
+fn first_word(text: &str) -> String {
+    text.split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_owned()
+}
+
+fn lowercase_first(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use errors::FormatErrorKind;
+    use validate_commit_message_report_with;
+
+    fn suggestions(message: &str, options: &ValidationOptions) -> Vec<(FormatErrorKind, String)> {
+        let report = validate_commit_message_report_with(message, options);
+        report
+            .errors
+            .iter()
+            .chain(&report.warnings)
+            .map(|e| {
+                (
+                    e.kind.clone(),
+                    e.suggestion().unwrap_or_default().to_owned(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn suggest_fixes_of_headers() {
+        let options = ValidationOptions::default();
+        assert_eq!(
+            suggestions("feat:add colors", &options),
+            vec![(
+                FormatErrorKind::MissingWhitespace,
+                "add a space after the colon: 'feat: add colors'".to_owned()
+            )]
+        );
+        assert_eq!(
+            suggestions("feat: Add colors", &options),
+            vec![(
+                FormatErrorKind::CapitalizedFirstLetter,
+                "write 'add' instead of 'Add'".to_owned()
+            )]
+        );
+        assert_eq!(
+            suggestions("feet: add colors", &options)[0].1,
+            "write 'feat' instead of 'feet'"
+        );
+        assert_eq!(
+            suggestions("colors: add colors", &options)[0].1,
+            "use one of the types feat, fix, docs, style, refactor, perf, test, chore, build, \
+             ci, revert"
+        );
+        assert_eq!(
+            suggestions("Fix(cli): handle colors", &options)[0].1,
+            "write 'fix(cli): handle colors'"
+        );
+        assert_eq!(
+            suggestions("fix:  handle colors", &options)[0].1,
+            "remove the whitespace: 'fix: handle colors'"
+        );
+        assert_eq!(
+            suggestions("feat: added colors", &options)[0].1,
+            "write 'add' instead of 'added'"
+        );
+    }
+
+    #[test]
+    fn suggest_configured_values() {
+        let options = ValidationOptions {
+            allowed_types: Some(vec!["feature".to_owned(), "bugfix".to_owned()]),
+            allowed_scopes: Some(vec!["cli".to_owned(), "lib".to_owned()]),
+            ..ValidationOptions::default()
+        };
+        assert_eq!(
+            suggestions("chore: add colors", &options)[0].1,
+            "use one of the types feature, bugfix"
+        );
+        assert_eq!(
+            suggestions("feature(ui): add colors", &options)[0].1,
+            "use one of the scopes cli, lib"
+        );
+    }
+
+    #[test]
+    fn suggest_fixes_of_bodies() {
+        let options = ValidationOptions {
+            require_signoff: true,
+            ..ValidationOptions::default()
+        };
+        assert_eq!(
+            suggestions("feat: add colors\nBody", &options),
+            vec![
+                (
+                    FormatErrorKind::MissingSignOff,
+                    "sign off the commit with 'git commit --signoff'".to_owned()
+                ),
+                (
+                    FormatErrorKind::NonEmptySecondLine,
+                    "add a blank line between the header and the body".to_owned()
+                ),
+            ]
+        );
+    }
+}
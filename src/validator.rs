@@ -0,0 +1,250 @@
+//! Validation of commit messages with custom rules.
+
+use std::path::Path;
+
+use errors::{CommitValidationError, ConfigError, FormatError};
+use options::ValidationOptions;
+use {
+    check_with_rules, read_commit_file, split_by_severity, with_suggestions, CommitMsg,
+    ValidationReport,
+};
+
+/// Rule checking the parsed commit messages
+///
+/// The rules are only run on the messages which can be parsed, after the built-in rules. Their
+/// errors are reported under [`RuleId::Custom`](::RuleId::Custom), usually with the
+/// [`FormatErrorKind::Custom`](::FormatErrorKind::Custom) kind.
+pub trait Rule {
+    /// Check the message, parsed from the `raw` message.
+    ///
+    /// The parts of the parsed message are slices of `raw`, so that the errors can be located in
+    /// it.
+    fn check(&self, msg: &CommitMsg, raw: &str) -> Vec<FormatError>;
+}
+
+/// Validator of commit messages, with the built-in rules and custom rules
+///
+/// # Examples
+///
+/// Forbidding temporary commits:
+/// ```
+/// # use validate_commit::{CommitMsg, FormatError, FormatErrorKind, Rule, Validator};
+/// struct NoTemp;
+///
+/// impl Rule for NoTemp {
+///     fn check(&self, msg: &CommitMsg, _raw: &str) -> Vec<FormatError> {
+///         if msg.header.subject.contains("temp") {
+///             let kind = FormatErrorKind::Custom {
+///                 rule: "no-temp".to_owned(),
+///                 message: "Temporary commits are not allowed".to_owned(),
+///             };
+///             vec![kind.into()]
+///         } else {
+///             Vec::new()
+///         }
+///     }
+/// }
+///
+/// let validator = Validator::new().with_rule(NoTemp);
+/// assert!(validator.validate("feat: add rules").unwrap().is_ok());
+/// assert!(!validator.validate("feat: add temp rules").unwrap().is_ok());
+/// ```
+#[derive(Default)]
+pub struct Validator {
+    options: ValidationOptions,
+    rules: Vec<Box<dyn Rule>>,
+}
+
+impl Validator {
+    /// Create a validator with the default options and only the built-in rules.
+    pub fn new() -> Self {
+        Validator::default()
+    }
+
+    /// Set the options of the validation.
+    pub fn with_options(mut self, options: ValidationOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Add a rule, run after the built-in rules and the rules added before it.
+    pub fn with_rule<R: Rule + 'static>(mut self, rule: R) -> Self {
+        self.rules.push(Box::new(rule));
+        self
+    }
+
+    /// Return the options of the validation.
+    pub fn options(&self) -> &ValidationOptions {
+        &self.options
+    }
+
+    /// Validate a commit message, separating the errors from the warnings.
+    ///
+    /// The [plugins](::plugin) of the options are run after the rules, on the messages which are
+    /// not ignored. Without custom rules and plugins, this is the same as
+    /// [`validate_commit_message_report_with`](::validate_commit_message_report_with).
+    ///
+    /// Return a configuration error if a plugin cannot be run or times out.
+    pub fn validate(&self, input: &str) -> Result<ValidationReport, ConfigError> {
+        #[cfg_attr(not(feature = "plugin"), allow(unused_mut))]
+        let mut errors = match check_with_rules(input, &self.options, &self.rules) {
+            Some(errors) => errors,
+            None => return Ok(ValidationReport::default()),
+        };
+        #[cfg(feature = "plugin")]
+        {
+            for plugin in &self.options.plugins {
+                errors.extend(plugin.check(input)?);
+            }
+            errors.sort_by_key(FormatError::sort_key);
+        }
+        let errors = with_suggestions(errors, &self.options);
+        Ok(split_by_severity(errors, &self.options))
+    }
+
+    /// Read and validate a commit file.
+    pub fn validate_file<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<ValidationReport, CommitValidationError> {
+        let message = read_commit_file(path)?;
+        Ok(self.validate(&message)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use errors::FormatErrorKind;
+    use rules::{RuleId, Severity};
+    use validate_commit_message_report_with;
+
+    /// Rule forbidding a word in the subject
+    struct ForbiddenWord(&'static str);
+
+    impl Rule for ForbiddenWord {
+        fn check(&self, msg: &CommitMsg, raw: &str) -> Vec<FormatError> {
+            let subject = msg.header.subject;
+            match subject.find(self.0) {
+                Some(pos) => {
+                    let pos = subject.as_ptr() as usize - raw.as_ptr() as usize + pos;
+                    let kind = FormatErrorKind::Custom {
+                        rule: "forbidden-word".to_owned(),
+                        message: format!("Forbidden word '{}'", self.0),
+                    };
+                    vec![kind.spanning(raw, 1, pos, pos + self.0.len())]
+                }
+                None => Vec::new(),
+            }
+        }
+    }
+
+    fn messages() -> Vec<&'static str> {
+        let mut messages: Vec<&str> = RuleId::ALL
+            .iter()
+            .flat_map(|rule| {
+                let explanation = rule.explanation();
+                explanation.valid.iter().chain(explanation.invalid)
+            })
+            .cloned()
+            .collect();
+        messages.extend_from_slice(&[
+            "",
+            "\n\n",
+            "Revert \"feat: Add reverts.\"\n\nThis reverts commit abc.",
+            "fix: Fix the crash.  \n\nsome body\n\n\n\nSigned-off-by: nobody",
+            "feat(lib): add a very long subject which goes on and on, until it is too long to read",
+        ]);
+        messages
+    }
+
+    #[test]
+    fn validate_like_the_functions_without_custom_rules() {
+        let strict = ValidationOptions {
+            require_signoff: true,
+            strict_reverts: true,
+            soft_subject_limit: Some(20),
+            require_reference: vec!["fix".parse().unwrap()],
+            ..ValidationOptions::default()
+        };
+        for options in &[ValidationOptions::default(), strict] {
+            let validator = Validator::new().with_options(options.clone());
+            for message in messages() {
+                assert_eq!(
+                    validator.validate(message).unwrap(),
+                    validate_commit_message_report_with(message, options),
+                    "{:?}",
+                    message
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn run_custom_rules() {
+        let validator = Validator::new().with_rule(ForbiddenWord("temp"));
+        assert!(validator.validate("feat: add rules").unwrap().is_ok());
+
+        let report = validator.validate("feat: Add temp rules").unwrap();
+        let kinds: Vec<_> = report.errors.iter().map(|e| e.kind.clone()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                FormatErrorKind::CapitalizedFirstLetter,
+                FormatErrorKind::Custom {
+                    rule: "forbidden-word".to_owned(),
+                    message: "Forbidden word 'temp'".to_owned(),
+                },
+            ]
+        );
+        assert_eq!(report.errors[1].rule(), RuleId::Custom);
+        assert_eq!(
+            report.errors[1].to_string(),
+            "Forbidden word 'temp' [VC032 custom]\nline 1, column 11:\nfeat: Add temp rules\n          ^^^^"
+        );
+
+        // The custom rules are not run on the messages which cannot be parsed
+        let report = validator.validate("temp rules").unwrap();
+        assert!(!report.is_ok());
+        assert!(report.errors.iter().all(|e| e.rule() != RuleId::Custom));
+    }
+
+    #[test]
+    fn demote_custom_rules() {
+        let mut options = ValidationOptions::default();
+        options.severities.insert(RuleId::Custom, Severity::Warning);
+        let validator = Validator::new()
+            .with_options(options)
+            .with_rule(ForbiddenWord("temp"));
+        let report = validator.validate("feat: add temp rules").unwrap();
+        assert!(report.is_ok());
+        assert_eq!(report.warnings.len(), 1);
+    }
+
+    #[test]
+    #[cfg(all(unix, feature = "plugin"))]
+    fn run_plugins() {
+        use plugin::Plugin;
+
+        let mut options = ValidationOptions::default();
+        options
+            .plugins
+            .push(Plugin::new("sh").with_args(vec!["-c", "echo 'Rejected'; exit 1"]));
+        let validator = Validator::new().with_options(options);
+        let report = validator.validate("feat: Add plugins").unwrap();
+        let messages: Vec<_> = report.errors.iter().map(|e| e.kind.to_string()).collect();
+        assert_eq!(
+            messages,
+            vec!["Rejected", "First letter must not be capitalized"]
+        );
+
+        // The ignored messages are not given to the plugins
+        assert!(validator.validate("WIP: add plugins").unwrap().is_ok());
+
+        let validator = Validator::new().with_options(ValidationOptions {
+            plugins: vec![Plugin::new("./missing-plugin")],
+            ..ValidationOptions::default()
+        });
+        assert!(validator.validate("feat: add plugins").is_err());
+    }
+}
@@ -0,0 +1,88 @@
+//! Bindings for JavaScript, to validate the messages in a browser or with Node.js.
+//!
+//! Build them with the `wasm` feature, then generate the JavaScript module with
+//! [`wasm-bindgen`](https://rustwasm.github.io/wasm-bindgen/reference/cli.html):
+//!
+//! ```sh
+//! cargo rustc --release --lib --crate-type cdylib --target wasm32-unknown-unknown --features wasm
+//! wasm-bindgen --target nodejs --out-dir pkg target/wasm32-unknown-unknown/release/validate_commit.wasm
+//! ```
+//!
+//! ```js
+//! const { validate } = require("./pkg/validate_commit.js");
+//!
+//! const report = validate("feat: Add colors", JSON.stringify({ max_header_length: 72 }));
+//! // { ok: false, errors: [{ kind: "CapitalizedFirstLetter", line: 1, column: 7, ... }], ... }
+//! ```
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use errors::FormatError;
+use options::ValidationOptions;
+use validate_commit_message_report_with;
+
+#[wasm_bindgen]
+extern "C" {
+    /// `JSON.parse`, turning the report into a JavaScript object
+    #[wasm_bindgen(js_namespace = JSON, js_name = parse)]
+    fn parse_json(text: &str) -> JsValue;
+}
+
+/// Report returned to JavaScript, whose diagnostics have the kind, message, line, column and
+/// suggestion of the errors
+#[derive(Serialize)]
+struct Report<'a> {
+    ok: bool,
+    errors: &'a [FormatError],
+    warnings: &'a [FormatError],
+}
+
+/// Validate the message with the options, written as JSON like the configuration file, or empty
+/// for the default options.
+///
+/// Return an object with `ok`, `errors` and `warnings` properties, and throw if the options are
+/// invalid.
+#[wasm_bindgen]
+pub fn validate(message: &str, options_json: &str) -> Result<JsValue, JsValue> {
+    let report = validate_to_json(message, options_json).map_err(|e| JsValue::from_str(&e))?;
+    Ok(parse_json(&report))
+}
+
+/// Validate the message like [`validate`], returning the report as JSON.
+fn validate_to_json(message: &str, options_json: &str) -> Result<String, String> {
+    let options: ValidationOptions = if options_json.trim().is_empty() {
+        ValidationOptions::default()
+    } else {
+        serde_json::from_str(options_json).map_err(|e| format!("Invalid options: {}", e))?
+    };
+    let report = validate_commit_message_report_with(message, &options);
+    let report = Report {
+        ok: report.is_ok(),
+        errors: &report.errors,
+        warnings: &report.warnings,
+    };
+    Ok(serde_json::to_string(&report).expect("The report can be serialized"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_to_json_report() {
+        assert_eq!(
+            validate_to_json("feat: add bindings", "").unwrap(),
+            r#"{"ok":true,"errors":[],"warnings":[]}"#
+        );
+        assert_eq!(
+            validate_to_json("feat: add bindings", r#"{"max_header_length": 10}"#).unwrap(),
+            r#"{"ok":false,"errors":[{"kind":"LineTooLong","code":"VC005","message":"Line must not be longer than 10 characters","line":1,"column":11,"suggestion":"wrap the line at 10 characters"}],"warnings":[]}"#
+        );
+        assert!(
+            validate_to_json("feat: add bindings", r#"{"max_length": 10}"#)
+                .unwrap_err()
+                .starts_with("Invalid options: unknown field `max_length`")
+        );
+    }
+}
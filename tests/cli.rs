@@ -0,0 +1,436 @@
+//! Exit statuses of the validate-commit binary.
+
+#![cfg(feature = "cli")]
+
+#[cfg(feature = "git")]
+extern crate git2;
+#[cfg(feature = "git")]
+extern crate serde_json;
+
+use std::process::{Command, Output, Stdio};
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_validate-commit"))
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .unwrap()
+}
+
+fn status(args: &[&str]) -> Option<i32> {
+    run(args).status.code()
+}
+
+#[test]
+fn exit_with_0_on_valid_messages() {
+    assert_eq!(status(&["--message", "feat: add exit statuses"]), Some(0));
+    assert_eq!(
+        status(&["tests/fixtures/messages/revert-commit.txt"]),
+        Some(0)
+    );
+}
+
+#[test]
+fn exit_with_1_on_invalid_messages() {
+    assert_eq!(status(&["--message", "feat: Add exit statuses"]), Some(1));
+    assert_eq!(
+        status(&["--message", "feat: add exit statuses", "--message", "Bad"]),
+        Some(1)
+    );
+}
+
+#[test]
+fn translate_diagnostics() {
+    let stderr = |output: Output| String::from_utf8_lossy(&output.stderr).into_owned();
+    let french =
+        "error: Le sujet ne doit pas se terminer par '.' [VC020 subject-no-trailing-punctuation]";
+    let output = run(&["--lang", "fr", "--message", "feat: add translations."]);
+    let text = stderr(output);
+    assert!(text.starts_with(french), "{}", text);
+    assert!(text.contains("help: supprimez le '.' final"), "{}", text);
+    let output = run(&[
+        "--lang",
+        "fr",
+        "--format",
+        "github",
+        "--message",
+        "feat: add translations.",
+    ]);
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "::error::Le sujet ne doit pas se terminer par '.'\n"
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_validate-commit"))
+        .args(["--message", "feat: add translations."])
+        .env_remove("LC_ALL")
+        .env_remove("LC_MESSAGES")
+        .env("LANG", "fr_FR.UTF-8")
+        .output()
+        .unwrap();
+    assert!(stderr(output).starts_with(french));
+
+    // The JSON output, with its suggestions, stays in English
+    let output = run(&[
+        "--lang",
+        "fr",
+        "--format",
+        "json",
+        "--message",
+        "feat: add it.",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(r#""message": "Subject must not end with '.'""#),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains(r#""suggestion": "remove the trailing '.'""#),
+        "{}",
+        stdout
+    );
+    assert_eq!(
+        status(&["--lang", "de", "--message", "feat: add it"]),
+        Some(2)
+    );
+}
+
+#[test]
+fn report_without_failing_in_dry_run() {
+    let output = run(&["--dry-run", "--message", "feat: Add exit statuses"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("[VC001 subject-not-capitalized]"));
+
+    assert_eq!(
+        status(&["--dry-run", "tests/fixtures/messages/missing.txt"]),
+        Some(2)
+    );
+}
+
+#[test]
+fn fix_commit_files() {
+    let path = std::env::temp_dir().join(format!("validate-commit-fix-{}", std::process::id()));
+    std::fs::write(&path, "feat:Add fixes  \nBody\n").unwrap();
+    let output = run(&["--fix", path.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "feat: add fixes\n\nBody\n"
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("line 1: fixed"));
+
+    // The remaining errors are reported
+    std::fs::write(&path, "feet: Add fixes\n").unwrap();
+    assert_eq!(status(&["--fix", path.to_str().unwrap()]), Some(1));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "feet: Add fixes\n");
+
+    assert_eq!(status(&["--fix", "--message", "feat: Add fixes"]), Some(2));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn validate_pull_request_titles() {
+    let title = format!("feat(cli): add {} (#1234)", "x".repeat(85));
+    assert_eq!(status(&["--pr-title", "--message", &title]), Some(0));
+    assert_eq!(status(&["--message", &title]), Some(1));
+}
+
+#[test]
+fn print_parsed_messages() {
+    let message = "feat(parser)!: print the parsed messages
+
+Show the structure of the messages,
+to debug the rules.
+
+Closes: #42
+Signed-off-by: Jane Doe <jane@example.com>";
+    let output = run(&["--print", "--message", message]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "type: feat
+scope: parser
+breaking: true
+subject: print the parsed messages
+body:
+    Show the structure of the messages,
+    to debug the rules.
+footers:
+    Closes: #42
+    Signed-off-by: Jane Doe <jane@example.com>
+"
+    );
+
+    let output = run(&["--format", "json", "--print", "--message", message]);
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(r#""subject": "print the parsed messages""#));
+    assert!(
+        stdout.contains(r#"{"token": "Signed-off-by", "value": "Jane Doe <jane@example.com>"}"#)
+    );
+
+    // The errors are reported as usual, without the structure
+    let output = run(&["--print", "--message", "feat: Print the parsed messages"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    assert_eq!(
+        status(&["--print", "--format", "github", "--message", message]),
+        Some(2)
+    );
+}
+
+#[test]
+fn override_options_with_environment_variables() {
+    let run_with = |name: &str, value: &str, args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_validate-commit"))
+            .args(args)
+            .env(name, value)
+            .stdin(Stdio::null())
+            .output()
+            .unwrap()
+    };
+    let header = "feat: add environment variables";
+    let output = run_with(
+        "VALIDATE_COMMIT_MAX_LINE_LENGTH",
+        "20",
+        &["--message", header],
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let args = ["--max-header-length", "72", "--message", header];
+    let output = run_with("VALIDATE_COMMIT_MAX_LINE_LENGTH", "20", &args);
+    assert_eq!(output.status.code(), Some(0));
+
+    let output = run_with(
+        "VALIDATE_COMMIT_MAX_LINE_LENGTH",
+        "short",
+        &["--message", header],
+    );
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Invalid environment variable VALIDATE_COMMIT_MAX_LINE_LENGTH"));
+    let output = run_with("VALIDATE_COMMIT_COLOR", "sometimes", &["--message", header]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+#[cfg(feature = "config")]
+fn write_default_config_file() {
+    let dir = std::env::temp_dir().join(format!("validate-commit-init-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join(".git")).unwrap();
+    let run_in = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_validate-commit"))
+            .args(args)
+            .current_dir(&dir)
+            .stdin(Stdio::null())
+            .output()
+            .unwrap()
+    };
+
+    assert_eq!(run_in(&["init"]).status.code(), Some(0));
+    let path = dir.join(".validate-commit.toml");
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert!(text.contains("max_header_length = 100\n"));
+    let output = run_in(&["init"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("use --force"));
+
+    std::fs::write(&path, "max_header_length = 50\n").unwrap();
+    let output = run_in(&["--max-body-line-length", "60", "config", "--show"]);
+    assert_eq!(output.status.code(), Some(0));
+    let shown = String::from_utf8_lossy(&output.stdout);
+    assert!(shown.contains("max_header_length = 50\n"));
+    assert!(shown.contains("max_body_line_length = 60\n"));
+    assert_eq!(run_in(&["config"]).status.code(), Some(2));
+
+    assert_eq!(run_in(&["init", "--force"]).status.code(), Some(0));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), text);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[cfg(feature = "config")]
+fn merge_user_and_repository_configs() {
+    let user_config_dir = std::env::current_dir()
+        .unwrap()
+        .join("tests/fixtures/user-config");
+    let run_in = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_validate-commit"))
+            .args(args)
+            .current_dir("tests/fixtures/layered-config")
+            .env("XDG_CONFIG_HOME", &user_config_dir)
+            .stdin(Stdio::null())
+            .output()
+            .unwrap()
+    };
+    let header = "deps: bump the regex crate to its latest version 1.10";
+    assert_eq!(run_in(&["--message", header]).status.code(), Some(0));
+    let output = run_in(&["--no-user-config", "--message", header]);
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn exit_with_2_on_missing_files() {
+    let output = run(&["tests/fixtures/messages/missing.txt"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("missing.txt"));
+
+    // A missing file among several ones is not only an invalid message
+    assert_eq!(
+        status(&[
+            "tests/fixtures/messages/revert-commit.txt",
+            "tests/fixtures/messages/missing.txt",
+        ]),
+        Some(2)
+    );
+}
+
+#[test]
+fn exit_with_2_on_usage_errors() {
+    assert_eq!(status(&["--unknown"]), Some(2));
+    assert_eq!(
+        status(&["--message", "feat: add", "COMMIT_EDITMSG"]),
+        Some(2)
+    );
+}
+
+#[test]
+#[cfg(all(unix, feature = "plugin"))]
+fn run_plugins_of_the_config_file() {
+    let run_in = |dir: &str, message: &str| {
+        Command::new(env!("CARGO_BIN_EXE_validate-commit"))
+            .args(["--message", message])
+            .current_dir(dir)
+            .output()
+            .unwrap()
+    };
+
+    let output = run_in("tests/fixtures/plugin-config", "feat: add plugins");
+    assert_eq!(output.status.code(), Some(0));
+    let output = run_in("tests/fixtures/plugin-config", "feat: add temp plugins");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Temporary commits are not allowed"));
+
+    // A plugin which cannot be run is a configuration error
+    let output = run_in("tests/fixtures/missing-plugin-config", "feat: add plugins");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("command not found"));
+}
+
+#[test]
+#[cfg(feature = "git")]
+fn report_only_new_failures_of_baselined_commits() {
+    use git2::{Oid, Repository, Signature};
+
+    let dir = std::env::temp_dir().join(format!("validate-commit-baseline-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let repo = Repository::init(&dir).unwrap();
+    let commit = |message: &str| -> Oid {
+        let signature = Signature::now("Test", "test@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.treebuilder(None).unwrap().write().unwrap())
+            .unwrap();
+        let parent = repo.head().ok().map(|head| head.peel_to_commit().unwrap());
+        let parents: Vec<_> = parent.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap()
+    };
+    let run_in = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_validate-commit"))
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .unwrap()
+    };
+
+    let root = commit("Add the project").to_string();
+    let old = commit("feat: Add colors").to_string();
+    let output = run_in(&["baseline", "--write", "baseline.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("2 of 2 commits recorded"));
+    assert_eq!(
+        run_in(&["head", "--baseline", "baseline.txt"])
+            .status
+            .code(),
+        Some(0)
+    );
+
+    // Only the new commit is reported, the old one only being baselined for its known error
+    let new = commit("fix: Handle empty input").to_string();
+    let range = format!("{}..HEAD", root);
+    let output = run_in(&["range", &range, "--baseline", "baseline.txt"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains(&new[..7]), "{}", stderr);
+    assert!(!stderr.contains(&old[..7]), "{}", stderr);
+    assert!(stderr.contains("1 of 2 commits invalid"), "{}", stderr);
+
+    let output = run_in(&[
+        "range",
+        &range,
+        "--baseline",
+        "baseline.txt",
+        "--max-line-length",
+        "10",
+    ]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("2 of 2 commits invalid"), "{}", stderr);
+
+    let output = run_in(&[
+        "range",
+        &range,
+        "--baseline",
+        "baseline.txt",
+        "--format",
+        "junit",
+    ]);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(r#"tests="2" failures="1" errors="0" skipped="0""#),
+        "{}",
+        stdout
+    );
+    let testcase = format!(r#"<testcase name="{} fix: Handle empty input""#, &new[..7]);
+    assert!(stdout.contains(&testcase), "{}", stdout);
+    assert_eq!(
+        run_in(&["head", "--format", "junit"]).status.code(),
+        Some(2)
+    );
+
+    // The reports of the commits are written in the other formats as well
+    let output = run_in(&["range", &range, "--format", "json"]);
+    assert_eq!(output.status.code(), Some(1));
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["ok"], false);
+    assert_eq!(report["commits"][1]["commit"], new.as_str());
+    assert_eq!(report["commits"][1]["ok"], false);
+    let output = run_in(&["range", &range, "--format", "github"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("::error::"), "{}", stdout);
+    assert!(output.stderr.starts_with(b"2 of 2 commits invalid"));
+    for args in [
+        &["range", &range, "--format", "markdown"][..],
+        &["bump", &range, "--format", "json"],
+    ] {
+        assert_eq!(run_in(args).status.code(), Some(2), "{:?}", args);
+    }
+
+    assert_eq!(run_in(&["range", &range]).status.code(), Some(1));
+    assert_eq!(run_in(&["baseline"]).status.code(), Some(2));
+    assert_eq!(
+        run_in(&["--message", "feat: add", "--baseline", "baseline.txt"])
+            .status
+            .code(),
+        Some(2)
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
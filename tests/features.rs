@@ -0,0 +1,137 @@
+//! Checks of the optional features, each test running with the features it needs.
+//!
+//! Like a CI matrix, run them with no feature, with each feature alone and with all of them:
+//!
+//! ```sh
+//! cargo test --test features
+//! for feature in baseline cli commitlint-compat config ffi git hook parallel plugin regex serde wasm; do
+//!     cargo test --features $feature --test features
+//! done
+//! cargo test --all-features --test features
+//! ```
+
+#[cfg(feature = "serde")]
+extern crate serde_json;
+extern crate validate_commit;
+
+use validate_commit::{
+    validate_commit_message_report_with, validate_commit_message_with, CommitType, FormatErrorKind,
+    ValidationOptions,
+};
+
+#[test]
+fn validate_without_features() {
+    let options = ValidationOptions {
+        require_reference: vec![CommitType::Fix],
+        forbidden_words: vec!["do not merge".to_owned()],
+        ..ValidationOptions::default()
+    };
+    assert!(validate_commit_message_with("fix: handle empty input (#42)", &options).is_ok());
+    assert!(validate_commit_message_with("fix: handle empty input", &options).is_err());
+
+    let report = validate_commit_message_report_with("feat: add colors, DO NOT MERGE", &options);
+    let kinds: Vec<_> = report.errors.into_iter().map(|e| e.kind).collect();
+    let word = "DO NOT MERGE".to_owned();
+    assert_eq!(kinds, vec![FormatErrorKind::ForbiddenWord { word }]);
+}
+
+#[test]
+#[cfg(feature = "regex")]
+fn validate_with_patterns() {
+    use validate_commit::Pattern;
+
+    let options = ValidationOptions {
+        header_pattern: Some(Pattern::new(r"^(?P<type>\w+) - (?P<subject>.+)$").unwrap()),
+        ..ValidationOptions::default()
+    };
+    assert!(validate_commit_message_with("feat - add patterns", &options).is_ok());
+    assert!(validate_commit_message_with("feat: add patterns", &options).is_err());
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn serialize_errors() {
+    let report =
+        validate_commit_message_report_with("feat: Add serde", &ValidationOptions::default());
+    let json = serde_json::to_value(&report.errors[0]).unwrap();
+    assert_eq!(json["kind"], "CapitalizedFirstLetter");
+    assert_eq!(json["code"], "VC001");
+}
+
+#[test]
+#[cfg(feature = "config")]
+fn load_config_file() {
+    use validate_commit::config::load_config;
+
+    let options = load_config("tests/fixtures/config").unwrap();
+    assert_eq!(options.max_header_length, 72);
+    assert!(validate_commit_message_with("[bot] Update", &options).is_ok());
+}
+
+#[test]
+#[cfg(all(feature = "config", not(feature = "plugin")))]
+fn reject_plugins_without_plugin_feature() {
+    use validate_commit::config::load_config;
+
+    assert!(load_config("tests/fixtures/plugin-config").is_err());
+}
+
+#[test]
+#[cfg(all(feature = "plugin", unix))]
+fn run_plugins() {
+    use validate_commit::plugin::Plugin;
+    use validate_commit::Validator;
+
+    let options = ValidationOptions {
+        plugins: vec![Plugin::new("sh").with_args(["-c", "! grep -q temp"])],
+        ..ValidationOptions::default()
+    };
+    let validator = Validator::new().with_options(options);
+    assert!(validator.validate("feat: add plugins").unwrap().is_ok());
+    let report = validator.validate("feat: add temp plugins").unwrap();
+    assert_eq!(report.errors.len(), 1);
+}
+
+#[test]
+#[cfg(feature = "commitlint-compat")]
+fn load_commitlint_config() {
+    use validate_commit::commitlint::load_commitlint_config;
+
+    let config = load_commitlint_config("tests/fixtures/commitlint-config").unwrap();
+    assert!(validate_commit_message_with("feat: add commitlint", &config.options).is_ok());
+}
+
+#[test]
+#[cfg(feature = "git")]
+fn parse_ref_updates() {
+    use validate_commit::git::RefUpdate;
+
+    let zero = "0".repeat(40);
+    let new = "a".repeat(40);
+    let updates = RefUpdate::parse_all(&format!("{} {} refs/heads/main\n", zero, new)).unwrap();
+    assert_eq!(updates.len(), 1);
+    assert_eq!(updates[0].name, "refs/heads/main");
+}
+
+#[test]
+#[cfg(feature = "parallel")]
+fn validate_in_parallel() {
+    use validate_commit::validate_commit_messages_par;
+
+    let messages = vec!["feat: add threads", "feat: Add threads"];
+    let results = validate_commit_messages_par(messages, &ValidationOptions::default());
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn run_binary() {
+    use std::process::Command;
+
+    let output = Command::new(env!("CARGO_BIN_EXE_validate-commit"))
+        .args(["--message", "feat: add a binary"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+}
@@ -0,0 +1,12 @@
+extern crate cc;
+
+fn main() {
+    // The C program testing the bindings, called by the tests
+    println!("cargo:rerun-if-changed=../../include/validate_commit.h");
+    println!("cargo:rerun-if-changed=validate.c");
+    cc::Build::new()
+        .file("validate.c")
+        .include("../../include")
+        .warnings_into_errors(true)
+        .compile("vc_ffi_test");
+}
@@ -0,0 +1 @@
+//! Tests of the C bindings of validate-commit, see the `tests` directory.
@@ -0,0 +1,16 @@
+//! Validation of messages through the C bindings.
+
+extern crate validate_commit;
+extern crate validate_commit_ffi_tests;
+
+use std::os::raw::c_int;
+
+extern "C" {
+    /// Defined in `validate.c`, returning the number of failed checks
+    fn vc_ffi_test() -> c_int;
+}
+
+#[test]
+fn validate_from_c() {
+    assert_eq!(unsafe { vc_ffi_test() }, 0);
+}
@@ -0,0 +1,35 @@
+/* Validate messages through the C bindings, returning the number of failed checks. */
+
+#include <string.h>
+
+#include "validate_commit.h"
+
+#define CHECK(condition) failures += !(condition)
+
+int vc_ffi_test(void) {
+    int failures = 0;
+
+    VcOptions options = vc_options_default();
+    CHECK(options.max_header_length == 100);
+    options.require_scope = true;
+
+    VcResult *result = vc_validate_message("feat: add colors", &options);
+    CHECK(result != NULL);
+    CHECK(vc_result_error_count(result) == 1);
+    CHECK(strcmp(vc_result_error_kind(result, 0), "MissingScope") == 0);
+    CHECK(strcmp(vc_result_error_message(result, 0), "Missing scope") == 0);
+    CHECK(vc_result_error_line(result, 0) == 1);
+    CHECK(vc_result_error_column(result, 0) == 5);
+    CHECK(vc_result_error_kind(result, 1) == NULL);
+    vc_result_free(result);
+
+    result = vc_validate_message("feat(ui): add colors", NULL);
+    CHECK(vc_result_error_count(result) == 0);
+    vc_result_free(result);
+
+    CHECK(vc_validate_message("feat: add \xff", NULL) == NULL);
+    CHECK(vc_validate_message(NULL, NULL) == NULL);
+    vc_result_free(NULL);
+
+    return failures;
+}
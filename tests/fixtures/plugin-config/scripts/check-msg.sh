@@ -0,0 +1,6 @@
+#!/bin/sh
+# Reject the temporary commits
+if grep -qiw temp; then
+    echo "1:1: Temporary commits are not allowed"
+    exit 1
+fi
@@ -0,0 +1 @@
+//! Tests of the JavaScript bindings of validate-commit, see the `tests` directory.
@@ -0,0 +1,36 @@
+//! Validation of messages through the JavaScript bindings.
+
+#![cfg(target_arch = "wasm32")]
+
+extern crate validate_commit;
+extern crate wasm_bindgen;
+extern crate wasm_bindgen_test;
+
+use validate_commit::wasm::validate;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = JSON)]
+    fn stringify(value: &JsValue) -> String;
+}
+
+#[wasm_bindgen_test]
+fn validate_valid_message() {
+    let report = validate("feat: add bindings", "").unwrap();
+    assert_eq!(
+        stringify(&report),
+        r#"{"ok":true,"errors":[],"warnings":[]}"#
+    );
+}
+
+#[wasm_bindgen_test]
+fn validate_invalid_message() {
+    let report = validate("feat: Add bindings", r#"{"max_header_length": 72}"#).unwrap();
+    let report = stringify(&report);
+    assert!(report.starts_with(r#"{"ok":false,"errors":[{"kind":"CapitalizedFirstLetter","#));
+    assert!(report.contains(r#""line":1,"column":7"#), "{}", report);
+
+    assert!(validate("feat: add bindings", "{").is_err());
+}